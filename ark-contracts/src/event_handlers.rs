@@ -4,27 +4,65 @@ use ark::total_paid_out_report::UnsavedTotalPaidOutReport;
 use ark_db::DBPool;
 
 use ark_utils::ethers::convert_wei_to_ether;
-use chaindexing::{utils::address_to_string, ContractState, EventContext, EventHandler};
+use ark_web3::chains::get_currency_chain_id;
+use ark_web3::events::{EventHandlerError, TypedEvent};
+use chaindexing::{ContractState, EventContext};
 
+use super::events::{Credit, Debit};
+use super::quarantine::FallibleEventHandler;
 use super::states::Wallet;
 
 pub struct CreditWalletEventHandler;
 
 #[async_trait::async_trait]
-impl EventHandler for CreditWalletEventHandler {
-    type SharedState = Arc<DBPool>;
+impl FallibleEventHandler for CreditWalletEventHandler {
+    fn name(&self) -> &'static str {
+        "CreditWalletEventHandler"
+    }
 
-    async fn handle_event<'a, 'b>(&self, event_context: EventContext<'a, 'b, Self::SharedState>) {
+    async fn handle_event<'a, 'b>(
+        &self,
+        event_context: EventContext<'a, 'b, Arc<DBPool>>,
+    ) -> Result<(), EventHandlerError> {
         let event = &event_context.event;
-        let event_params = event.get_params();
 
-        let owner_address =
-            address_to_string(&event_params.get("owner").unwrap().clone().into_address().unwrap())
-                .to_lowercase();
-        let credit_amount = convert_wei_to_ether(
-            &event_params.get("amount").unwrap().clone().into_uint().unwrap().to_string(),
+        let Credit {
+            owner_address,
+            amount,
+        } = Credit::from_event(event)?;
+        let credit_amount = convert_wei_to_ether(&amount);
+
+        // Everything that can fail goes before the wallet write and the report is keyed by
+        // the event, so retrying a quarantined credit doesn't count it twice
+        let pool = event_context.get_shared_state().await;
+        let mut conn = pool.get_owned().await.map_err(EventHandlerError::unavailable)?;
+
+        let currency_chain_id = get_currency_chain_id(event.chain_id);
+        let chain_currency = ark_repo::get_chain_currency(&mut conn, currency_chain_id)
+            .await
+            .map_err(EventHandlerError::unavailable)?
+            .ok_or_else(|| EventHandlerError::missing_state("ChainCurrency", currency_chain_id))?;
+        let credit_amount_usd = chain_currency.convert_to_usd(credit_amount);
+
+        let total_paid_out_report = if let Some(last_total_paid_out_report) =
+            ark_repo::get_last_total_paid_out_report(&mut conn)
+                .await
+                .map_err(EventHandlerError::unavailable)?
+        {
+            last_total_paid_out_report.derive_new(credit_amount_usd)
+        } else {
+            UnsavedTotalPaidOutReport::new(credit_amount_usd)
+        };
+        let total_paid_out_report = total_paid_out_report.for_event(
+            event.chain_id,
+            &event.transaction_hash,
+            event.log_index,
         );
 
+        ark_repo::create_total_paid_out_report(&mut conn, &total_paid_out_report)
+            .await
+            .map_err(EventHandlerError::unavailable)?;
+
         let initial_wallet = Wallet::read_one(
             [("owner_address".to_string(), owner_address.to_string())].into(),
             &event_context,
@@ -42,40 +80,29 @@ impl EventHandler for CreditWalletEventHandler {
         )
         .await;
 
-        let pool = event_context.get_shared_state().await;
-        let mut conn = pool.get_owned().await.unwrap();
-
-        let chain_currency = ark_repo::get_chain_currency(&mut conn, event.chain_id).await.unwrap();
-        let credit_amount_usd = chain_currency.convert_to_usd(credit_amount);
-
-        let total_paid_out_report = if let Some(last_total_paid_out_report) =
-            ark_repo::get_last_total_paid_out_report(&mut conn).await
-        {
-            last_total_paid_out_report.derive_new(credit_amount_usd)
-        } else {
-            UnsavedTotalPaidOutReport::new(credit_amount_usd)
-        };
-
-        ark_repo::create_total_paid_out_report(&mut conn, &total_paid_out_report).await;
+        Ok(())
     }
 }
 
 pub struct DebitWalletEventHandler;
 
 #[async_trait::async_trait]
-impl EventHandler for DebitWalletEventHandler {
-    type SharedState = Arc<DBPool>;
+impl FallibleEventHandler for DebitWalletEventHandler {
+    fn name(&self) -> &'static str {
+        "DebitWalletEventHandler"
+    }
 
-    async fn handle_event<'a, 'b>(&self, event_context: EventContext<'a, 'b, Self::SharedState>) {
+    async fn handle_event<'a, 'b>(
+        &self,
+        event_context: EventContext<'a, 'b, Arc<DBPool>>,
+    ) -> Result<(), EventHandlerError> {
         let event = &event_context.event;
-        let event_params = event.get_params();
 
-        let owner_address =
-            address_to_string(&event_params.get("owner").unwrap().clone().into_address().unwrap())
-                .to_lowercase();
-        let debit_amount = convert_wei_to_ether(
-            &event_params.get("amount").unwrap().clone().into_uint().unwrap().to_string(),
-        );
+        let Debit {
+            owner_address,
+            amount,
+        } = Debit::from_event(event)?;
+        let debit_amount = convert_wei_to_ether(&amount);
 
         let initial_wallet = Wallet::read_one(
             [("owner_address".to_string(), owner_address.to_string())].into(),
@@ -93,6 +120,8 @@ impl EventHandler for DebitWalletEventHandler {
             &event_context,
         )
         .await;
+
+        Ok(())
     }
 }
 
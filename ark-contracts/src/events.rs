@@ -0,0 +1,37 @@
+use ark_web3::events::{EventDecodeError, EventParams, TypedEvent};
+
+pub struct Credit {
+    pub owner_address: String,
+    /// In wei
+    pub amount: String,
+}
+
+impl TypedEvent for Credit {
+    const NAME: &'static str = "Credit";
+    const SIGNATURE: &'static str = "event Credit(address indexed owner, uint amount)";
+
+    fn decode(params: &EventParams) -> Result<Self, EventDecodeError> {
+        Ok(Credit {
+            owner_address: params.get_address("owner")?,
+            amount: params.get_uint_string("amount")?,
+        })
+    }
+}
+
+pub struct Debit {
+    pub owner_address: String,
+    /// In wei
+    pub amount: String,
+}
+
+impl TypedEvent for Debit {
+    const NAME: &'static str = "Debit";
+    const SIGNATURE: &'static str = "event Debit(address indexed owner, uint amount)";
+
+    fn decode(params: &EventParams) -> Result<Self, EventDecodeError> {
+        Ok(Debit {
+            owner_address: params.get_address("owner")?,
+            amount: params.get_uint_string("amount")?,
+        })
+    }
+}
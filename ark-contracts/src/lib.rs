@@ -1,3 +1,5 @@
 mod event_handlers;
+pub mod events;
+pub mod quarantine;
 mod states;
 pub mod wallets;
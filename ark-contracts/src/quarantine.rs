@@ -0,0 +1,186 @@
+use std::fmt;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+
+use ark::quarantined_events::{QuarantinedEvent, UnsavedQuarantinedEvent};
+use ark_db::DBPool;
+use ark_utils::clocks::Clock;
+use ark_utils::panics::get_panic_message;
+use ark_web3::events::EventHandlerError;
+use chaindexing::{ChaindexingRepo, Contract, Contracts, Event, EventContext, EventHandler};
+use chaindexing::{ExecutesWithRawQuery, HasRawQueryClient, Repo};
+use futures::FutureExt;
+use tokio::sync::Mutex;
+use tracing::error;
+
+/// Like chaindexing's `EventHandler` but failures are reported instead of panicking.
+/// Register with `Quarantined` so failed events get quarantined and indexing carries on.
+#[async_trait::async_trait]
+pub trait FallibleEventHandler: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    async fn handle_event<'a, 'b>(
+        &self,
+        event_context: EventContext<'a, 'b, Arc<DBPool>>,
+    ) -> Result<(), EventHandlerError>;
+}
+
+pub struct Quarantined<H: FallibleEventHandler> {
+    handler: H,
+    clock: Arc<dyn Clock>,
+}
+
+impl<H: FallibleEventHandler> Quarantined<H> {
+    pub fn new(handler: H, clock: Arc<dyn Clock>) -> Self {
+        Self { handler, clock }
+    }
+
+    /// Panics are caught too, so they get quarantined like any other failure
+    async fn try_handle_event<'a, 'b>(
+        &self,
+        event_context: EventContext<'a, 'b, Arc<DBPool>>,
+    ) -> Result<(), EventHandlerError> {
+        AssertUnwindSafe(self.handler.handle_event(event_context))
+            .catch_unwind()
+            .await
+            .unwrap_or_else(|panic| Err(EventHandlerError::Panicked(get_panic_message(&*panic))))
+    }
+}
+
+#[async_trait::async_trait]
+impl<H: FallibleEventHandler> EventHandler for Quarantined<H> {
+    type SharedState = Arc<DBPool>;
+
+    async fn handle_event<'a, 'b>(&self, event_context: EventContext<'a, 'b, Self::SharedState>) {
+        let event = event_context.event.clone();
+        let pool = event_context.get_shared_state().await;
+
+        if let Err(err) = self.try_handle_event(event_context).await {
+            let handler_name = self.handler.name();
+
+            error!(
+                "[{handler_name}]: Quarantining event in Transaction:{transaction_hash} Log:{log_index} on Chain:{chain_id}: {err}",
+                transaction_hash = event.transaction_hash,
+                log_index = event.log_index,
+                chain_id = event.chain_id,
+            );
+
+            match pool.get_owned().await {
+                Ok(mut conn) => {
+                    let quarantined_event =
+                        new_quarantined_event(&event, handler_name, &err, self.clock.now());
+
+                    if let Err(query_err) =
+                        ark_repo::create_or_update_quarantined_event(&mut conn, &quarantined_event)
+                            .await
+                    {
+                        error!("[{handler_name}]: Could not quarantine event: {query_err}");
+                    }
+                }
+                Err(conn_err) => {
+                    error!("[{handler_name}]: Could not quarantine event: {conn_err}");
+                }
+            }
+        }
+    }
+}
+
+fn new_quarantined_event(
+    event: &Event,
+    handler_name: &str,
+    err: &EventHandlerError,
+    now: i64,
+) -> UnsavedQuarantinedEvent {
+    UnsavedQuarantinedEvent {
+        event_id: event.id.to_string(),
+        chain_id: event.chain_id,
+        contract_address: event.contract_address.clone(),
+        contract_name: event.contract_name.clone(),
+        event_abi: event.abi.clone(),
+        handler_name: handler_name.to_string(),
+        transaction_hash: event.transaction_hash.clone(),
+        block_number: event.block_number,
+        log_index: event.log_index,
+        payload: serde_json::to_value(event.get_params()).unwrap_or_default(),
+        error: err.to_string(),
+        first_failed_at: now,
+        last_failed_at: now,
+    }
+}
+
+#[derive(Debug)]
+pub enum RetryError {
+    /// Pruned or removed by a reorg
+    EventNotFound,
+    UnknownEvent,
+    Unavailable(String),
+}
+
+impl fmt::Display for RetryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RetryError::EventNotFound => write!(f, "Event is no longer indexed"),
+            RetryError::UnknownEvent => write!(f, "No handler for this event"),
+            RetryError::Unavailable(reason) => write!(f, "Unavailable: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for RetryError {}
+
+/// Runs a quarantined event through its handler again, in its own transaction.
+/// It is resolved up front and re-quarantined if it fails again, so the returned
+/// event tells whether the retry went through.
+/// Events are looked up through `pool`, which chaindexing's repo can use as is,
+/// and `chaindexing_repo` only opens the handler's transaction.
+pub async fn retry(
+    quarantined_event: &QuarantinedEvent,
+    contracts: &[Contract<Arc<DBPool>>],
+    pool: Arc<DBPool>,
+    chaindexing_repo: &ChaindexingRepo,
+    clock: &dyn Clock,
+) -> Result<QuarantinedEvent, RetryError> {
+    let mut conn =
+        pool.get_owned().await.map_err(|err| RetryError::Unavailable(err.to_string()))?;
+
+    let block_number = quarantined_event.block_number as u64;
+    let event = ChaindexingRepo::get_events(
+        &mut conn,
+        quarantined_event.contract_address.clone(),
+        block_number,
+        block_number,
+    )
+    .await
+    .into_iter()
+    .find(|event| event.id.to_string() == quarantined_event.event_id)
+    .ok_or(RetryError::EventNotFound)?;
+
+    let event_handlers_by_event_abi = Contracts::get_all_event_handlers_by_event_abi(contracts);
+    let event_handler = event_handlers_by_event_abi
+        .get(event.abi.as_str())
+        .ok_or(RetryError::UnknownEvent)?;
+
+    ark_repo::resolve_quarantined_event(&mut conn, &quarantined_event.event_id, clock.now())
+        .await
+        .map_err(|err| RetryError::Unavailable(err.to_string()))?;
+
+    let mut raw_query_client = chaindexing_repo.get_raw_query_client().await;
+    let raw_query_txn_client =
+        ChaindexingRepo::get_raw_query_txn_client(&mut raw_query_client).await;
+    let shared_state = Some(Arc::new(Mutex::new(pool.clone())));
+
+    event_handler
+        .handle_event(EventContext::new(
+            event,
+            &raw_query_txn_client,
+            &shared_state,
+        ))
+        .await;
+
+    ChaindexingRepo::commit_raw_query_txns(raw_query_txn_client).await;
+
+    ark_repo::get_quarantined_event(&mut conn, quarantined_event.id)
+        .await
+        .map_err(|err| RetryError::Unavailable(err.to_string()))?
+        .ok_or(RetryError::EventNotFound)
+}
@@ -1,51 +1,37 @@
 use std::sync::Arc;
 
+use ark::configs::ArkConfig;
 use ark_db::DBPool;
+use ark_utils::clocks::Clock;
 use chaindexing::Contract;
 
-use ark_web3::chains::ChainId;
+use ark_web3::events::TypedEvent;
 
 use super::event_handlers::{CreditWalletEventHandler, DebitWalletEventHandler};
+use super::events::{Credit, Debit};
+use super::quarantine::Quarantined;
 use super::states::WalletMigrations;
 
-pub fn get() -> Contract<Arc<DBPool>> {
+pub fn get(config: &ArkConfig, clock: Arc<dyn Clock>) -> Contract<Arc<DBPool>> {
     let contract = Contract::new("Wallets")
         .add_event(
-            "event Credit(address indexed owner, uint amount)",
-            CreditWalletEventHandler,
+            Credit::SIGNATURE,
+            Quarantined::new(CreditWalletEventHandler, clock.clone()),
         )
         .add_event(
-            "event Debit(address indexed owner, uint amount)",
-            DebitWalletEventHandler,
+            Debit::SIGNATURE,
+            Quarantined::new(DebitWalletEventHandler, clock.clone()),
         )
         .add_state_migrations(WalletMigrations);
 
-    let current_environment = ark::environments::current();
-
-    if current_environment.is_local() {
-        contract.add_address(
-            &ChainId::Local.get_contract_address("WALLETS"),
-            &chaindexing::ChainId::Dev,
-            0,
-        )
-    } else if current_environment.is_production() {
-        contract
-            .add_address(
-                &ChainId::Sepolia.get_contract_address("WALLETS"),
-                &chaindexing::ChainId::Sepolia,
-                ChainId::Sepolia.get_start_block_number("WALLETS"),
-            )
-            .add_address(
-                &ChainId::Polygon.get_contract_address("WALLETS"),
-                &chaindexing::ChainId::Polygon,
-                ChainId::Polygon.get_start_block_number("WALLETS"),
-            )
-            .add_address(
-                &ChainId::Ethereum.get_contract_address("WALLETS"),
-                &chaindexing::ChainId::Mainnet,
-                ChainId::Ethereum.get_start_block_number("WALLETS"),
+    config.get_contract_deployments("wallets").into_iter().fold(
+        contract,
+        |contract, (chain_id, deployment)| {
+            contract.add_address(
+                &deployment.address,
+                &chain_id.into(),
+                deployment.start_block_number,
             )
-    } else {
-        contract
-    }
+        },
+    )
 }
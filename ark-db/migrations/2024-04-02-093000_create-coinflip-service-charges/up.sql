@@ -0,0 +1,12 @@
+-- Your SQL goes here
+
+ CREATE TABLE coinflip_service_charges (
+                id BIGSERIAL PRIMARY KEY,
+                chain_id BIGINT NOT NULL,
+                percent INTEGER NOT NULL,
+                effective_from_block_number BIGINT NOT NULL,
+                source VARCHAR NOT NULL
+            );
+
+
+CREATE UNIQUE INDEX unique_coinflip_service_charge_effective_from ON coinflip_service_charges(chain_id, effective_from_block_number);
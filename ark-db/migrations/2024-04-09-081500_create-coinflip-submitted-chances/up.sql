@@ -0,0 +1,14 @@
+-- Your SQL goes here
+
+ CREATE TABLE coinflip_submitted_chances (
+                id BIGSERIAL PRIMARY KEY,
+                game_id BIGINT NOT NULL,
+                chain_id BIGINT NOT NULL,
+                game_play_id INTEGER NOT NULL,
+                player_address VARCHAR NOT NULL,
+                encrypted_chance_and_salt VARCHAR NOT NULL,
+                submitted_at BIGINT NOT NULL
+            );
+
+
+CREATE UNIQUE INDEX unique_coinflip_submitted_chance ON coinflip_submitted_chances(game_id, chain_id, game_play_id);
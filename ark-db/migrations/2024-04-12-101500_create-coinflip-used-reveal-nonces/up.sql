@@ -0,0 +1,12 @@
+-- Your SQL goes here
+
+ CREATE TABLE coinflip_used_reveal_nonces (
+                id BIGSERIAL PRIMARY KEY,
+                chain_id BIGINT NOT NULL,
+                player_address VARCHAR NOT NULL,
+                nonce VARCHAR NOT NULL,
+                used_at BIGINT NOT NULL
+            );
+
+
+CREATE UNIQUE INDEX unique_coinflip_used_reveal_nonce ON coinflip_used_reveal_nonces(chain_id, player_address, nonce);
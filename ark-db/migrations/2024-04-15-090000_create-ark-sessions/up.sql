@@ -0,0 +1,19 @@
+-- Your SQL goes here
+
+ CREATE TABLE ark_auth_nonces (
+                id BIGSERIAL PRIMARY KEY,
+                nonce VARCHAR NOT NULL,
+                expires_at BIGINT NOT NULL,
+                used_at BIGINT
+            );
+
+ CREATE TABLE ark_sessions (
+                id BIGSERIAL PRIMARY KEY,
+                token_hash VARCHAR NOT NULL,
+                player_address VARCHAR NOT NULL,
+                expires_at BIGINT NOT NULL
+            );
+
+
+CREATE UNIQUE INDEX unique_ark_auth_nonce ON ark_auth_nonces(nonce);
+CREATE UNIQUE INDEX unique_ark_session_token_hash ON ark_sessions(token_hash);
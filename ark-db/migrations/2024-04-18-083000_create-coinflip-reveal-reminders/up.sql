@@ -0,0 +1,17 @@
+-- Your SQL goes here
+
+ CREATE TABLE coinflip_reveal_reminders (
+                id BIGSERIAL PRIMARY KEY,
+                game_id BIGINT NOT NULL,
+                chain_id BIGINT NOT NULL,
+                game_play_id INTEGER NOT NULL,
+                player_address VARCHAR NOT NULL,
+                blocked_since BIGINT NOT NULL,
+                first_detected_at BIGINT NOT NULL,
+                last_detected_at BIGINT NOT NULL,
+                resolved_at BIGINT
+            );
+
+
+CREATE UNIQUE INDEX unique_coinflip_reveal_reminder ON coinflip_reveal_reminders(game_id, chain_id, game_play_id);
+CREATE INDEX coinflip_reveal_reminders_unresolved_index ON coinflip_reveal_reminders(blocked_since) WHERE resolved_at IS NULL;
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX coinflip_game_activities_game_id_chain_id_id_index;
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+
+CREATE INDEX coinflip_game_activities_game_id_chain_id_id_index ON coinflip_game_activities(game_id, chain_id, id DESC);
@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX coinflip_game_activities_chain_id_block_number_index;
+DROP INDEX unique_coinflip_game_activity_event;
+
+ALTER TABLE coinflip_game_activities DROP COLUMN block_number;
+ALTER TABLE coinflip_game_activities DROP COLUMN log_index;
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+
+ALTER TABLE coinflip_game_activities ADD COLUMN log_index INTEGER;
+ALTER TABLE coinflip_game_activities ADD COLUMN block_number BIGINT;
+
+CREATE UNIQUE INDEX unique_coinflip_game_activity_event ON coinflip_game_activities(chain_id, transaction_hash, log_index, kind, trigger_public_address);
+CREATE INDEX coinflip_game_activities_chain_id_block_number_index ON coinflip_game_activities(chain_id, block_number);
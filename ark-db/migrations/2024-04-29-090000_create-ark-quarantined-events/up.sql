@@ -0,0 +1,24 @@
+-- Your SQL goes here
+
+ CREATE TABLE ark_quarantined_events (
+                id BIGSERIAL PRIMARY KEY,
+                event_id VARCHAR NOT NULL,
+                chain_id BIGINT NOT NULL,
+                contract_address VARCHAR NOT NULL,
+                contract_name VARCHAR NOT NULL,
+                event_abi TEXT NOT NULL,
+                handler_name VARCHAR NOT NULL,
+                transaction_hash VARCHAR NOT NULL,
+                block_number BIGINT NOT NULL,
+                log_index INTEGER NOT NULL,
+                payload JSON NOT NULL,
+                error TEXT NOT NULL,
+                failure_count INTEGER NOT NULL DEFAULT 1,
+                first_failed_at BIGINT NOT NULL,
+                last_failed_at BIGINT NOT NULL,
+                resolved_at BIGINT
+            );
+
+
+CREATE UNIQUE INDEX unique_ark_quarantined_event ON ark_quarantined_events(event_id);
+CREATE INDEX ark_quarantined_events_unresolved_index ON ark_quarantined_events(first_failed_at) WHERE resolved_at IS NULL;
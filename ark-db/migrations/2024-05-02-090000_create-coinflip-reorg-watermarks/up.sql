@@ -0,0 +1,6 @@
+-- Your SQL goes here
+
+CREATE TABLE coinflip_reorg_watermarks (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    last_reorged_block_id BIGINT NOT NULL
+);
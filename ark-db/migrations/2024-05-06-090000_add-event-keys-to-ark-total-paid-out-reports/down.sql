@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX unique_ark_total_paid_out_report_event;
+
+ALTER TABLE ark_total_paid_out_reports DROP COLUMN log_index;
+ALTER TABLE ark_total_paid_out_reports DROP COLUMN transaction_hash;
+ALTER TABLE ark_total_paid_out_reports DROP COLUMN chain_id;
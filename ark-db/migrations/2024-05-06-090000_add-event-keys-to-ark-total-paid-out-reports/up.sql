@@ -0,0 +1,7 @@
+-- Your SQL goes here
+
+ALTER TABLE ark_total_paid_out_reports ADD COLUMN chain_id BIGINT;
+ALTER TABLE ark_total_paid_out_reports ADD COLUMN transaction_hash VARCHAR;
+ALTER TABLE ark_total_paid_out_reports ADD COLUMN log_index INTEGER;
+
+CREATE UNIQUE INDEX unique_ark_total_paid_out_report_event ON ark_total_paid_out_reports(chain_id, transaction_hash, log_index);
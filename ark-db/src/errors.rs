@@ -0,0 +1,36 @@
+use std::fmt;
+
+use diesel_async::pooled_connection::PoolError;
+
+/// Failures reaching or querying the database
+#[derive(Debug)]
+pub enum RepoError {
+    /// No connection could be checked out of the pool
+    Unavailable(String),
+    Query(diesel::result::Error),
+}
+
+pub type RepoResult<T> = Result<T, RepoError>;
+
+impl fmt::Display for RepoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepoError::Unavailable(reason) => write!(f, "database unavailable: {reason}"),
+            RepoError::Query(err) => write!(f, "database query failed: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RepoError {}
+
+impl From<diesel::result::Error> for RepoError {
+    fn from(err: diesel::result::Error) -> Self {
+        RepoError::Query(err)
+    }
+}
+
+impl From<bb8::RunError<PoolError>> for RepoError {
+    fn from(err: bb8::RunError<PoolError>) -> Self {
+        RepoError::Unavailable(err.to_string())
+    }
+}
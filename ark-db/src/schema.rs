@@ -25,7 +25,12 @@ diesel::table! {
       completed_at -> Nullable<Int8>,
       refunded_amount_per_player -> Nullable<VarChar>,
       refunded_at -> Nullable<Int8>,
-      chain_agnostic_index -> Int8
+      chain_agnostic_index -> Int8,
+      created_at -> Int8,
+      status -> VarChar,
+      head_play_count -> Int4,
+      tail_play_count -> Int4,
+      all_seats_filled_at -> Nullable<Int8>,
   }
 }
 
@@ -55,6 +60,54 @@ diesel::table! {
       data -> Json,
       occurred_at -> Int8,
       transaction_hash -> Nullable<VarChar>,
+      log_index -> Nullable<Int4>,
+      block_number -> Nullable<Int8>,
+  }
+}
+
+diesel::table! {
+  coinflip_submitted_chances (id) {
+      id -> Int8,
+      game_id -> Int8,
+      chain_id -> Int8,
+      game_play_id -> Int4,
+      player_address -> VarChar,
+      encrypted_chance_and_salt -> VarChar,
+      submitted_at -> Int8,
+  }
+}
+
+diesel::table! {
+  coinflip_used_reveal_nonces (id) {
+      id -> Int8,
+      chain_id -> Int8,
+      player_address -> VarChar,
+      nonce -> VarChar,
+      used_at -> Int8,
+  }
+}
+
+diesel::table! {
+  coinflip_reveal_reminders (id) {
+      id -> Int8,
+      game_id -> Int8,
+      chain_id -> Int8,
+      game_play_id -> Int4,
+      player_address -> VarChar,
+      blocked_since -> Int8,
+      first_detected_at -> Int8,
+      last_detected_at -> Int8,
+      resolved_at -> Nullable<Int8>,
+  }
+}
+
+diesel::table! {
+  coinflip_service_charges (id) {
+      id -> Int8,
+      chain_id -> Int8,
+      percent -> Int4,
+      effective_from_block_number -> Int8,
+      source -> VarChar,
   }
 }
 
@@ -70,6 +123,55 @@ diesel::table! {
 diesel::table! {
   ark_total_paid_out_reports (id) {
       id -> Int8,
-      amount -> VarChar
+      amount -> VarChar,
+      chain_id -> Nullable<Int8>,
+      transaction_hash -> Nullable<VarChar>,
+      log_index -> Nullable<Int4>,
+  }
+}
+
+diesel::table! {
+  ark_auth_nonces (id) {
+      id -> Int8,
+      nonce -> VarChar,
+      expires_at -> Int8,
+      used_at -> Nullable<Int8>,
+  }
+}
+
+diesel::table! {
+  ark_sessions (id) {
+      id -> Int8,
+      token_hash -> VarChar,
+      player_address -> VarChar,
+      expires_at -> Int8,
+  }
+}
+
+diesel::table! {
+  ark_quarantined_events (id) {
+      id -> Int8,
+      event_id -> VarChar,
+      chain_id -> Int8,
+      contract_address -> VarChar,
+      contract_name -> VarChar,
+      event_abi -> Text,
+      handler_name -> VarChar,
+      transaction_hash -> VarChar,
+      block_number -> Int8,
+      log_index -> Int4,
+      payload -> Json,
+      error -> Text,
+      failure_count -> Int4,
+      first_failed_at -> Int8,
+      last_failed_at -> Int8,
+      resolved_at -> Nullable<Int8>,
+  }
+}
+
+diesel::table! {
+  coinflip_reorg_watermarks (id) {
+      id -> Int4,
+      last_reorged_block_id -> Int8,
   }
 }
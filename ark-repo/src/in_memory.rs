@@ -0,0 +1,180 @@
+use std::sync::RwLock;
+
+use ark::quarantined_events::QuarantinedEvent;
+use ark::sessions::{AuthNonce, Session, UnsavedAuthNonce, UnsavedSession};
+use ark::total_paid_out_report::{TotalPaidOutReport, UnsavedTotalPaidOutReport};
+use ark::wallets::Wallet;
+use ark_db::RepoResult;
+use ark_web3::chains::{ChainCurrency, UnsavedChainCurrency};
+use async_trait::async_trait;
+
+use crate::{ChainCurrencyRepo, QuarantinedEventRepo, SessionRepo, WalletRepo};
+
+/// Mirrors `PostgresArkRepo` without a database e.g. for handler tests
+#[derive(Default)]
+pub struct InMemoryArkRepo {
+    wallets: RwLock<Vec<Wallet>>,
+    chain_currencies: RwLock<Vec<ChainCurrency>>,
+    total_paid_out_reports: RwLock<Vec<TotalPaidOutReport>>,
+    auth_nonces: RwLock<Vec<AuthNonce>>,
+    sessions: RwLock<Vec<Session>>,
+    quarantined_events: RwLock<Vec<QuarantinedEvent>>,
+}
+
+impl InMemoryArkRepo {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Wallets are only ever written by the indexer, so they are seeded directly
+    pub fn insert_wallet(&self, wallet: Wallet) {
+        self.wallets.write().unwrap().push(wallet);
+    }
+    pub fn insert_total_paid_out_report(&self, total_paid_out_report: &UnsavedTotalPaidOutReport) {
+        let mut total_paid_out_reports = self.total_paid_out_reports.write().unwrap();
+        let id = total_paid_out_reports.len() as i64 + 1;
+
+        total_paid_out_reports.push(total_paid_out_report.to_saved(id));
+    }
+    pub fn insert_quarantined_event(&self, quarantined_event: QuarantinedEvent) {
+        self.quarantined_events.write().unwrap().push(quarantined_event);
+    }
+
+    /// Synchronous so other in-memory repos can price wagers while filtering
+    pub fn find_chain_currency(&self, chain_id: i64) -> Option<ChainCurrency> {
+        self.chain_currencies
+            .read()
+            .unwrap()
+            .iter()
+            .find(|chain_currency| chain_currency.chain_id == chain_id)
+            .cloned()
+    }
+}
+
+#[async_trait]
+impl WalletRepo for InMemoryArkRepo {
+    async fn get_wallet(&self, owner_address: &str, chain_id: i64) -> RepoResult<Option<Wallet>> {
+        let owner_address = owner_address.to_lowercase();
+
+        Ok(self
+            .wallets
+            .read()
+            .unwrap()
+            .iter()
+            .find(|wallet| wallet.owner_address == owner_address && wallet.chain_id == chain_id)
+            .cloned())
+    }
+    async fn get_last_total_paid_out_report(&self) -> RepoResult<Option<TotalPaidOutReport>> {
+        Ok(self.total_paid_out_reports.read().unwrap().last().cloned())
+    }
+}
+
+#[async_trait]
+impl ChainCurrencyRepo for InMemoryArkRepo {
+    async fn create_or_update_chain_currencies(
+        &self,
+        chain_currencies: &[UnsavedChainCurrency],
+    ) -> RepoResult<()> {
+        let mut saved_chain_currencies = self.chain_currencies.write().unwrap();
+
+        for chain_currency in chain_currencies {
+            let new_id = saved_chain_currencies.len() as i32 + 1;
+            let new_chain_currency = chain_currency.to_saved(new_id);
+
+            // Ids are assigned in insertion order, so an update keeps the id of its position
+            match saved_chain_currencies.iter().position(|saved_chain_currency| {
+                saved_chain_currency.chain_id == new_chain_currency.chain_id
+                    && saved_chain_currency.currency_symbol == new_chain_currency.currency_symbol
+            }) {
+                Some(index) => {
+                    saved_chain_currencies[index] = chain_currency.to_saved(index as i32 + 1)
+                }
+                None => saved_chain_currencies.push(new_chain_currency),
+            }
+        }
+
+        Ok(())
+    }
+    async fn get_chain_currencies(&self, chain_ids: &[i64]) -> RepoResult<Vec<ChainCurrency>> {
+        Ok(self
+            .chain_currencies
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|chain_currency| chain_ids.contains(&chain_currency.chain_id))
+            .cloned()
+            .collect())
+    }
+    async fn get_chain_currency(&self, chain_id: i64) -> RepoResult<Option<ChainCurrency>> {
+        Ok(self.find_chain_currency(chain_id))
+    }
+}
+
+#[async_trait]
+impl SessionRepo for InMemoryArkRepo {
+    async fn create_auth_nonce(&self, auth_nonce: &UnsavedAuthNonce) -> RepoResult<()> {
+        let mut auth_nonces = self.auth_nonces.write().unwrap();
+        let id = auth_nonces.len() as i64 + 1;
+
+        auth_nonces.push(auth_nonce.to_saved(id));
+
+        Ok(())
+    }
+    async fn use_auth_nonce(&self, nonce: &str, now: i64) -> RepoResult<bool> {
+        let mut auth_nonces = self.auth_nonces.write().unwrap();
+
+        match auth_nonces.iter_mut().find(|auth_nonce| {
+            auth_nonce.nonce == nonce && auth_nonce.used_at.is_none() && auth_nonce.expires_at > now
+        }) {
+            Some(auth_nonce) => {
+                auth_nonce.used_at = Some(now);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+    async fn create_session(&self, session: &UnsavedSession) -> RepoResult<()> {
+        let mut sessions = self.sessions.write().unwrap();
+        let id = sessions.len() as i64 + 1;
+
+        sessions.push(session.to_saved(id));
+
+        Ok(())
+    }
+    async fn get_active_session(&self, token: &str, now: i64) -> RepoResult<Option<Session>> {
+        Ok(self
+            .sessions
+            .read()
+            .unwrap()
+            .iter()
+            .find(|session| session.is_for_token(token) && session.expires_at > now)
+            .cloned())
+    }
+}
+
+#[async_trait]
+impl QuarantinedEventRepo for InMemoryArkRepo {
+    async fn get_unresolved_quarantined_events(&self) -> RepoResult<Vec<QuarantinedEvent>> {
+        let mut quarantined_events: Vec<_> = self
+            .quarantined_events
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|quarantined_event| quarantined_event.resolved_at.is_none())
+            .cloned()
+            .collect();
+
+        quarantined_events.sort_by_key(|quarantined_event| quarantined_event.first_failed_at);
+
+        Ok(quarantined_events)
+    }
+    async fn get_quarantined_event(&self, id: i64) -> RepoResult<Option<QuarantinedEvent>> {
+        Ok(self
+            .quarantined_events
+            .read()
+            .unwrap()
+            .iter()
+            .find(|quarantined_event| quarantined_event.id == id)
+            .cloned())
+    }
+}
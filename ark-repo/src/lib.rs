@@ -1,3 +1,11 @@
+mod in_memory;
+mod repos;
+
+pub use in_memory::*;
+pub use repos::*;
+
+use ark::quarantined_events::{QuarantinedEvent, UnsavedQuarantinedEvent};
+use ark::sessions::{self, Session, UnsavedAuthNonce, UnsavedSession};
 use ark::total_paid_out_report::{TotalPaidOutReport, UnsavedTotalPaidOutReport};
 use ark::wallets::Wallet;
 
@@ -5,13 +13,13 @@ use ark_db::DBConn;
 
 use ark_web3::chains::{ChainCurrency, UnsavedChainCurrency};
 
-use diesel::{upsert::excluded, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel::{upsert::excluded, ExpressionMethods, OptionalExtension, QueryDsl, QueryResult};
 use diesel_async::RunQueryDsl;
 
 pub async fn create_or_update_chain_currencies<'a>(
     conn: &mut DBConn<'a>,
-    chain_currencies: &Vec<UnsavedChainCurrency>,
-) {
+    chain_currencies: &[UnsavedChainCurrency],
+) -> QueryResult<()> {
     use ark_db::schema::ark_chain_currencies::dsl::*;
 
     diesel::insert_into(ark_chain_currencies)
@@ -20,42 +28,34 @@ pub async fn create_or_update_chain_currencies<'a>(
         .do_update()
         .set(unit_usd_price.eq(excluded(unit_usd_price)))
         .execute(conn)
-        .await
-        .unwrap();
+        .await?;
+
+    Ok(())
 }
 
 pub async fn get_chain_currencies<'a>(
     conn: &mut DBConn<'a>,
-    chain_ids: &Vec<i64>,
-) -> Vec<ChainCurrency> {
+    chain_ids: &[i64],
+) -> QueryResult<Vec<ChainCurrency>> {
     use ark_db::schema::ark_chain_currencies::dsl::*;
 
-    ark_chain_currencies
-        .filter(chain_id.eq_any(chain_ids))
-        .load(conn)
-        .await
-        .unwrap()
+    ark_chain_currencies.filter(chain_id.eq_any(chain_ids)).load(conn).await
 }
 
 pub async fn get_chain_currency<'a>(
     conn: &mut DBConn<'a>,
     chain_id_: i64,
-) -> Option<ChainCurrency> {
+) -> QueryResult<Option<ChainCurrency>> {
     use ark_db::schema::ark_chain_currencies::dsl::*;
 
-    ark_chain_currencies
-        .filter(chain_id.eq(chain_id_))
-        .first(conn)
-        .await
-        .optional()
-        .unwrap()
+    ark_chain_currencies.filter(chain_id.eq(chain_id_)).first(conn).await.optional()
 }
 
 pub async fn get_wallet<'a>(
     conn: &mut DBConn<'a>,
     owner_address_: &str,
     chain_id_: i64,
-) -> Option<Wallet> {
+) -> QueryResult<Option<Wallet>> {
     use ark_db::schema::ark_wallets::dsl::*;
 
     ark_wallets
@@ -64,31 +64,146 @@ pub async fn get_wallet<'a>(
         .first(conn)
         .await
         .optional()
-        .unwrap()
 }
 
+/// A no-op for reports already created for the same event
 pub async fn create_total_paid_out_report<'a>(
     conn: &mut DBConn<'a>,
     total_paid_out_report: &UnsavedTotalPaidOutReport,
-) {
+) -> QueryResult<()> {
     use ark_db::schema::ark_total_paid_out_reports::dsl::*;
 
     diesel::insert_into(ark_total_paid_out_reports)
         .values(total_paid_out_report)
+        .on_conflict_do_nothing()
         .execute(conn)
-        .await
-        .unwrap();
+        .await?;
+
+    Ok(())
 }
 
 pub async fn get_last_total_paid_out_report<'a>(
     conn: &mut DBConn<'a>,
-) -> Option<TotalPaidOutReport> {
+) -> QueryResult<Option<TotalPaidOutReport>> {
     use ark_db::schema::ark_total_paid_out_reports::dsl::*;
 
-    ark_total_paid_out_reports
-        .order_by(id.desc())
+    ark_total_paid_out_reports.order_by(id.desc()).first(conn).await.optional()
+}
+
+pub async fn create_auth_nonce<'a>(
+    conn: &mut DBConn<'a>,
+    auth_nonce: &UnsavedAuthNonce,
+) -> QueryResult<()> {
+    use ark_db::schema::ark_auth_nonces::dsl::*;
+
+    diesel::insert_into(ark_auth_nonces).values(auth_nonce).execute(conn).await?;
+
+    Ok(())
+}
+
+/// Marks an unexpired nonce as used, returning false if it was unknown, expired or already used
+pub async fn use_auth_nonce<'a>(
+    conn: &mut DBConn<'a>,
+    nonce_: &str,
+    now: i64,
+) -> QueryResult<bool> {
+    use ark_db::schema::ark_auth_nonces::dsl::*;
+
+    let updated_count = diesel::update(ark_auth_nonces)
+        .filter(nonce.eq(nonce_))
+        .filter(used_at.is_null())
+        .filter(expires_at.gt(now))
+        .set(used_at.eq(Some(now)))
+        .execute(conn)
+        .await?;
+
+    Ok(updated_count == 1)
+}
+
+pub async fn create_session<'a>(
+    conn: &mut DBConn<'a>,
+    session: &UnsavedSession,
+) -> QueryResult<()> {
+    use ark_db::schema::ark_sessions::dsl::*;
+
+    diesel::insert_into(ark_sessions).values(session).execute(conn).await?;
+
+    Ok(())
+}
+
+pub async fn get_active_session<'a>(
+    conn: &mut DBConn<'a>,
+    token: &str,
+    now: i64,
+) -> QueryResult<Option<Session>> {
+    use ark_db::schema::ark_sessions::dsl::*;
+
+    ark_sessions
+        .filter(token_hash.eq(sessions::hash_session_token(token)))
+        .filter(expires_at.gt(now))
         .first(conn)
         .await
         .optional()
-        .unwrap()
+}
+
+/// Failing the same event again bumps its failure count and reopens it if it was resolved
+pub async fn create_or_update_quarantined_event<'a>(
+    conn: &mut DBConn<'a>,
+    quarantined_event: &UnsavedQuarantinedEvent,
+) -> QueryResult<()> {
+    use ark_db::schema::ark_quarantined_events::dsl::*;
+
+    diesel::insert_into(ark_quarantined_events)
+        .values(quarantined_event)
+        .on_conflict(event_id)
+        .do_update()
+        .set((
+            handler_name.eq(excluded(handler_name)),
+            error.eq(excluded(error)),
+            failure_count.eq(failure_count + 1),
+            last_failed_at.eq(excluded(last_failed_at)),
+            resolved_at.eq(None::<i64>),
+        ))
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_unresolved_quarantined_events<'a>(
+    conn: &mut DBConn<'a>,
+) -> QueryResult<Vec<QuarantinedEvent>> {
+    use ark_db::schema::ark_quarantined_events::dsl::*;
+
+    ark_quarantined_events
+        .filter(resolved_at.is_null())
+        .order_by(first_failed_at.asc())
+        .load(conn)
+        .await
+}
+
+pub async fn get_quarantined_event<'a>(
+    conn: &mut DBConn<'a>,
+    id_: i64,
+) -> QueryResult<Option<QuarantinedEvent>> {
+    use ark_db::schema::ark_quarantined_events::dsl::*;
+
+    ark_quarantined_events.filter(id.eq(id_)).first(conn).await.optional()
+}
+
+pub async fn resolve_quarantined_event<'a>(
+    conn: &mut DBConn<'a>,
+    event_id_: &str,
+    now: i64,
+) -> QueryResult<()> {
+    use ark_db::schema::ark_quarantined_events::dsl::*;
+
+    diesel::update(ark_quarantined_events)
+        .filter(event_id.eq(event_id_))
+        .filter(resolved_at.is_null())
+        .set(resolved_at.eq(Some(now)))
+        .execute(conn)
+        .await?;
+
+    Ok(())
 }
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use ark::quarantined_events::QuarantinedEvent;
+use ark::sessions::{Session, UnsavedAuthNonce, UnsavedSession};
+use ark::total_paid_out_report::TotalPaidOutReport;
+use ark::wallets::Wallet;
+use ark_db::{DBConn, DBPool, RepoError, RepoResult};
+use ark_web3::chains::{ChainCurrency, UnsavedChainCurrency};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait WalletRepo: Send + Sync {
+    async fn get_wallet(&self, owner_address: &str, chain_id: i64) -> RepoResult<Option<Wallet>>;
+    /// The running total credited to wallets across all chains
+    async fn get_last_total_paid_out_report(&self) -> RepoResult<Option<TotalPaidOutReport>>;
+}
+
+#[async_trait]
+pub trait ChainCurrencyRepo: Send + Sync {
+    async fn create_or_update_chain_currencies(
+        &self,
+        chain_currencies: &[UnsavedChainCurrency],
+    ) -> RepoResult<()>;
+    async fn get_chain_currencies(&self, chain_ids: &[i64]) -> RepoResult<Vec<ChainCurrency>>;
+    async fn get_chain_currency(&self, chain_id: i64) -> RepoResult<Option<ChainCurrency>>;
+}
+
+#[async_trait]
+pub trait SessionRepo: Send + Sync {
+    async fn create_auth_nonce(&self, auth_nonce: &UnsavedAuthNonce) -> RepoResult<()>;
+    /// Marks an unexpired nonce as used, returning false if it was unknown, expired or already used
+    async fn use_auth_nonce(&self, nonce: &str, now: i64) -> RepoResult<bool>;
+    async fn create_session(&self, session: &UnsavedSession) -> RepoResult<()>;
+    async fn get_active_session(&self, token: &str, now: i64) -> RepoResult<Option<Session>>;
+}
+
+#[async_trait]
+pub trait QuarantinedEventRepo: Send + Sync {
+    /// Oldest first
+    async fn get_unresolved_quarantined_events(&self) -> RepoResult<Vec<QuarantinedEvent>>;
+    async fn get_quarantined_event(&self, id: i64) -> RepoResult<Option<QuarantinedEvent>>;
+}
+
+/// Runs the free functions in this crate on a fresh connection per call
+#[derive(Clone)]
+pub struct PostgresArkRepo {
+    pool: Arc<DBPool>,
+}
+
+impl PostgresArkRepo {
+    pub fn new(pool: Arc<DBPool>) -> Self {
+        Self { pool }
+    }
+
+    async fn get_conn(&self) -> RepoResult<DBConn<'_>> {
+        self.pool.get().await.map_err(RepoError::from)
+    }
+}
+
+#[async_trait]
+impl WalletRepo for PostgresArkRepo {
+    async fn get_wallet(&self, owner_address: &str, chain_id: i64) -> RepoResult<Option<Wallet>> {
+        crate::get_wallet(&mut self.get_conn().await?, owner_address, chain_id)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_last_total_paid_out_report(&self) -> RepoResult<Option<TotalPaidOutReport>> {
+        crate::get_last_total_paid_out_report(&mut self.get_conn().await?)
+            .await
+            .map_err(RepoError::from)
+    }
+}
+
+#[async_trait]
+impl ChainCurrencyRepo for PostgresArkRepo {
+    async fn create_or_update_chain_currencies(
+        &self,
+        chain_currencies: &[UnsavedChainCurrency],
+    ) -> RepoResult<()> {
+        crate::create_or_update_chain_currencies(&mut self.get_conn().await?, chain_currencies)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_chain_currencies(&self, chain_ids: &[i64]) -> RepoResult<Vec<ChainCurrency>> {
+        crate::get_chain_currencies(&mut self.get_conn().await?, chain_ids)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_chain_currency(&self, chain_id: i64) -> RepoResult<Option<ChainCurrency>> {
+        crate::get_chain_currency(&mut self.get_conn().await?, chain_id)
+            .await
+            .map_err(RepoError::from)
+    }
+}
+
+#[async_trait]
+impl SessionRepo for PostgresArkRepo {
+    async fn create_auth_nonce(&self, auth_nonce: &UnsavedAuthNonce) -> RepoResult<()> {
+        crate::create_auth_nonce(&mut self.get_conn().await?, auth_nonce)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn use_auth_nonce(&self, nonce: &str, now: i64) -> RepoResult<bool> {
+        crate::use_auth_nonce(&mut self.get_conn().await?, nonce, now)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn create_session(&self, session: &UnsavedSession) -> RepoResult<()> {
+        crate::create_session(&mut self.get_conn().await?, session)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_active_session(&self, token: &str, now: i64) -> RepoResult<Option<Session>> {
+        crate::get_active_session(&mut self.get_conn().await?, token, now)
+            .await
+            .map_err(RepoError::from)
+    }
+}
+
+#[async_trait]
+impl QuarantinedEventRepo for PostgresArkRepo {
+    async fn get_unresolved_quarantined_events(&self) -> RepoResult<Vec<QuarantinedEvent>> {
+        crate::get_unresolved_quarantined_events(&mut self.get_conn().await?)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_quarantined_event(&self, id: i64) -> RepoResult<Option<QuarantinedEvent>> {
+        crate::get_quarantined_event(&mut self.get_conn().await?, id)
+            .await
+            .map_err(RepoError::from)
+    }
+}
@@ -0,0 +1,81 @@
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use http::header::AUTHORIZATION;
+use subtle::ConstantTimeEq;
+
+use crate::{AppState, Error};
+
+/// The player whose session token came with the request,
+/// inserted into request extensions by `authenticate`.
+#[derive(Clone, Debug)]
+pub struct AuthenticatedPlayer {
+    pub address: String,
+}
+
+impl AuthenticatedPlayer {
+    pub fn is(&self, address: &str) -> bool {
+        self.address.to_lowercase() == address.to_lowercase()
+    }
+}
+
+/// Resolves `Authorization: Bearer <session token>` into an `AuthenticatedPlayer`.
+/// Requests without the header go through anonymously, invalid or expired tokens are rejected.
+pub async fn authenticate(
+    State(app_state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim_start_matches("Bearer ").trim().to_string());
+
+    if let Some(token) = token {
+        let now = app_state.clock.now();
+
+        match app_state.repos.sessions.get_active_session(&token, now).await {
+            Ok(Some(session)) => {
+                request.extensions_mut().insert(AuthenticatedPlayer {
+                    address: session.player_address,
+                });
+            }
+            Ok(None) => return Error::unauthorized("Invalid or expired session").into_response(),
+            Err(err) => return Error::from(err).into_response(),
+        }
+    }
+
+    next.run(request).await
+}
+
+/// Guards private views, must run after `authenticate`
+pub async fn require_authenticated_player(request: Request, next: Next) -> Response {
+    if request.extensions().get::<AuthenticatedPlayer>().is_some() {
+        next.run(request).await
+    } else {
+        Error::unauthorized("Sign in required").into_response()
+    }
+}
+
+pub const ADMIN_KEY_HEADER: &str = "x-admin-key";
+
+/// Guards admin views with the configured `admin_api_key` shared secret.
+/// Compared in constant time, so response times don't leak how much of a guess matched.
+pub async fn require_admin(
+    State(app_state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let admin_api_key = app_state.config.admin_api_key.as_deref();
+    let given_key = request.headers().get(ADMIN_KEY_HEADER).and_then(|value| value.to_str().ok());
+
+    match (admin_api_key, given_key) {
+        (Some(admin_api_key), Some(given_key))
+            if bool::from(admin_api_key.as_bytes().ct_eq(given_key.as_bytes())) =>
+        {
+            next.run(request).await
+        }
+        _ => Error::unauthorized("Admin key required").into_response(),
+    }
+}
@@ -0,0 +1,150 @@
+use ark_db::RepoError;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use diesel_async::pooled_connection::PoolError;
+use http::StatusCode;
+use serde::Serialize;
+use tracing::error;
+
+/// Every failure a handler can return, rendered as `{"code": ..., "message": ...}`
+#[derive(Debug)]
+pub enum Error {
+    NotFound(String),
+    /// The request itself is malformed or fails a domain rule
+    Validation(String),
+    /// The request parses but one of its values is out of range e.g. an unknown coin side
+    InvalidParams(String),
+    Unauthorized(String),
+    Forbidden(String),
+    /// The request is valid but clashes with the current state e.g. a replayed signature
+    Conflict(String),
+    /// A dependency, like the database or a price row, can't serve the request right now
+    UpstreamUnavailable(String),
+    /// Details are logged but never sent to the client
+    Internal(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl Error {
+    pub fn not_found(message: &str) -> Self {
+        Error::NotFound(message.to_string())
+    }
+    pub fn validation(message: &str) -> Self {
+        Error::Validation(message.to_string())
+    }
+    pub fn invalid_params(message: &str) -> Self {
+        Error::InvalidParams(message.to_string())
+    }
+    pub fn unauthorized(message: &str) -> Self {
+        Error::Unauthorized(message.to_string())
+    }
+    pub fn forbidden(message: &str) -> Self {
+        Error::Forbidden(message.to_string())
+    }
+    pub fn conflict(message: &str) -> Self {
+        Error::Conflict(message.to_string())
+    }
+    pub fn upstream_unavailable(message: &str) -> Self {
+        Error::UpstreamUnavailable(message.to_string())
+    }
+
+    pub fn internal<E>(err: E) -> Self
+    where
+        E: std::fmt::Display,
+    {
+        Error::Internal(err.to_string())
+    }
+
+    pub fn get_code(&self) -> &'static str {
+        match self {
+            Error::NotFound(_) => "not_found",
+            Error::Validation(_) => "validation_failed",
+            Error::InvalidParams(_) => "invalid_params",
+            Error::Unauthorized(_) => "unauthorized",
+            Error::Forbidden(_) => "forbidden",
+            Error::Conflict(_) => "conflict",
+            Error::UpstreamUnavailable(_) => "upstream_unavailable",
+            Error::Internal(_) => "internal",
+        }
+    }
+
+    pub fn get_status_code(&self) -> StatusCode {
+        match self {
+            Error::NotFound(_) => StatusCode::NOT_FOUND,
+            Error::Validation(_) => StatusCode::BAD_REQUEST,
+            Error::InvalidParams(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            Error::Forbidden(_) => StatusCode::FORBIDDEN,
+            Error::Conflict(_) => StatusCode::CONFLICT,
+            Error::UpstreamUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Error::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn get_message(&self) -> &str {
+        match self {
+            Error::NotFound(message)
+            | Error::Validation(message)
+            | Error::InvalidParams(message)
+            | Error::Unauthorized(message)
+            | Error::Forbidden(message)
+            | Error::Conflict(message)
+            | Error::UpstreamUnavailable(message) => message,
+            Error::Internal(_) => "Internal server error",
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Internal(detail) => write!(f, "{}: {}", self.get_code(), detail),
+            _ => write!(f, "{}: {}", self.get_code(), self.get_message()),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<RepoError> for Error {
+    fn from(err: RepoError) -> Self {
+        match err {
+            RepoError::Unavailable(_) => Error::UpstreamUnavailable(err.to_string()),
+            RepoError::Query(_) => Error::Internal(err.to_string()),
+        }
+    }
+}
+
+impl From<diesel::result::Error> for Error {
+    fn from(err: diesel::result::Error) -> Self {
+        RepoError::from(err).into()
+    }
+}
+
+impl From<bb8::RunError<PoolError>> for Error {
+    fn from(err: bb8::RunError<PoolError>) -> Self {
+        RepoError::from(err).into()
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        match &self {
+            Error::Internal(detail) => error!("Internal error: {detail}"),
+            Error::UpstreamUnavailable(detail) => error!("Upstream unavailable: {detail}"),
+            _ => {}
+        }
+
+        let body = ErrorBody {
+            code: self.get_code(),
+            message: self.get_message().to_string(),
+        };
+
+        (self.get_status_code(), Json(body)).into_response()
+    }
+}
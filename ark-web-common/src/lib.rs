@@ -1,22 +1,66 @@
-use std::sync::Arc;
+pub mod auth;
+mod errors;
+mod repos;
+pub mod workers;
 
+pub use errors::{Error, ErrorBody};
+pub use repos::Repos;
+pub use workers::WorkerHealths;
+
+use std::sync::{Arc, RwLock};
+
+use ark::configs::ArkConfig;
 use ark_db::DBPool;
-use chaindexing::KeepNodeActiveRequest;
+use ark_utils::clocks::Clock;
+use chaindexing::{ChaindexingRepo, KeepNodeActiveRequest, Repo};
+use coinflip::{ChanceCipher, RecentResults};
 
 #[derive(Clone)]
 pub struct AppState {
     pub db_pool: Arc<DBPool>,
     pub keep_chaindexing_node_active_request: KeepNodeActiveRequest,
+    /// e.g. for retrying quarantined events in chaindexing's own transactions
+    pub chaindexing_repo: ChaindexingRepo,
+    pub clock: Arc<dyn Clock>,
+    pub repos: Repos,
+    pub config: Arc<ArkConfig>,
+    /// Built once from the key `ArkConfig` validated at boot
+    pub chance_cipher: ChanceCipher,
+    pub worker_healths: WorkerHealths,
+    /// Latest wins for the ticker, kept fresh by the `LoadRecentResults` worker
+    pub recent_results: Arc<RwLock<RecentResults>>,
 }
 
 impl AppState {
     pub fn new(
         db_pool: Arc<DBPool>,
         keep_chaindexing_node_active_request: &KeepNodeActiveRequest,
+        clock: Arc<dyn Clock>,
+        repos: Repos,
+        config: Arc<ArkConfig>,
     ) -> Self {
         Self {
+            repos,
             db_pool,
             keep_chaindexing_node_active_request: keep_chaindexing_node_active_request.clone(),
+            chaindexing_repo: ChaindexingRepo::new(&config.database_url),
+            clock,
+            chance_cipher: ChanceCipher::new(&config.chance_encryption_key),
+            config,
+            worker_healths: WorkerHealths::default(),
+            recent_results: Default::default(),
         }
     }
+
+    /// Shares the supervisor's worker healths with the admin endpoint
+    pub fn with_worker_healths(mut self, worker_healths: WorkerHealths) -> Self {
+        self.worker_healths = worker_healths;
+        self
+    }
+
+    /// Shares the buffer the `LoadRecentResults` worker reloads
+    pub fn with_recent_results(mut self, recent_results: Arc<RwLock<RecentResults>>) -> Self {
+        self.recent_results = recent_results;
+        self
+    }
 }
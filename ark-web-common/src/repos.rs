@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use ark_repo::{ChainCurrencyRepo, QuarantinedEventRepo, SessionRepo, WalletRepo};
+use coinflip::{ActivityRepo, GamePlayRepo, GameRepo, RevealReminderRepo};
+
+/// Repos handlers and workers read through, so they can run against Postgres or in memory
+#[derive(Clone)]
+pub struct Repos {
+    pub games: Arc<dyn GameRepo>,
+    pub game_plays: Arc<dyn GamePlayRepo>,
+    pub activities: Arc<dyn ActivityRepo>,
+    pub reveal_reminders: Arc<dyn RevealReminderRepo>,
+    pub wallets: Arc<dyn WalletRepo>,
+    pub chain_currencies: Arc<dyn ChainCurrencyRepo>,
+    pub sessions: Arc<dyn SessionRepo>,
+    pub quarantined_events: Arc<dyn QuarantinedEventRepo>,
+}
+
+impl Repos {
+    /// e.g. `PostgresArkRepo` with `coinflip_repo::PostgresCoinflipRepo`,
+    /// or their in-memory counterparts in tests
+    pub fn new<A, C>(ark_repo: Arc<A>, coinflip_repo: Arc<C>) -> Self
+    where
+        A: WalletRepo + ChainCurrencyRepo + SessionRepo + QuarantinedEventRepo + 'static,
+        C: GameRepo + GamePlayRepo + ActivityRepo + RevealReminderRepo + 'static,
+    {
+        Self {
+            games: coinflip_repo.clone(),
+            game_plays: coinflip_repo.clone(),
+            activities: coinflip_repo.clone(),
+            reveal_reminders: coinflip_repo,
+            wallets: ark_repo.clone(),
+            chain_currencies: ark_repo.clone(),
+            sessions: ark_repo.clone(),
+            quarantined_events: ark_repo,
+        }
+    }
+}
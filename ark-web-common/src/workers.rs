@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use ark::profiles::Worker;
+use ark_utils::clocks::Clock;
+use ark_utils::panics::get_panic_message;
+use async_trait::async_trait;
+use futures::FutureExt;
+use serde::Serialize;
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+use tokio::time::{sleep, timeout, Instant};
+use tracing::{error, info, warn};
+
+const BASE_BACKOFF_SECS: u64 = 5;
+const MAX_BACKOFF_SECS: u64 = 5 * 60;
+/// How long runs in progress get to finish on shutdown before they are dropped
+const DRAIN_TIMEOUT_SECS: u64 = 30;
+/// Runs that lasted this long before panicking start backing off from scratch again
+const STABLE_RUN_SECS: u64 = 10 * 60;
+
+/// Background work owned by a `WorkerSupervisor`.
+/// Cloned afresh on every (re)start, so a worker's caches don't outlive a panic.
+#[async_trait]
+pub trait AppWorker: Clone + Send + 'static {
+    fn get_worker(&self) -> Worker;
+
+    /// Between runs, `None` for workers that are done once they succeed e.g. boot warmups
+    fn get_interval(&self) -> Option<Duration>;
+
+    /// Waited out before the first run e.g. for chaindexing to set up
+    fn get_initial_delay(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    /// True when runs hand their work off to tasks the supervisor can't see
+    /// e.g. chaindexing's, so shutdown can't drain them
+    fn is_detached(&self) -> bool {
+        false
+    }
+
+    /// Should check out its own connections, so a dead one doesn't outlive the run
+    async fn run(&mut self) -> eyre::Result<()>;
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct WorkerHealth {
+    pub name: &'static str,
+    pub last_run_at: Option<i64>,
+    pub last_success_at: Option<i64>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<i64>,
+    /// Times it panicked and got started again since it last ran for `STABLE_RUN_SECS`
+    pub restart_count: u32,
+}
+
+/// Shared between the supervisor, which records into it, and the admin endpoint
+#[derive(Clone, Default)]
+pub struct WorkerHealths {
+    healths: Arc<RwLock<HashMap<Worker, WorkerHealth>>>,
+}
+
+impl WorkerHealths {
+    /// By name
+    pub fn get_all(&self) -> Vec<WorkerHealth> {
+        let mut healths: Vec<_> = self.healths.read().unwrap().values().cloned().collect();
+        healths.sort_by_key(|health| health.name);
+        healths
+    }
+
+    pub fn get(&self, worker: Worker) -> Option<WorkerHealth> {
+        self.healths.read().unwrap().get(&worker).cloned()
+    }
+
+    fn update(&self, worker: Worker, update: impl FnOnce(&mut WorkerHealth)) {
+        let mut healths = self.healths.write().unwrap();
+        let health = healths.entry(worker).or_insert_with(|| WorkerHealth {
+            name: worker.get_name(),
+            ..Default::default()
+        });
+
+        update(health);
+    }
+}
+
+pub struct WorkerSupervisor {
+    clock: Arc<dyn Clock>,
+    healths: WorkerHealths,
+    shutdown: watch::Sender<bool>,
+    tasks: JoinSet<()>,
+    detached_workers: Vec<&'static str>,
+}
+
+impl WorkerSupervisor {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        let (shutdown, _) = watch::channel(false);
+
+        Self {
+            clock,
+            healths: WorkerHealths::default(),
+            shutdown,
+            tasks: JoinSet::new(),
+            detached_workers: vec![],
+        }
+    }
+
+    pub fn get_healths(&self) -> WorkerHealths {
+        self.healths.clone()
+    }
+
+    /// Runs the worker until shutdown, restarting it with backoff whenever it panics
+    pub fn supervise(&mut self, worker: impl AppWorker) {
+        let clock = self.clock.clone();
+        let healths = self.healths.clone();
+        let mut shutdown = self.shutdown.subscribe();
+
+        healths.update(worker.get_worker(), |_| {});
+
+        if worker.is_detached() {
+            self.detached_workers.push(worker.get_worker().get_name());
+        }
+
+        self.tasks.spawn(async move {
+            let name = worker.get_worker().get_name();
+            let mut restart_count = 0;
+
+            loop {
+                let started_at = Instant::now();
+                let run = run_until_shutdown(worker.clone(), &clock, &healths, shutdown.clone());
+
+                let Err(panic) = AssertUnwindSafe(run).catch_unwind().await else {
+                    return;
+                };
+
+                if started_at.elapsed() >= Duration::from_secs(STABLE_RUN_SECS) {
+                    restart_count = 0;
+                }
+                restart_count += 1;
+                let backoff = get_backoff(restart_count);
+
+                healths.update(worker.get_worker(), |health| {
+                    health.last_error = Some(format!("panicked: {}", get_panic_message(&*panic)));
+                    health.last_error_at = Some(clock.now());
+                    health.restart_count = restart_count;
+                });
+                error!(
+                    "[{name}]: Panicked, restarting in {}s...",
+                    backoff.as_secs()
+                );
+
+                if !wait(backoff, &mut shutdown).await {
+                    return;
+                }
+            }
+        });
+    }
+
+    /// Stops workers from starting new runs and waits for the ones in progress to finish
+    pub async fn shutdown(mut self) {
+        info!("[WorkerSupervisor]: Draining workers...");
+
+        self.shutdown.send_replace(true);
+
+        let drain = async { while self.tasks.join_next().await.is_some() {} };
+
+        if timeout(Duration::from_secs(DRAIN_TIMEOUT_SECS), drain).await.is_err() {
+            warn!("[WorkerSupervisor]: Dropping workers still running after {DRAIN_TIMEOUT_SECS}s");
+        }
+        for name in self.detached_workers {
+            warn!("[{name}]: Not drained, its tasks stop with the process");
+        }
+    }
+}
+
+async fn run_until_shutdown(
+    mut worker: impl AppWorker,
+    clock: &Arc<dyn Clock>,
+    healths: &WorkerHealths,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let name = worker.get_worker().get_name();
+
+    if !wait(worker.get_initial_delay(), &mut shutdown).await {
+        return;
+    }
+
+    let mut failure_count = 0;
+
+    loop {
+        healths.update(worker.get_worker(), |health| {
+            health.last_run_at = Some(clock.now());
+        });
+
+        let next_run_in = match worker.run().await {
+            Ok(()) => {
+                failure_count = 0;
+                healths.update(worker.get_worker(), |health| {
+                    health.last_success_at = Some(clock.now());
+                });
+
+                match worker.get_interval() {
+                    Some(interval) => interval,
+                    None => return,
+                }
+            }
+            Err(err) => {
+                failure_count += 1;
+                let err = format!("{err:#}");
+                error!("[{name}]: Failed because:{err}");
+
+                healths.update(worker.get_worker(), |health| {
+                    health.last_error = Some(err);
+                    health.last_error_at = Some(clock.now());
+                });
+
+                worker.get_interval().unwrap_or_else(|| get_backoff(failure_count))
+            }
+        };
+
+        if !wait(next_run_in, &mut shutdown).await {
+            return;
+        }
+    }
+}
+
+/// False if shutdown came first
+async fn wait(duration: Duration, shutdown: &mut watch::Receiver<bool>) -> bool {
+    tokio::select! {
+        biased;
+        _ = shutdown.wait_for(|is_shutting_down| *is_shutting_down) => false,
+        _ = sleep(duration) => true,
+    }
+}
+
+/// Doubles with every attempt, capped at `MAX_BACKOFF_SECS`
+pub fn get_backoff(attempt: u32) -> Duration {
+    let secs = BASE_BACKOFF_SECS.saturating_mul(2_u64.saturating_pow(attempt.saturating_sub(1)));
+
+    Duration::from_secs(secs.min(MAX_BACKOFF_SECS))
+}
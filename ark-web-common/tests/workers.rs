@@ -0,0 +1,183 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use ark::profiles::Worker;
+use ark_utils::clocks::TestClock;
+use ark_web_common::workers::{get_backoff, AppWorker, WorkerSupervisor};
+use async_trait::async_trait;
+use eyre::{eyre, Result};
+use tokio::time::sleep;
+
+const NOW: i64 = 1_700_000_000;
+
+/// Panics on `panicking_runs`, fails for its first `failing_runs` and succeeds otherwise
+#[derive(Clone)]
+struct ScriptedWorker {
+    interval: Option<Duration>,
+    panicking_runs: Vec<u32>,
+    failing_runs: u32,
+    run_count: Arc<AtomicU32>,
+}
+
+impl ScriptedWorker {
+    fn new(interval: Option<Duration>) -> Self {
+        Self {
+            interval,
+            panicking_runs: vec![],
+            failing_runs: 0,
+            run_count: Arc::new(AtomicU32::new(0)),
+        }
+    }
+
+    fn get_run_count(&self) -> u32 {
+        self.run_count.load(Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl AppWorker for ScriptedWorker {
+    fn get_worker(&self) -> Worker {
+        Worker::SyncServiceCharges
+    }
+
+    fn get_interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    async fn run(&mut self) -> Result<()> {
+        let run = self.run_count.fetch_add(1, Ordering::SeqCst) + 1;
+
+        if self.panicking_runs.contains(&run) {
+            panic!("run {run} panicked");
+        }
+        if run <= self.failing_runs {
+            return Err(eyre!("run {run} failed"));
+        }
+
+        Ok(())
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn records_runs_successes_and_errors() {
+    let clock = Arc::new(TestClock::new(NOW));
+    let mut supervisor = WorkerSupervisor::new(clock.clone());
+    let worker = ScriptedWorker {
+        failing_runs: 1,
+        ..ScriptedWorker::new(Some(Duration::from_secs(60)))
+    };
+
+    supervisor.supervise(worker.clone());
+    sleep(Duration::from_secs(1)).await;
+
+    let health = supervisor.get_healths().get(Worker::SyncServiceCharges).unwrap();
+    assert_eq!(health.name, "SyncServiceCharges");
+    assert_eq!(health.last_run_at, Some(NOW));
+    assert_eq!(health.last_success_at, None);
+    assert_eq!(health.last_error.as_deref(), Some("run 1 failed"));
+    assert_eq!(health.last_error_at, Some(NOW));
+
+    clock.advance(60);
+    sleep(Duration::from_secs(60)).await;
+
+    let health = supervisor.get_healths().get(Worker::SyncServiceCharges).unwrap();
+    assert_eq!(worker.get_run_count(), 2);
+    assert_eq!(health.last_run_at, Some(NOW + 60));
+    assert_eq!(health.last_success_at, Some(NOW + 60));
+    // Kept around so a recovered worker still shows what went wrong last
+    assert_eq!(health.last_error_at, Some(NOW));
+}
+
+#[tokio::test(start_paused = true)]
+async fn restarts_panicking_workers_with_backoff() {
+    let mut supervisor = WorkerSupervisor::new(Arc::new(TestClock::new(NOW)));
+    let worker = ScriptedWorker {
+        panicking_runs: vec![1, 2],
+        ..ScriptedWorker::new(Some(Duration::from_secs(60)))
+    };
+
+    supervisor.supervise(worker.clone());
+    sleep(Duration::from_secs(1)).await;
+
+    let health = supervisor.get_healths().get(Worker::SyncServiceCharges).unwrap();
+    assert_eq!(worker.get_run_count(), 1);
+    assert_eq!(health.restart_count, 1);
+    assert_eq!(
+        health.last_error.as_deref(),
+        Some("panicked: run 1 panicked")
+    );
+
+    sleep(get_backoff(1) + get_backoff(2)).await;
+
+    let health = supervisor.get_healths().get(Worker::SyncServiceCharges).unwrap();
+    assert_eq!(worker.get_run_count(), 3);
+    assert_eq!(health.restart_count, 2);
+    assert_eq!(health.last_success_at, Some(NOW));
+}
+
+#[tokio::test(start_paused = true)]
+async fn resets_backoff_for_workers_that_ran_stably_before_panicking() {
+    let mut supervisor = WorkerSupervisor::new(Arc::new(TestClock::new(NOW)));
+    let worker = ScriptedWorker {
+        // Run 13 is 11 minutes into the restarted worker's runs
+        panicking_runs: vec![1, 13],
+        ..ScriptedWorker::new(Some(Duration::from_secs(60)))
+    };
+
+    supervisor.supervise(worker.clone());
+    sleep(Duration::from_secs(1)).await;
+    assert_eq!(
+        supervisor.get_healths().get(Worker::SyncServiceCharges).unwrap().restart_count,
+        1
+    );
+
+    sleep(get_backoff(1) + Duration::from_secs(11 * 60)).await;
+
+    let health = supervisor.get_healths().get(Worker::SyncServiceCharges).unwrap();
+    assert_eq!(worker.get_run_count(), 13);
+    assert_eq!(health.restart_count, 1);
+    assert_eq!(
+        health.last_error.as_deref(),
+        Some("panicked: run 13 panicked")
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn retries_run_once_workers_until_they_succeed() {
+    let mut supervisor = WorkerSupervisor::new(Arc::new(TestClock::new(NOW)));
+    let worker = ScriptedWorker {
+        failing_runs: 2,
+        ..ScriptedWorker::new(None)
+    };
+
+    supervisor.supervise(worker.clone());
+    sleep(get_backoff(1) + get_backoff(2) + Duration::from_secs(1)).await;
+    assert_eq!(worker.get_run_count(), 3);
+
+    sleep(Duration::from_secs(60 * 60)).await;
+    assert_eq!(worker.get_run_count(), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn stops_running_workers_on_shutdown() {
+    let mut supervisor = WorkerSupervisor::new(Arc::new(TestClock::new(NOW)));
+    let worker = ScriptedWorker::new(Some(Duration::from_secs(60)));
+
+    supervisor.supervise(worker.clone());
+    sleep(Duration::from_secs(1)).await;
+
+    supervisor.shutdown().await;
+    sleep(Duration::from_secs(60 * 60)).await;
+
+    assert_eq!(worker.get_run_count(), 1);
+}
+
+#[test]
+fn doubles_backoff_up_to_five_minutes() {
+    assert_eq!(get_backoff(1), Duration::from_secs(5));
+    assert_eq!(get_backoff(2), Duration::from_secs(10));
+    assert_eq!(get_backoff(3), Duration::from_secs(20));
+    assert_eq!(get_backoff(10), Duration::from_secs(5 * 60));
+    assert_eq!(get_backoff(u32::MAX), Duration::from_secs(5 * 60));
+}
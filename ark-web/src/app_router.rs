@@ -3,7 +3,9 @@
 use ark_web_common::AppState;
 
 // use axum::error_handling::HandleErrorLayer;
+use ark_web_common::auth;
 use axum::extract::{MatchedPath, Request};
+use axum::middleware;
 use axum::routing::{get, post};
 // use axum::BoxError;
 use axum::{http::HeaderValue, Router};
@@ -17,19 +19,26 @@ use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 use tracing::info_span;
 
-use crate::handlers::{keep_indexing_active_request_handler, wallet_handler};
+use crate::handlers::{keep_indexing_active_request_handler, quarantined_event_handler};
+use crate::handlers::{session_handler, wallet_handler, worker_handler};
 
 pub struct AppRouter {
     pub routes: Router<AppState>,
 }
 
 impl AppRouter {
-    pub fn new() -> Self {
+    pub fn new(app_state: &AppState) -> Self {
         Self {
             routes: Router::new()
                 .merge(Self::ark_routes())
-                .merge(Self::coinflip_routes())
-                .layer(Self::cors_layer())
+                .merge(Self::session_routes())
+                .merge(Self::coinflip_routes(app_state))
+                .merge(Self::admin_routes(app_state))
+                .layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth::authenticate,
+                ))
+                .layer(Self::cors_layer(app_state))
                 // .layer(
                 //     ServiceBuilder::new()
                 //         .layer(HandleErrorLayer::new(|err: BoxError| async move {
@@ -69,15 +78,41 @@ impl AppRouter {
             )
     }
 
-    fn coinflip_routes() -> Router<AppState> {
-        Router::new().nest("/coinflip", coinflip_web::AppRouter::new().routes)
+    fn session_routes() -> Router<AppState> {
+        Router::new().nest(
+            "/sessions",
+            Router::new()
+                .route("/", post(session_handler::create_session))
+                .route("/nonce", post(session_handler::create_nonce)),
+        )
     }
 
-    fn cors_layer() -> CorsLayer {
-        dotenvy::dotenv().ok();
+    fn coinflip_routes(app_state: &AppState) -> Router<AppState> {
+        Router::new().nest("/coinflip", coinflip_web::AppRouter::new(app_state).routes)
+    }
+
+    fn admin_routes(app_state: &AppState) -> Router<AppState> {
+        Router::new().nest(
+            "/admin",
+            Router::new()
+                .route(
+                    "/quarantined_events",
+                    get(quarantined_event_handler::get_quarantined_events),
+                )
+                .route(
+                    "/quarantined_events/:id/retry",
+                    post(quarantined_event_handler::retry_quarantined_event),
+                )
+                .route("/workers", get(worker_handler::get_workers))
+                .route_layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth::require_admin,
+                )),
+        )
+    }
 
-        let frontend_origin =
-            std::env::var("FRONTEND_ORIGIN").expect("FRONTEND_ORIGIN must be set");
+    fn cors_layer(app_state: &AppState) -> CorsLayer {
+        let frontend_origin = &app_state.config.frontend_origin;
 
         CorsLayer::new()
             .allow_origin(frontend_origin.parse::<HeaderValue>().unwrap())
@@ -1,5 +1,7 @@
 use std::net::{SocketAddr, ToSocketAddrs};
 
+use ark::configs::ArkConfig;
+
 #[derive(Debug)]
 pub struct AppServerConfig {
     pub host: String,
@@ -7,15 +9,10 @@ pub struct AppServerConfig {
 }
 
 impl AppServerConfig {
-    pub fn new() -> Self {
-        dotenvy::dotenv().ok();
-
-        let server_host = std::env::var("HOST").unwrap_or(Self::default_host());
-        let server_port = std::env::var("PORT").unwrap_or(Self::default_port());
-
+    pub fn new(config: &ArkConfig) -> Self {
         Self {
-            host: server_host,
-            port: server_port.parse().unwrap(),
+            host: config.server.host.clone(),
+            port: config.server.port,
         }
     }
 
@@ -26,12 +23,4 @@ impl AppServerConfig {
     fn host_with_port(&self) -> String {
         format!("{}:{}", self.host, self.port)
     }
-
-    fn default_host() -> String {
-        "127.0.0.1".to_string()
-    }
-
-    fn default_port() -> String {
-        "4446".to_string()
-    }
 }
@@ -1,60 +1,79 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use ark_db::DBPool;
-use ark_web3::chains::{ChainId, UnsavedChainCurrency};
-use strum::IntoEnumIterator;
+use ark::configs::ArkConfig;
+use ark::profiles::Worker;
+use ark_db::{DBPool, RepoError};
+use ark_web3::chains::UnsavedChainCurrency;
+use ark_web_common::workers::AppWorker;
+use async_trait::async_trait;
+use eyre::{eyre, Result, WrapErr};
+use tracing::error;
 
-use ark_web3::chains;
+const TWENTY_MINUTES: u64 = 20 * 60;
 
-use tokio::time::interval;
+#[derive(Clone)]
+pub struct CacheChainUnitCurrenciesInUsd {
+    pool: Arc<DBPool>,
+    config: Arc<ArkConfig>,
+}
 
-const TWENTY_MINUTES: u64 = 20 * 60;
+impl CacheChainUnitCurrenciesInUsd {
+    pub fn new(pool: Arc<DBPool>, config: Arc<ArkConfig>) -> Self {
+        Self { pool, config }
+    }
+}
+
+#[async_trait]
+impl AppWorker for CacheChainUnitCurrenciesInUsd {
+    fn get_worker(&self) -> Worker {
+        Worker::CacheChainUnitCurrenciesInUsd
+    }
+
+    fn get_interval(&self) -> Option<Duration> {
+        Some(Duration::from_secs(TWENTY_MINUTES))
+    }
+
+    async fn run(&mut self) -> Result<()> {
+        let price_sources = &self.config.profile.price_sources;
+
+        let mut quoted_currency_symbols: Vec<_> = price_sources
+            .iter()
+            .map(|price_source| price_source.quoted_chain_id.get_currency_symbol())
+            .collect();
+        quoted_currency_symbols.sort();
+        quoted_currency_symbols.dedup();
+
+        let unit_prices_in_usd = crypto_compare::get_unit_prices_in_usd(&quoted_currency_symbols)
+            .await
+            .map_err(|err| eyre!("Failed to fetch unit prices because:{err:?}"))?;
+
+        let chain_currencies: Vec<_> = price_sources
+            .iter()
+            .filter_map(|price_source| {
+                let quoted_currency_symbol = price_source.quoted_chain_id.get_currency_symbol();
+
+                let Some(unit_usd_price) = unit_prices_in_usd.get(quoted_currency_symbol) else {
+                    error!(
+                        "[CacheChainUnitCurrenciesInUsd]: No quote for {quoted_currency_symbol}"
+                    );
+                    return None;
+                };
+
+                Some(UnsavedChainCurrency::new(
+                    price_source.chain_id,
+                    price_source.chain_id.get_currency_symbol(),
+                    *unit_usd_price,
+                ))
+            })
+            .collect();
+
+        let mut conn = self.pool.get().await.map_err(RepoError::from)?;
+
+        ark_repo::create_or_update_chain_currencies(&mut conn, &chain_currencies)
+            .await
+            .wrap_err("Failed to save chain currencies")?;
 
-pub fn start(pool: Arc<DBPool>) {
-    tokio::spawn(async move {
-        let mut interval = interval(Duration::from_secs(TWENTY_MINUTES));
-        let pool = pool.clone();
-        let mut conn = pool.get().await.unwrap();
-
-        loop {
-            let test_net_chains = chains::get_test_nets();
-            let chains = ChainId::iter().filter(|c| !test_net_chains.contains(c));
-
-            let chain_currency_symbols: Vec<_> = chains.map(|c| c.get_currency_symbol()).collect();
-
-            if let Ok(unit_prices_in_usd) =
-                crypto_compare::get_unit_prices_in_usd(&chain_currency_symbols).await
-            {
-                let mut chain_currencies: Vec<_> = unit_prices_in_usd
-                    .iter()
-                    .map(|(currency_symbol, unit_usd_price)| {
-                        let chain = ChainId::from_currency_symbol(currency_symbol);
-
-                        UnsavedChainCurrency::new(chain, currency_symbol, *unit_usd_price)
-                    })
-                    .collect();
-
-                let eth_unit_usd_price =
-                    *unit_prices_in_usd.get(ChainId::Ethereum.get_currency_symbol()).unwrap();
-
-                let local_chain_currencies = [
-                    UnsavedChainCurrency::new(ChainId::Local, "LocalETH", eth_unit_usd_price),
-                    UnsavedChainCurrency::new(ChainId::LocalAlt, "LocalAltETH", eth_unit_usd_price),
-                ];
-                chain_currencies.extend(local_chain_currencies);
-
-                let testnet_currencies = [UnsavedChainCurrency::new(
-                    ChainId::Sepolia,
-                    "SepoliaETH",
-                    eth_unit_usd_price,
-                )];
-                chain_currencies.extend(testnet_currencies);
-
-                ark_repo::create_or_update_chain_currencies(&mut conn, &chain_currencies).await;
-            }
-
-            interval.tick().await;
-        }
-    });
+        Ok(())
+    }
 }
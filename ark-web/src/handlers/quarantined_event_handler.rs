@@ -0,0 +1,46 @@
+use ark::quarantined_events::QuarantinedEvent;
+use ark_contracts::quarantine::{self, RetryError};
+use ark_web_common::AppState;
+use axum::extract::{Json, Path, State};
+use coinflip_web::app_workers::index_contracts;
+
+use crate::handlers::{self, Error};
+
+/// Unresolved ones, oldest first
+pub async fn get_quarantined_events(
+    State(app_state): State<AppState>,
+) -> Result<Json<Vec<QuarantinedEvent>>, handlers::Error> {
+    let quarantined_events =
+        app_state.repos.quarantined_events.get_unresolved_quarantined_events().await?;
+
+    Ok(Json(quarantined_events))
+}
+
+/// Returns the event with `resolved_at` set if the retry went through
+pub async fn retry_quarantined_event(
+    State(app_state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<QuarantinedEvent>, handlers::Error> {
+    let quarantined_event = app_state
+        .repos
+        .quarantined_events
+        .get_quarantined_event(id)
+        .await?
+        .ok_or(Error::not_found("Quarantined event not found"))?;
+
+    let contracts = index_contracts::get_contracts(&app_state.config, app_state.clock.clone());
+
+    quarantine::retry(
+        &quarantined_event,
+        &contracts,
+        app_state.db_pool,
+        &app_state.chaindexing_repo,
+        app_state.clock.as_ref(),
+    )
+    .await
+    .map(Json)
+    .map_err(|err| match err {
+        RetryError::EventNotFound | RetryError::UnknownEvent => Error::validation(&err.to_string()),
+        RetryError::Unavailable(_) => Error::upstream_unavailable(&err.to_string()),
+    })
+}
@@ -0,0 +1,90 @@
+use ark::sessions::{UnsavedAuthNonce, UnsavedSession};
+use ark_web_common::AppState;
+use axum::extract::{Json, State};
+
+use serde::{Deserialize, Serialize};
+use siwe::{Message, VerificationOpts};
+use time::OffsetDateTime;
+
+use crate::handlers::{self, Error};
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AuthNonceResponse {
+    pub nonce: String,
+    pub expires_at: i64,
+}
+
+pub async fn create_nonce(
+    State(app_state): State<AppState>,
+) -> Result<Json<AuthNonceResponse>, handlers::Error> {
+    let now = app_state.clock.now();
+    let auth_nonce = UnsavedAuthNonce::new(now);
+
+    app_state.repos.sessions.create_auth_nonce(&auth_nonce).await?;
+
+    Ok(Json(AuthNonceResponse {
+        nonce: auth_nonce.nonce,
+        expires_at: auth_nonce.expires_at,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSessionParams {
+    /// An EIP-4361 (Sign-In-With-Ethereum) message
+    message: String,
+    signature: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SessionResponse {
+    pub token: String,
+    pub player_address: String,
+    pub expires_at: i64,
+}
+
+pub async fn create_session(
+    State(app_state): State<AppState>,
+    Json(CreateSessionParams { message, signature }): Json<CreateSessionParams>,
+) -> Result<Json<SessionResponse>, handlers::Error> {
+    let message: Message =
+        message.parse().map_err(|_| Error::validation("Invalid SIWE message"))?;
+    let signature = hex::decode(signature.trim_start_matches("0x"))
+        .map_err(|_| Error::validation("Invalid signature"))?;
+
+    let now = app_state.clock.now();
+    let now_date_time = OffsetDateTime::from_unix_timestamp(now).map_err(Error::internal)?;
+
+    if message.domain.to_string() != app_state.config.siwe_domain {
+        return Err(Error::unauthorized("Invalid SIWE domain"));
+    }
+    if !message.valid_at(&now_date_time) {
+        return Err(Error::unauthorized("SIWE message expired"));
+    }
+
+    let verification_opts = VerificationOpts {
+        timestamp: Some(now_date_time),
+        ..Default::default()
+    };
+
+    message
+        .verify(&signature, &verification_opts)
+        .await
+        .map_err(|_| Error::unauthorized("Invalid signature"))?;
+
+    let sessions = &app_state.repos.sessions;
+
+    if !sessions.use_auth_nonce(&message.nonce, now).await? {
+        return Err(Error::unauthorized("Invalid or used nonce"));
+    }
+
+    let player_address = format!("0x{}", hex::encode(message.address));
+    let (session, token) = UnsavedSession::new(&player_address, now);
+
+    sessions.create_session(&session).await?;
+
+    Ok(Json(SessionResponse {
+        token,
+        player_address: session.player_address,
+        expires_at: session.expires_at,
+    }))
+}
@@ -1,10 +1,9 @@
 use ark_web_common::AppState;
 use axum::extract::{Json, Path, State};
-use http::StatusCode;
 
 use serde::{Deserialize, Serialize};
 
-use crate::handlers;
+use crate::handlers::{self, Error};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WalletResponse {
@@ -18,10 +17,14 @@ pub async fn get_wallet(
     Path((public_address, chain_id)): Path<(String, u64)>,
 ) -> Result<Json<WalletResponse>, handlers::Error> {
     let chain_id = chain_id as i64;
-    let mut conn = handlers::new_conn(app_state.db_pool).await?;
+    let repos = app_state.repos;
 
-    let chain_currency = ark_repo::get_chain_currency(&mut conn, chain_id).await.unwrap();
-    let maybe_wallet = ark_repo::get_wallet(&mut conn, &public_address, chain_id).await;
+    let chain_currency = repos
+        .chain_currencies
+        .get_chain_currency(chain_id)
+        .await?
+        .ok_or(Error::upstream_unavailable("No USD price for chain yet"))?;
+    let maybe_wallet = repos.wallets.get_wallet(&public_address, chain_id).await?;
 
     match maybe_wallet {
         Some(wallet) => {
@@ -33,7 +36,7 @@ pub async fn get_wallet(
                 balance_usd: to_2dp(chain_currency.convert_to_usd(balance_ether)),
             }))
         }
-        None => Err((StatusCode::NOT_FOUND, "Wallet not found".to_string())),
+        None => Err(Error::not_found("Wallet not found")),
     }
 }
 
@@ -0,0 +1,8 @@
+use ark_web_common::workers::WorkerHealth;
+use ark_web_common::AppState;
+use axum::extract::{Json, State};
+
+/// Last run, success and error of every supervised worker
+pub async fn get_workers(State(app_state): State<AppState>) -> Json<Vec<WorkerHealth>> {
+    Json(app_state.worker_healths.get_all())
+}
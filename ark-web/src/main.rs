@@ -1,16 +1,30 @@
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
+use ark::configs::ArkConfig;
+use ark::profiles::Worker;
+use ark_repo::PostgresArkRepo;
+use ark_utils::clocks::{Clock, SystemClock};
 use ark_web::{AppRouter, AppServerConfig};
-use ark_web_common::AppState;
+use ark_web_common::workers::WorkerSupervisor;
+use ark_web_common::{AppState, Repos};
 
-use ark_web::app_workers::cache_chain_unit_currencies_in_usd;
+use ark_web::app_workers::cache_chain_unit_currencies_in_usd::CacheChainUnitCurrenciesInUsd;
 use chaindexing::KeepNodeActiveRequest;
-use coinflip_web::app_workers::{
-    index_contracts, refund_expired_game_players, reveal_game_play_chances,
-};
+use coinflip_repo::PostgresCoinflipRepo;
+use coinflip_web::app_workers::detect_stuck_games::DetectStuckGames;
+use coinflip_web::app_workers::index_contracts::IndexContracts;
+use coinflip_web::app_workers::load_recent_results::LoadRecentResults;
+use coinflip_web::app_workers::refund_expired_game_players::RefundExpiredGamePlayers;
+use coinflip_web::app_workers::reveal_game_play_chances::RevealGamePlayChances;
+use coinflip_web::app_workers::rollback_reorged_game_activities::RollbackReorgedGameActivities;
+use coinflip_web::app_workers::sync_service_charges::SyncServiceCharges;
+use tokio::signal;
 
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// Validates the config, reports the outcome and exits without starting anything
+const CHECK_CONFIG_FLAG: &str = "--check-config";
+
 #[tokio::main]
 async fn main() {
     tracing_subscriber::registry()
@@ -21,41 +35,140 @@ async fn main() {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
-    let db_pool = Arc::new(ark_db::get_pool().await);
+    let config = match ArkConfig::load() {
+        Ok(config) => Arc::new(config),
+        Err(errors) => {
+            eprintln!("{errors}");
+            std::process::exit(1);
+        }
+    };
+
+    if std::env::args().any(|arg| arg == CHECK_CONFIG_FLAG) {
+        println!(
+            "Config OK: {:?} environment with {} chain(s) and {} worker(s)",
+            config.environment,
+            config.chains.len(),
+            config.profile.workers.len()
+        );
+        return;
+    }
+
+    let db_pool = Arc::new(ark_db::get_pool(&config.database_url).await);
     let keep_chaindexing_node_active_request = KeepNodeActiveRequest::new(10 * 60_000);
+    let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+    let repos = Repos::new(
+        Arc::new(PostgresArkRepo::new(db_pool.clone())),
+        Arc::new(PostgresCoinflipRepo::new(db_pool.clone())),
+    );
+    let recent_results: Arc<RwLock<_>> = Arc::default();
 
     // Start Workers
-    index_contracts::start(
-        db_pool.clone(),
-        keep_chaindexing_node_active_request.clone(),
-    );
-    cache_chain_unit_currencies_in_usd::start(db_pool.clone());
-    reveal_game_play_chances::start(
-        db_pool.clone(),
-        keep_chaindexing_node_active_request.clone(),
-    );
-    refund_expired_game_players::start(
-        db_pool.clone(),
-        keep_chaindexing_node_active_request.clone(),
-    );
+    let profile = &config.profile;
+    let mut worker_supervisor = WorkerSupervisor::new(clock.clone());
+
+    if profile.has_worker(Worker::LoadRecentResults) {
+        worker_supervisor.supervise(LoadRecentResults::new(
+            db_pool.clone(),
+            recent_results.clone(),
+        ));
+    }
+    if profile.has_worker(Worker::IndexContracts) {
+        worker_supervisor.supervise(IndexContracts::new(
+            db_pool.clone(),
+            keep_chaindexing_node_active_request.clone(),
+            clock.clone(),
+            config.clone(),
+        ));
+    }
+    if profile.has_worker(Worker::CacheChainUnitCurrenciesInUsd) {
+        worker_supervisor.supervise(CacheChainUnitCurrenciesInUsd::new(
+            db_pool.clone(),
+            config.clone(),
+        ));
+    }
+    if profile.has_worker(Worker::RevealGamePlayChances) {
+        worker_supervisor.supervise(RevealGamePlayChances::new(
+            repos.clone(),
+            keep_chaindexing_node_active_request.clone(),
+            clock.clone(),
+            config.clone(),
+        ));
+    }
+    if profile.has_worker(Worker::RefundExpiredGamePlayers) {
+        worker_supervisor.supervise(RefundExpiredGamePlayers::new(
+            repos.clone(),
+            keep_chaindexing_node_active_request.clone(),
+            clock.clone(),
+            config.clone(),
+        ));
+    }
+    if profile.has_worker(Worker::SyncServiceCharges) {
+        worker_supervisor.supervise(SyncServiceCharges::new(db_pool.clone(), config.clone()));
+    }
+    if profile.has_worker(Worker::DetectStuckGames) {
+        worker_supervisor.supervise(DetectStuckGames::new(
+            repos.clone(),
+            clock.clone(),
+            config.clone(),
+        ));
+    }
+    if profile.has_worker(Worker::RollbackReorgedGameActivities) {
+        worker_supervisor.supervise(RollbackReorgedGameActivities::new(db_pool.clone()));
+    }
 
     // Start Server
-    let config = AppServerConfig::new();
+    let server_config = AppServerConfig::new(&config);
+
+    tracing::info!(
+        "Starting server at http://{}:{}/",
+        server_config.host,
+        server_config.port
+    );
 
-    tracing::info!("Starting server at http://{}:{}/", config.host, config.port);
+    let listener = tokio::net::TcpListener::bind(&server_config.socket_address()).await.unwrap();
 
-    let listener = tokio::net::TcpListener::bind(&config.socket_address()).await.unwrap();
+    let app_state = AppState::new(
+        db_pool,
+        &keep_chaindexing_node_active_request,
+        clock,
+        repos,
+        config,
+    )
+    .with_worker_healths(worker_supervisor.get_healths())
+    .with_recent_results(recent_results);
 
     axum::serve(
         listener,
-        AppRouter::new()
-            .routes
-            .with_state(AppState::new(
-                db_pool,
-                &keep_chaindexing_node_active_request,
-            ))
-            .into_make_service(),
+        AppRouter::new(&app_state).routes.with_state(app_state).into_make_service(),
     )
+    .with_graceful_shutdown(shutdown_signal())
     .await
     .unwrap();
+
+    worker_supervisor.shutdown().await;
+}
+
+/// Ctrl+C locally, SIGTERM when deployed
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c().await.expect("Failed to listen for Ctrl+C");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("Shutting down...");
 }
@@ -0,0 +1,102 @@
+mod in_memory_app;
+
+use ark::sessions::{UnsavedSession, SESSION_TTL_SECS};
+use ark_repo::SessionRepo;
+use ark_web_common::auth::ADMIN_KEY_HEADER;
+use axum::body::Body;
+use http::{Request, StatusCode};
+use in_memory_app::{InMemoryApp, NOW};
+
+const PLAYER_ADDRESS: &str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+const ADMIN_API_KEY: &str = "admin-key";
+
+fn get(uri: &str, header: Option<(&str, &str)>) -> Request<Body> {
+    let request = match header {
+        Some((name, value)) => Request::get(uri).header(name, value),
+        None => Request::get(uri),
+    };
+
+    request.body(Body::empty()).unwrap()
+}
+
+async fn create_session(app: &InMemoryApp) -> String {
+    let (session, token) = UnsavedSession::new(PLAYER_ADDRESS, NOW);
+    app.ark_repo.create_session(&session).await.unwrap();
+
+    format!("Bearer {token}")
+}
+
+#[tokio::test]
+async fn requires_a_session_for_private_views() {
+    let app = InMemoryApp::new(None);
+
+    let (status, body) = app.send(get("/coinflip/players/me/pending_reveals", None)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["message"], "Sign in required");
+
+    let authorization = create_session(&app).await;
+    let request = get(
+        "/coinflip/players/me/pending_reveals",
+        Some(("authorization", &authorization)),
+    );
+    let (status, _) = app.send(request).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn rejects_unknown_and_expired_sessions() {
+    let app = InMemoryApp::new(None);
+
+    let request = get(
+        "/coinflip/feed/recent",
+        Some(("authorization", "Bearer unknown")),
+    );
+    let (status, body) = app.send(request).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["message"], "Invalid or expired session");
+
+    let authorization = create_session(&app).await;
+    app.clock.advance(SESSION_TTL_SECS);
+
+    let request = get(
+        "/coinflip/players/me/pending_reveals",
+        Some(("authorization", &authorization)),
+    );
+    let (status, body) = app.send(request).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["message"], "Invalid or expired session");
+}
+
+#[tokio::test]
+async fn requires_the_admin_key_for_admin_views() {
+    let app = InMemoryApp::new(Some(ADMIN_API_KEY));
+
+    for uri in ["/admin/workers", "/coinflip/admin/reports/house_revenue"] {
+        let (status, body) = app.send(get(uri, None)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["message"], "Admin key required");
+
+        let (status, _) = app.send(get(uri, Some((ADMIN_KEY_HEADER, "wrong-key")))).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let authorization = create_session(&app).await;
+        let (status, _) = app.send(get(uri, Some(("authorization", &authorization)))).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    let (status, _) = app
+        .send(get(
+            "/admin/workers",
+            Some((ADMIN_KEY_HEADER, ADMIN_API_KEY)),
+        ))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn rejects_every_admin_request_without_a_configured_key() {
+    let app = InMemoryApp::new(None);
+
+    let (status, _) = app.send(get("/admin/workers", Some((ADMIN_KEY_HEADER, "")))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,155 @@
+mod in_memory_app;
+
+use axum::body::Body;
+use chrono::{DateTime, SecondsFormat};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::utils::to_checksum;
+use http::{Request, StatusCode};
+use in_memory_app::{InMemoryApp, NOW, SIWE_DOMAIN};
+use serde_json::{json, Value};
+
+const PLAYER_PRIVATE_KEY: &str =
+    "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+struct SiweMessage<'a> {
+    domain: &'a str,
+    nonce: &'a str,
+    issued_at: i64,
+    expires_at: i64,
+}
+
+impl<'a> SiweMessage<'a> {
+    fn new(nonce: &'a str) -> Self {
+        Self {
+            domain: SIWE_DOMAIN,
+            nonce,
+            issued_at: NOW,
+            expires_at: NOW + 300,
+        }
+    }
+
+    async fn sign(&self) -> Value {
+        let wallet: LocalWallet = PLAYER_PRIVATE_KEY.parse().unwrap();
+
+        let message = format!(
+            "{domain} wants you to sign in with your Ethereum account:\n\
+             {address}\n\
+             \n\
+             Sign in to Ark\n\
+             \n\
+             URI: http://{domain}\n\
+             Version: 1\n\
+             Chain ID: 31337\n\
+             Nonce: {nonce}\n\
+             Issued At: {issued_at}\n\
+             Expiration Time: {expires_at}",
+            domain = self.domain,
+            address = to_checksum(&wallet.address(), None),
+            nonce = self.nonce,
+            issued_at = to_rfc3339(self.issued_at),
+            expires_at = to_rfc3339(self.expires_at),
+        );
+        let signature = wallet.sign_message(&message).await.unwrap();
+
+        json!({ "message": message, "signature": format!("0x{signature}") })
+    }
+}
+
+fn to_rfc3339(timestamp: i64) -> String {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap()
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn get_player_address() -> String {
+    let wallet: LocalWallet = PLAYER_PRIVATE_KEY.parse().unwrap();
+
+    format!("{:?}", wallet.address())
+}
+
+fn post(uri: &str, body: Value) -> Request<Body> {
+    Request::post(uri)
+        .header("content-type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+async fn create_nonce(app: &InMemoryApp) -> String {
+    let (status, body) = app.send(post("/sessions/nonce", json!({}))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    body["nonce"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn creates_a_session_for_a_signed_in_player() {
+    let app = InMemoryApp::new(None);
+    let nonce = create_nonce(&app).await;
+
+    let params = SiweMessage::new(&nonce).sign().await;
+    let (status, body) = app.send(post("/sessions", params)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["player_address"], get_player_address());
+
+    let request = Request::get("/coinflip/players/me/pending_reveals")
+        .header(
+            "authorization",
+            format!("Bearer {}", body["token"].as_str().unwrap()),
+        )
+        .body(Body::empty())
+        .unwrap();
+    let (status, _) = app.send(request).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn rejects_nonces_that_were_not_issued_or_already_used() {
+    let app = InMemoryApp::new(None);
+
+    let params = SiweMessage::new("unissuednonce123").sign().await;
+    let (status, body) = app.send(post("/sessions", params)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["message"], "Invalid or used nonce");
+
+    let nonce = create_nonce(&app).await;
+    let params = SiweMessage::new(&nonce).sign().await;
+    let (status, _) = app.send(post("/sessions", params.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = app.send(post("/sessions", params)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["message"], "Invalid or used nonce");
+}
+
+#[tokio::test]
+async fn rejects_expired_messages() {
+    let app = InMemoryApp::new(None);
+    let nonce = create_nonce(&app).await;
+
+    let params = SiweMessage {
+        issued_at: NOW - 600,
+        expires_at: NOW - 60,
+        ..SiweMessage::new(&nonce)
+    }
+    .sign()
+    .await;
+    let (status, body) = app.send(post("/sessions", params)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["message"], "SIWE message expired");
+}
+
+#[tokio::test]
+async fn rejects_messages_for_another_domain() {
+    let app = InMemoryApp::new(None);
+    let nonce = create_nonce(&app).await;
+
+    let params = SiweMessage {
+        domain: "ark.example.com",
+        ..SiweMessage::new(&nonce)
+    }
+    .sign()
+    .await;
+    let (status, body) = app.send(post("/sessions", params)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["message"], "Invalid SIWE domain");
+}
@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 
-use eyre::Result;
+use eyre::{eyre, Result};
 use serde_json::Value;
 
-use crate::{chains::ChainId, CHAIN_AGNOSTIC_MAX_GAS_PRICE_F64, GWEI_F64};
+use crate::{
+    chains::{ChainConfig, ChainId},
+    CHAIN_AGNOSTIC_MAX_GAS_PRICE_F64, GWEI_F64,
+};
 
 #[derive(Clone, Debug)]
 pub struct GasInfo {
@@ -34,20 +37,26 @@ impl GasInfo {
 const POLYSCAN_BASE_API_URL: &'static str = "https://api.polygonscan.com/api";
 const ETHERSCAN_BASE_API_URL: &'static str = "https://api.etherscan.com/api";
 
-pub async fn get_gas_info(chain_id: &ChainId) -> Result<GasInfo> {
+pub async fn get_gas_info(chain_config: &ChainConfig) -> Result<GasInfo> {
+    if let Some(gas_oracle_url) = &chain_config.gas_oracle_url {
+        return fetch_gas_info(gas_oracle_url).await;
+    }
+
+    let chain_id = &chain_config.chain_id;
+
     match chain_id {
         ChainId::Local | ChainId::LocalAlt => Ok(Default::default()),
         ChainId::Polygon => {
             fetch_gas_info(&get_gas_info_api_url(
                 POLYSCAN_BASE_API_URL,
-                &get_explorer_api_key(chain_id),
+                get_explorer_api_key(chain_config)?,
             ))
             .await
         }
         ChainId::Sepolia | ChainId::Ethereum => {
             fetch_gas_info(&get_gas_info_api_url(
                 ETHERSCAN_BASE_API_URL,
-                &get_explorer_api_key(chain_id),
+                get_explorer_api_key(chain_config)?,
             ))
             .await
         }
@@ -72,16 +81,11 @@ fn get_gas_info_api_url(base_api_url: &str, api_key: &str) -> String {
     format!("{base_api_url}?module=gastracker&action=gasoracle&apiKey={api_key}")
 }
 
-fn get_explorer_api_key(chain_id: &ChainId) -> String {
-    dotenvy::dotenv().ok();
-
-    match chain_id {
-        ChainId::Sepolia | ChainId::Ethereum => {
-            std::env::var("ETHERSCAN_API_KEY").expect("ETHERSCAN_API_KEY must be set")
-        }
-        ChainId::Polygon => {
-            std::env::var("POLYSCAN_API_KEY").expect("POLYSCAN_API_KEY must be set")
-        }
-        _ => unimplemented!(),
-    }
+fn get_explorer_api_key(chain_config: &ChainConfig) -> Result<&str> {
+    chain_config.explorer_api_key.as_deref().ok_or_else(|| {
+        eyre!(
+            "Chain:{} has no explorer_api_key",
+            chain_config.chain_id as u64
+        )
+    })
 }
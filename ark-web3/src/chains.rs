@@ -1,6 +1,10 @@
+use std::collections::HashMap;
+
 use ark_db::schema::ark_chain_currencies;
 
 use diesel::prelude::{Insertable, Queryable};
+use ethers::signers::LocalWallet;
+use ethers::types::Address;
 use strum_macros::EnumIter;
 
 #[derive(Debug, Hash, EnumIter, PartialEq, Eq, Copy, Clone)]
@@ -31,6 +35,15 @@ impl From<u64> for ChainId {
     }
 }
 
+/// Local chains are priced with Ethereum's currency
+pub fn get_currency_chain_id(chain_id: i64) -> i64 {
+    if chain_id == ChainId::Local as i64 || chain_id == ChainId::LocalAlt as i64 {
+        ChainId::Ethereum as i64
+    } else {
+        chain_id
+    }
+}
+
 impl From<chaindexing::ChainId> for ChainId {
     fn from(value: chaindexing::ChainId) -> Self {
         match value {
@@ -46,6 +59,20 @@ impl From<chaindexing::ChainId> for ChainId {
     }
 }
 
+impl From<ChainId> for chaindexing::ChainId {
+    fn from(value: ChainId) -> Self {
+        match value {
+            ChainId::Arbitrum => chaindexing::ChainId::Arbitrum,
+            ChainId::Avalanche => chaindexing::ChainId::Avalanche,
+            ChainId::Ethereum => chaindexing::ChainId::Mainnet,
+            ChainId::Local | ChainId::LocalAlt => chaindexing::ChainId::Dev,
+            ChainId::Optimism => chaindexing::ChainId::Optimism,
+            ChainId::Polygon => chaindexing::ChainId::Polygon,
+            ChainId::Sepolia => chaindexing::ChainId::Sepolia,
+        }
+    }
+}
+
 impl ChainId {
     pub fn get_currency_symbol(&self) -> &'static str {
         match self {
@@ -59,30 +86,8 @@ impl ChainId {
         }
     }
 
-    pub fn get_contract_address(&self, contract_name: &str) -> String {
-        dotenvy::dotenv().ok();
-
-        let contract_name = &contract_name.to_uppercase();
-        let chain_env_namespace = self.get_env_namespace();
-        let env_var = &format!("{chain_env_namespace}_{contract_name}_CONTRACT_ADDRESS");
-
-        std::env::var(env_var).expect(&format!("{env_var} must be set"))
-    }
-
-    pub fn get_start_block_number(&self, contract_name: &str) -> i64 {
-        dotenvy::dotenv().ok();
-
-        let contract_name = &contract_name.to_uppercase();
-        let chain_env_namespace = self.get_env_namespace();
-        let env_var = &format!("{chain_env_namespace}_{contract_name}_START_BLOCK_NUMBER");
-
-        std::env::var(env_var)
-            .expect(&format!("{env_var} must be set"))
-            .parse()
-            .unwrap()
-    }
-
-    fn get_env_namespace(&self) -> &'static str {
+    /// Prefixes the chain's env vars e.g. `SEPOLIA_JSON_RPC_URL`
+    pub fn get_env_namespace(&self) -> &'static str {
         match self {
             ChainId::Arbitrum => "ARBITRUM",
             ChainId::Avalanche => "AVALANCHE",
@@ -94,6 +99,14 @@ impl ChainId {
         }
     }
 
+    /// Whether gas prices come from an explorer API e.g. Etherscan
+    pub fn has_explorer(&self) -> bool {
+        matches!(
+            self,
+            ChainId::Ethereum | ChainId::Polygon | ChainId::Sepolia
+        )
+    }
+
     pub fn from_currency_symbol(currency_symbol: &str) -> ChainId {
         match currency_symbol {
             "ARB" => ChainId::Arbitrum,
@@ -108,8 +121,82 @@ impl ChainId {
     }
 }
 
-pub fn get_test_nets() -> Vec<ChainId> {
-    vec![ChainId::Local, ChainId::LocalAlt, ChainId::Sepolia]
+/// Everything needed to index and transact on a chain, loaded once at boot
+#[derive(Clone, Debug)]
+pub struct ChainConfig {
+    pub chain_id: ChainId,
+    pub json_rpc_url: String,
+    pub private_key: String,
+    /// e.g. a mock gas oracle, used instead of the chain's explorer when set
+    pub gas_oracle_url: Option<String>,
+    pub explorer_api_key: Option<String>,
+    /// By lowercase contract name e.g. `coinflip`
+    pub contracts: HashMap<String, ContractConfig>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ContractConfig {
+    pub address: String,
+    pub start_block_number: i64,
+}
+
+impl ChainConfig {
+    pub fn get_contract(&self, contract_name: &str) -> Option<&ContractConfig> {
+        self.contracts.get(&contract_name.to_lowercase())
+    }
+
+    /// Panics for contracts missing from the config, which boot validation rules out
+    pub fn get_contract_address(&self, contract_name: &str) -> &str {
+        match self.get_contract(contract_name) {
+            Some(contract) => &contract.address,
+            None => panic!(
+                "Contract:{contract_name} is not configured for Chain:{}",
+                self.chain_id as u64
+            ),
+        }
+    }
+
+    /// Values that are present but unusable, since missing ones never make it into a `ChainConfig`
+    pub fn get_problems(&self) -> Vec<String> {
+        let mut problems = vec![];
+
+        if reqwest::Url::parse(&self.json_rpc_url).is_err() {
+            problems.push(format!("json_rpc_url `{}` is not a URL", self.json_rpc_url));
+        }
+
+        if self.private_key.parse::<LocalWallet>().is_err() {
+            problems.push("private_key is not a valid private key".to_string());
+        }
+
+        if let Some(gas_oracle_url) = &self.gas_oracle_url {
+            if reqwest::Url::parse(gas_oracle_url).is_err() {
+                problems.push(format!("gas_oracle_url `{gas_oracle_url}` is not a URL"));
+            }
+        } else if self.chain_id.has_explorer() && self.explorer_api_key.is_none() {
+            problems.push("explorer_api_key must be set when gas_oracle_url isn't".to_string());
+        }
+
+        let mut contract_names: Vec<_> = self.contracts.keys().collect();
+        contract_names.sort();
+
+        for contract_name in contract_names {
+            let contract = &self.contracts[contract_name];
+
+            if contract.address.parse::<Address>().is_err() {
+                problems.push(format!(
+                    "contracts.{contract_name}.address `{}` is not an address",
+                    contract.address
+                ));
+            }
+            if contract.start_block_number < 0 {
+                problems.push(format!(
+                    "contracts.{contract_name}.start_block_number must not be negative"
+                ));
+            }
+        }
+
+        problems
+    }
 }
 
 #[derive(Clone, Debug, Insertable)]
@@ -132,6 +219,16 @@ impl UnsavedChainCurrency {
             unit_usd_price: unit_usd_price.to_string(),
         }
     }
+
+    /// For stores that assign ids themselves e.g. in-memory repos
+    pub fn to_saved(&self, id: i32) -> ChainCurrency {
+        ChainCurrency {
+            _id: id,
+            chain_id: self.chain_id,
+            currency_symbol: self.currency_symbol.clone(),
+            unit_usd_price: self.unit_usd_price.clone(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Queryable)]
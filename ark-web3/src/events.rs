@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use chaindexing::{utils::address_to_string, Event};
+use ethers::abi::Token;
+use ethers::types::U256;
+
+/// A contract event decoded from its chaindexing `Event`.
+/// `SIGNATURE` is what the event is registered with in `Contract::add_event`.
+pub trait TypedEvent: Sized {
+    const NAME: &'static str;
+    const SIGNATURE: &'static str;
+
+    fn decode(params: &EventParams) -> Result<Self, EventDecodeError>;
+
+    fn from_event(event: &Event) -> Result<Self, EventDecodeError> {
+        Self::decode(&EventParams::new(Self::NAME, event))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EventDecodeError {
+    MissingParam {
+        event_name: &'static str,
+        param_name: &'static str,
+    },
+    InvalidParam {
+        event_name: &'static str,
+        param_name: &'static str,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for EventDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventDecodeError::MissingParam {
+                event_name,
+                param_name,
+            } => write!(f, "{event_name} is missing {param_name}"),
+            EventDecodeError::InvalidParam {
+                event_name,
+                param_name,
+                expected,
+            } => write!(f, "{event_name}'s {param_name} is not a valid {expected}"),
+        }
+    }
+}
+
+impl std::error::Error for EventDecodeError {}
+
+#[derive(Debug)]
+pub enum EventHandlerError {
+    Decode(EventDecodeError),
+    /// A state the event builds on hasn't been indexed e.g. a play for an unknown game
+    MissingState {
+        state_name: &'static str,
+        id: String,
+    },
+    /// The event doesn't apply to the state it builds on e.g. a replayed or reordered event
+    InvalidState(String),
+    /// e.g. no DB connection
+    Unavailable(String),
+    /// Caught so a bug in one handler quarantines its event instead of halting indexing
+    Panicked(String),
+}
+
+impl EventHandlerError {
+    pub fn missing_state(state_name: &'static str, id: impl ToString) -> Self {
+        EventHandlerError::MissingState {
+            state_name,
+            id: id.to_string(),
+        }
+    }
+    pub fn invalid_state(err: impl std::error::Error) -> Self {
+        EventHandlerError::InvalidState(err.to_string())
+    }
+    pub fn unavailable(err: impl std::error::Error) -> Self {
+        EventHandlerError::Unavailable(err.to_string())
+    }
+}
+
+impl From<EventDecodeError> for EventHandlerError {
+    fn from(err: EventDecodeError) -> Self {
+        EventHandlerError::Decode(err)
+    }
+}
+
+impl fmt::Display for EventHandlerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EventHandlerError::Decode(err) => write!(f, "{err}"),
+            EventHandlerError::MissingState { state_name, id } => {
+                write!(f, "{state_name}:{id} has not been indexed")
+            }
+            EventHandlerError::InvalidState(reason) => write!(f, "Invalid state: {reason}"),
+            EventHandlerError::Unavailable(reason) => write!(f, "Unavailable: {reason}"),
+            EventHandlerError::Panicked(message) => write!(f, "Panicked: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for EventHandlerError {}
+
+/// Checked accessors over an event's params
+pub struct EventParams {
+    event_name: &'static str,
+    params: HashMap<String, Token>,
+}
+
+impl EventParams {
+    pub fn new(event_name: &'static str, event: &Event) -> Self {
+        Self {
+            event_name,
+            params: event.get_params(),
+        }
+    }
+
+    pub fn get_uint(&self, param_name: &'static str) -> Result<U256, EventDecodeError> {
+        self.get(param_name)?
+            .clone()
+            .into_uint()
+            .ok_or_else(|| self.invalid_param(param_name, "uint"))
+    }
+    /// Decimal e.g. wei amounts
+    pub fn get_uint_string(&self, param_name: &'static str) -> Result<String, EventDecodeError> {
+        self.get_uint(param_name).map(|value| value.to_string())
+    }
+    pub fn get_u64(&self, param_name: &'static str) -> Result<u64, EventDecodeError> {
+        let value = self.get_uint(param_name)?;
+
+        (value <= U256::from(u64::MAX))
+            .then(|| value.as_u64())
+            .ok_or_else(|| self.invalid_param(param_name, "u64"))
+    }
+    pub fn get_u32(&self, param_name: &'static str) -> Result<u32, EventDecodeError> {
+        self.get_u64(param_name)?
+            .try_into()
+            .map_err(|_| self.invalid_param(param_name, "u32"))
+    }
+    pub fn get_u16(&self, param_name: &'static str) -> Result<u16, EventDecodeError> {
+        self.get_u64(param_name)?
+            .try_into()
+            .map_err(|_| self.invalid_param(param_name, "u16"))
+    }
+    pub fn get_u8(&self, param_name: &'static str) -> Result<u8, EventDecodeError> {
+        self.get_u64(param_name)?
+            .try_into()
+            .map_err(|_| self.invalid_param(param_name, "u8"))
+    }
+    /// Lowercased
+    pub fn get_address(&self, param_name: &'static str) -> Result<String, EventDecodeError> {
+        self.get(param_name)?
+            .clone()
+            .into_address()
+            .map(|address| address_to_string(&address).to_lowercase())
+            .ok_or_else(|| self.invalid_param(param_name, "address"))
+    }
+    /// Hex encoded without a `0x` prefix, for both `bytes` and `bytesN`
+    pub fn get_bytes_hex(&self, param_name: &'static str) -> Result<String, EventDecodeError> {
+        match self.get(param_name)? {
+            Token::Bytes(bytes) | Token::FixedBytes(bytes) => Ok(hex::encode(bytes)),
+            _ => Err(self.invalid_param(param_name, "bytes")),
+        }
+    }
+
+    fn get(&self, param_name: &'static str) -> Result<&Token, EventDecodeError> {
+        self.params.get(param_name).ok_or(EventDecodeError::MissingParam {
+            event_name: self.event_name,
+            param_name,
+        })
+    }
+    fn invalid_param(&self, param_name: &'static str, expected: &'static str) -> EventDecodeError {
+        EventDecodeError::InvalidParam {
+            event_name: self.event_name,
+            param_name,
+            expected,
+        }
+    }
+}
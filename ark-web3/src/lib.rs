@@ -1,6 +1,6 @@
 pub mod chain_explorers;
 pub mod chains;
-pub mod json_rpcs;
+pub mod events;
 pub mod wallets;
 
 pub const GWEI: u64 = 1000000000;
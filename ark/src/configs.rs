@@ -0,0 +1,448 @@
+//! Loaded once at boot from an optional TOML file, with env vars layered on top.
+//!
+//! Env vars keep the names Ark has always read e.g. `DATABASE_URL` or
+//! `SEPOLIA_COINFLIP_CONTRACT_ADDRESS`, so a `.env` on its own is still a complete config.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use ark_web3::chains::{ChainConfig, ChainId, ContractConfig};
+use serde::Deserialize;
+
+use crate::environments::Environment;
+use crate::profiles::Profile;
+
+pub const CONFIG_FILE_ENV_VAR: &str = "ARK_CONFIG_FILE";
+/// Read when `ARK_CONFIG_FILE` is not set, and skipped if it doesn't exist
+pub const DEFAULT_CONFIG_FILE: &str = "ark.toml";
+
+pub const DEFAULT_HOST: &str = "127.0.0.1";
+pub const DEFAULT_PORT: u16 = 4446;
+pub const DEFAULT_STUCK_GAME_THRESHOLD_SECS: i64 = 10 * 60;
+
+#[derive(Clone, Debug)]
+pub struct ArkConfig {
+    pub environment: Environment,
+    /// The environment's chains, contracts, workers and price sources
+    pub profile: Profile,
+    pub database_url: String,
+    pub server: ServerConfig,
+    pub frontend_origin: String,
+    /// Host the frontend's Sign-In-With-Ethereum messages are issued for
+    pub siwe_domain: String,
+    /// Admin endpoints reject every request without it
+    pub admin_api_key: Option<String>,
+    /// Encrypts players' submitted chances at rest, given as 32 bytes hex
+    pub chance_encryption_key: [u8; 32],
+    /// How long a filled game can wait on reveals before its missing players get reminded
+    pub stuck_game_threshold_secs: i64,
+    pub chaindexing_reset_count: u64,
+    /// One for each of the profile's chains
+    pub chains: Vec<ChainConfig>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+/// Every problem found while loading, so they can all be fixed in one go
+#[derive(Debug)]
+pub struct ConfigErrors(pub Vec<String>);
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid config, {} problem(s):", self.0.len())?;
+
+        for problem in self.0.iter() {
+            writeln!(f, "  - {problem}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+impl ArkConfig {
+    /// Reads `.env`, then the TOML file at `ARK_CONFIG_FILE` or `ark.toml`, then env overrides
+    pub fn load() -> Result<Self, ConfigErrors> {
+        dotenvy::dotenv().ok();
+
+        let env: HashMap<String, String> = std::env::vars().collect();
+
+        let file_contents = match env.get(CONFIG_FILE_ENV_VAR) {
+            Some(path) => Some(std::fs::read_to_string(path).map_err(|err| {
+                ConfigErrors(vec![format!("Could not read config file {path}: {err}")])
+            })?),
+            None => std::fs::read_to_string(DEFAULT_CONFIG_FILE).ok(),
+        };
+
+        Self::parse(file_contents.as_deref(), &env)
+    }
+
+    /// Env vars win over the file's values. Doesn't touch the process env, so tests can use it.
+    pub fn parse(
+        file_contents: Option<&str>,
+        env: &HashMap<String, String>,
+    ) -> Result<Self, ConfigErrors> {
+        let mut layers = Layers {
+            env,
+            problems: vec![],
+        };
+
+        let file = match file_contents.map(toml::from_str::<ConfigFile>) {
+            Some(Ok(file)) => file,
+            Some(Err(err)) => {
+                layers.problems.push(format!("Config file is invalid: {}", err.message()));
+                ConfigFile::default()
+            }
+            None => ConfigFile::default(),
+        };
+
+        let environment = match layers
+            .require("environment", "ARK_ENV", file.environment)
+            .map(|environment| environment.parse::<Environment>())
+        {
+            Some(Ok(environment)) => Some(environment),
+            Some(Err(problem)) => {
+                layers.problems.push(problem);
+                None
+            }
+            None => None,
+        };
+
+        let database_url = layers.require("database_url", "DATABASE_URL", file.database_url);
+        if let Some(database_url) = &database_url {
+            if !database_url.starts_with("postgres://")
+                && !database_url.starts_with("postgresql://")
+            {
+                layers.problems.push("database_url must be a postgres:// URL".to_string());
+            }
+        }
+
+        let server = ServerConfig {
+            host: layers.get("HOST", file.server.host).unwrap_or_else(|| DEFAULT_HOST.to_string()),
+            port: layers.get_number("PORT", file.server.port).unwrap_or(DEFAULT_PORT),
+        };
+
+        let frontend_origin =
+            layers.require("frontend_origin", "FRONTEND_ORIGIN", file.frontend_origin);
+        if let Some(frontend_origin) = &frontend_origin {
+            let is_http =
+                frontend_origin.starts_with("http://") || frontend_origin.starts_with("https://");
+
+            // Also sent back as a header by the CORS layer
+            if !is_http || !frontend_origin.chars().all(|c| c.is_ascii_graphic()) {
+                layers.problems.push(format!(
+                    "frontend_origin `{frontend_origin}` must be an http:// or https:// origin"
+                ));
+            }
+        }
+
+        let siwe_domain = layers.require("siwe_domain", "SIWE_DOMAIN", file.siwe_domain);
+        let admin_api_key = layers
+            .get("ADMIN_API_KEY", file.admin_api_key)
+            .filter(|admin_api_key| !admin_api_key.is_empty());
+
+        let chance_encryption_key = layers
+            .require(
+                "chance_encryption_key",
+                "CHANCE_ENCRYPTION_KEY",
+                file.chance_encryption_key,
+            )
+            .and_then(|chance_encryption_key| {
+                let chance_encryption_key = parse_32_bytes_hex(&chance_encryption_key);
+                if chance_encryption_key.is_none() {
+                    layers.problems.push("chance_encryption_key must be 32 bytes hex".to_string());
+                }
+                chance_encryption_key
+            });
+
+        let stuck_game_threshold_secs = layers
+            .get_number("STUCK_GAME_THRESHOLD_SECS", file.stuck_game_threshold_secs)
+            .unwrap_or(DEFAULT_STUCK_GAME_THRESHOLD_SECS);
+        if stuck_game_threshold_secs <= 0 {
+            layers.problems.push("stuck_game_threshold_secs must be positive".to_string());
+        }
+
+        let chaindexing_reset_count = layers
+            .get_number("CHAINDEXING_RESET_COUNT", file.chaindexing_reset_count)
+            .unwrap_or(0);
+
+        let profile = environment.as_ref().map(Profile::get);
+
+        let mut chain_files = file.chains;
+        let chains: Vec<_> = profile
+            .iter()
+            .flat_map(|profile| profile.chains.iter().map(move |chain_id| (profile, chain_id)))
+            .filter_map(|(profile, chain_id)| {
+                let chain_file = chain_files.remove(&get_chain_key(chain_id)).unwrap_or_default();
+
+                layers.get_chain(*chain_id, chain_file, &profile.contracts)
+            })
+            .collect();
+
+        match (
+            environment,
+            profile,
+            database_url,
+            frontend_origin,
+            siwe_domain,
+            chance_encryption_key,
+        ) {
+            (
+                Some(environment),
+                Some(profile),
+                Some(database_url),
+                Some(frontend_origin),
+                Some(siwe_domain),
+                Some(chance_encryption_key),
+            ) if layers.problems.is_empty() => Ok(ArkConfig {
+                environment,
+                profile,
+                database_url,
+                server,
+                frontend_origin,
+                siwe_domain,
+                admin_api_key,
+                chance_encryption_key,
+                stuck_game_threshold_secs,
+                chaindexing_reset_count,
+                chains,
+            }),
+            _ => Err(ConfigErrors(layers.problems)),
+        }
+    }
+
+    /// Matches by env namespace, so `LocalAlt` gets `Local`'s config
+    pub fn get_chain(&self, chain_id: &ChainId) -> Option<&ChainConfig> {
+        self.chains.iter().find(|chain_config| {
+            chain_config.chain_id.get_env_namespace() == chain_id.get_env_namespace()
+        })
+    }
+
+    /// Where the contract is deployed on each of the environment's chains
+    pub fn get_contract_deployments(&self, contract_name: &str) -> Vec<(ChainId, &ContractConfig)> {
+        self.chains
+            .iter()
+            .filter_map(|chain_config| {
+                chain_config
+                    .get_contract(contract_name)
+                    .map(|contract| (chain_config.chain_id, contract))
+            })
+            .collect()
+    }
+}
+
+/// e.g. `chains.sepolia` in the config file
+fn get_chain_key(chain_id: &ChainId) -> String {
+    chain_id.get_env_namespace().to_lowercase()
+}
+
+/// Explorer keys used to be shared across the chains on the same explorer
+fn get_legacy_explorer_api_key_env_var(chain_id: &ChainId) -> Option<&'static str> {
+    match chain_id {
+        ChainId::Ethereum | ChainId::Sepolia => Some("ETHERSCAN_API_KEY"),
+        ChainId::Polygon => Some("POLYSCAN_API_KEY"),
+        _ => None,
+    }
+}
+
+fn parse_32_bytes_hex(value: &str) -> Option<[u8; 32]> {
+    hex::decode(value.trim_start_matches("0x")).ok()?.try_into().ok()
+}
+
+struct Layers<'a> {
+    env: &'a HashMap<String, String>,
+    problems: Vec<String>,
+}
+
+impl<'a> Layers<'a> {
+    /// Empty env vars count as unset, as they would in a `.env` with a blank value
+    fn get(&self, env_var: &str, file_value: Option<String>) -> Option<String> {
+        self.get_env(env_var).map(|value| value.to_string()).or(file_value)
+    }
+
+    fn require(&mut self, key: &str, env_var: &str, file_value: Option<String>) -> Option<String> {
+        let value = self.get(env_var, file_value);
+
+        if value.is_none() {
+            self.problems.push(format!(
+                "{key} must be set, in the config file or as {env_var}"
+            ));
+        }
+
+        value
+    }
+
+    fn get_number<T: FromStr>(&mut self, env_var: &str, file_value: Option<T>) -> Option<T> {
+        match self.get_env(env_var).map(|value| (value, value.parse())) {
+            Some((_, Ok(number))) => Some(number),
+            Some((value, Err(_))) => {
+                self.problems.push(format!("{env_var} `{value}` is not a valid number"));
+                None
+            }
+            None => file_value,
+        }
+    }
+
+    fn get_env(&self, env_var: &str) -> Option<&'a str> {
+        self.env
+            .get(env_var)
+            .map(|value| value.as_str())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn get_chain(
+        &mut self,
+        chain_id: ChainId,
+        chain_file: ChainFile,
+        contract_names: &[&str],
+    ) -> Option<ChainConfig> {
+        let key = format!("chains.{}", get_chain_key(&chain_id));
+        let namespace = chain_id.get_env_namespace();
+
+        let json_rpc_url = self.require(
+            &format!("{key}.json_rpc_url"),
+            &format!("{namespace}_JSON_RPC_URL"),
+            chain_file.json_rpc_url,
+        );
+        let private_key = self.require(
+            &format!("{key}.private_key"),
+            &format!("{namespace}_PRIVATE_KEY"),
+            chain_file.private_key,
+        );
+        let gas_oracle_url = self.get(
+            &format!("{namespace}_GAS_ORACLE_URL"),
+            chain_file.gas_oracle_url,
+        );
+        let explorer_api_key = self
+            .get(
+                &format!("{namespace}_EXPLORER_API_KEY"),
+                chain_file.explorer_api_key,
+            )
+            .or_else(|| {
+                get_legacy_explorer_api_key_env_var(&chain_id)
+                    .and_then(|env_var| self.get(env_var, None))
+            });
+
+        let mut contract_files = chain_file.contracts;
+        let mut contracts = HashMap::new();
+
+        for contract_name in contract_names.iter().copied() {
+            let contract_file = contract_files.remove(contract_name).unwrap_or_default();
+            let contract_key = format!("{key}.contracts.{contract_name}");
+            let env_var_prefix = format!("{namespace}_{}", contract_name.to_uppercase());
+
+            let address = self.require(
+                &format!("{contract_key}.address"),
+                &format!("{env_var_prefix}_CONTRACT_ADDRESS"),
+                contract_file.address,
+            );
+
+            let start_block_number_env_var = format!("{env_var_prefix}_START_BLOCK_NUMBER");
+            let start_block_number = match self.get_number(
+                &start_block_number_env_var,
+                contract_file.start_block_number,
+            ) {
+                Some(start_block_number) => Some(start_block_number),
+                // Local chains are always indexed from genesis
+                None if chain_id == ChainId::Local => Some(0),
+                None => {
+                    self.problems.push(format!(
+                            "{contract_key}.start_block_number must be set, in the config file or as {start_block_number_env_var}"
+                        ));
+                    None
+                }
+            };
+
+            if let (Some(address), Some(start_block_number)) = (address, start_block_number) {
+                contracts.insert(
+                    contract_name.to_string(),
+                    ContractConfig {
+                        address,
+                        start_block_number,
+                    },
+                );
+            }
+        }
+
+        let mut unknown_contract_names: Vec<_> = contract_files.into_keys().collect();
+        unknown_contract_names.sort();
+        for contract_name in unknown_contract_names {
+            self.problems.push(format!(
+                "{key}.contracts.{contract_name} is not a known contract"
+            ));
+        }
+
+        let (Some(json_rpc_url), Some(private_key)) = (json_rpc_url, private_key) else {
+            return None;
+        };
+        if contracts.len() != contract_names.len() {
+            return None;
+        }
+
+        let chain_config = ChainConfig {
+            chain_id,
+            json_rpc_url,
+            private_key,
+            gas_oracle_url,
+            explorer_api_key,
+            contracts,
+        };
+
+        self.problems.extend(
+            chain_config
+                .get_problems()
+                .into_iter()
+                .map(|problem| format!("{key}.{problem}")),
+        );
+
+        Some(chain_config)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    environment: Option<String>,
+    database_url: Option<String>,
+    #[serde(default)]
+    server: ServerFile,
+    frontend_origin: Option<String>,
+    siwe_domain: Option<String>,
+    admin_api_key: Option<String>,
+    chance_encryption_key: Option<String>,
+    stuck_game_threshold_secs: Option<i64>,
+    chaindexing_reset_count: Option<u64>,
+    #[serde(default)]
+    chains: HashMap<String, ChainFile>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ServerFile {
+    host: Option<String>,
+    port: Option<u16>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChainFile {
+    json_rpc_url: Option<String>,
+    private_key: Option<String>,
+    gas_oracle_url: Option<String>,
+    explorer_api_key: Option<String>,
+    #[serde(default)]
+    contracts: HashMap<String, ContractFile>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ContractFile {
+    address: Option<String>,
+    start_block_number: Option<i64>,
+}
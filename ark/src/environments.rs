@@ -1,17 +1,23 @@
-#[derive(Debug, PartialEq)]
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Environment {
     Local,
     Staging,
     Production,
 }
 
-impl From<String> for Environment {
-    fn from(value: String) -> Self {
-        match value.as_str() {
-            "local" => Environment::Local,
-            "staging" => Environment::Staging,
-            "production" => Environment::Production,
-            _ => panic!("Invalid Environment"),
+impl FromStr for Environment {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "local" => Ok(Environment::Local),
+            "staging" => Ok(Environment::Staging),
+            "production" => Ok(Environment::Production),
+            _ => Err(format!(
+                "Invalid environment `{value}`, expected local, staging or production"
+            )),
         }
     }
 }
@@ -27,9 +33,3 @@ impl Environment {
         *self == Environment::Production
     }
 }
-
-pub fn current() -> Environment {
-    dotenvy::dotenv().ok();
-
-    std::env::var("ARK_ENV").expect("ARK_ENV must be set").into()
-}
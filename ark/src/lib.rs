@@ -1,3 +1,7 @@
+pub mod configs;
 pub mod environments;
+pub mod profiles;
+pub mod quarantined_events;
+pub mod sessions;
 pub mod total_paid_out_report;
 pub mod wallets;
@@ -0,0 +1,121 @@
+use ark_web3::chains::ChainId;
+
+use crate::environments::Environment;
+
+/// What a deployment runs, so environments differ by data instead of branching
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    /// Indexed and transacted on, each needs a `[chains.*]` config
+    pub chains: Vec<ChainId>,
+    /// Lowercase contract names, deployed on every one of the profile's chains
+    pub contracts: Vec<&'static str>,
+    pub workers: Vec<Worker>,
+    pub price_sources: Vec<PriceSource>,
+}
+
+impl Profile {
+    pub fn get(environment: &Environment) -> Self {
+        match environment {
+            Environment::Local => Profile {
+                chains: vec![ChainId::Local],
+                contracts: vec!["coinflip", "wallets"],
+                workers: Worker::all(),
+                price_sources: vec![
+                    PriceSource::pegged(ChainId::Local, ChainId::Ethereum),
+                    PriceSource::pegged(ChainId::LocalAlt, ChainId::Ethereum),
+                ],
+            },
+            Environment::Staging => Profile {
+                chains: vec![ChainId::Sepolia],
+                contracts: vec!["coinflip", "wallets"],
+                workers: Worker::all(),
+                price_sources: vec![PriceSource::pegged(ChainId::Sepolia, ChainId::Ethereum)],
+            },
+            Environment::Production => Profile {
+                chains: vec![ChainId::Sepolia, ChainId::Polygon, ChainId::Ethereum],
+                contracts: vec!["coinflip", "wallets"],
+                workers: Worker::all(),
+                price_sources: vec![
+                    PriceSource::pegged(ChainId::Sepolia, ChainId::Ethereum),
+                    PriceSource::quoted(ChainId::Polygon),
+                    PriceSource::quoted(ChainId::Ethereum),
+                ],
+            },
+        }
+    }
+
+    pub fn has_contract(&self, contract_name: &str) -> bool {
+        self.contracts.contains(&contract_name.to_lowercase().as_str())
+    }
+
+    pub fn has_worker(&self, worker: Worker) -> bool {
+        self.workers.contains(&worker)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Worker {
+    LoadRecentResults,
+    IndexContracts,
+    CacheChainUnitCurrenciesInUsd,
+    RevealGamePlayChances,
+    RefundExpiredGamePlayers,
+    SyncServiceCharges,
+    DetectStuckGames,
+    RollbackReorgedGameActivities,
+}
+
+impl Worker {
+    pub fn all() -> Vec<Worker> {
+        vec![
+            Worker::LoadRecentResults,
+            Worker::IndexContracts,
+            Worker::CacheChainUnitCurrenciesInUsd,
+            Worker::RevealGamePlayChances,
+            Worker::RefundExpiredGamePlayers,
+            Worker::SyncServiceCharges,
+            Worker::DetectStuckGames,
+            Worker::RollbackReorgedGameActivities,
+        ]
+    }
+
+    /// As used in the workers' log prefixes e.g. `[SyncServiceCharges]`
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Worker::LoadRecentResults => "LoadRecentResults",
+            Worker::IndexContracts => "IndexContracts",
+            Worker::CacheChainUnitCurrenciesInUsd => "CacheChainUnitCurrenciesInUsd",
+            Worker::RevealGamePlayChances => "RevealGamePlayChances",
+            Worker::RefundExpiredGamePlayers => "RefundExpiredGamePlayers",
+            Worker::SyncServiceCharges => "SyncServiceCharges",
+            Worker::DetectStuckGames => "DetectStuckGames",
+            Worker::RollbackReorgedGameActivities => "RollbackReorgedGameActivities",
+        }
+    }
+}
+
+/// Where a chain's currency gets its USD price from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PriceSource {
+    pub chain_id: ChainId,
+    /// The chain whose currency's CryptoCompare quote is used
+    pub quoted_chain_id: ChainId,
+}
+
+impl PriceSource {
+    /// Quoted under the chain's own currency symbol
+    pub fn quoted(chain_id: ChainId) -> Self {
+        Self {
+            chain_id,
+            quoted_chain_id: chain_id,
+        }
+    }
+
+    /// For currencies without a market e.g. testnet ETH, priced as the currency they stand in for
+    pub fn pegged(chain_id: ChainId, quoted_chain_id: ChainId) -> Self {
+        Self {
+            chain_id,
+            quoted_chain_id,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use ark_db::schema::ark_quarantined_events;
+use diesel::prelude::{Insertable, Queryable};
+
+use serde::{Deserialize, Serialize};
+
+/// An indexed event whose handler failed.
+/// Indexing carries on past it until it's retried from the admin endpoint.
+#[derive(Clone, Debug, Serialize, Deserialize, Queryable)]
+#[diesel(table_name = ark_quarantined_events)]
+pub struct QuarantinedEvent {
+    pub id: i64,
+    /// The chaindexing event's id
+    pub event_id: String,
+    pub chain_id: i64,
+    pub contract_address: String,
+    pub contract_name: String,
+    pub event_abi: String,
+    pub handler_name: String,
+    pub transaction_hash: String,
+    pub block_number: i64,
+    pub log_index: i32,
+    pub payload: serde_json::Value,
+    pub error: String,
+    pub failure_count: i32,
+    pub first_failed_at: i64,
+    pub last_failed_at: i64,
+    pub resolved_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Insertable)]
+#[diesel(table_name = ark_quarantined_events)]
+pub struct UnsavedQuarantinedEvent {
+    pub event_id: String,
+    pub chain_id: i64,
+    pub contract_address: String,
+    pub contract_name: String,
+    pub event_abi: String,
+    pub handler_name: String,
+    pub transaction_hash: String,
+    pub block_number: i64,
+    pub log_index: i32,
+    pub payload: serde_json::Value,
+    pub error: String,
+    pub first_failed_at: i64,
+    pub last_failed_at: i64,
+}
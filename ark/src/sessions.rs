@@ -0,0 +1,102 @@
+use ark_db::schema::{ark_auth_nonces, ark_sessions};
+use diesel::prelude::{Insertable, Queryable};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// How long a player has to sign a Sign-In-With-Ethereum message
+pub const AUTH_NONCE_TTL_SECS: i64 = 5 * 60;
+pub const SESSION_TTL_SECS: i64 = 60 * 60;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Queryable)]
+#[diesel(table_name = ark_auth_nonces)]
+pub struct AuthNonce {
+    pub id: i64,
+    pub nonce: String,
+    pub expires_at: i64,
+    pub used_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Insertable)]
+#[diesel(table_name = ark_auth_nonces)]
+pub struct UnsavedAuthNonce {
+    pub nonce: String,
+    pub expires_at: i64,
+}
+
+impl UnsavedAuthNonce {
+    pub fn new(now: i64) -> Self {
+        UnsavedAuthNonce {
+            // SIWE nonces must be alphanumeric
+            nonce: uuid::Uuid::new_v4().simple().to_string(),
+            expires_at: now + AUTH_NONCE_TTL_SECS,
+        }
+    }
+
+    /// For stores that assign ids themselves e.g. in-memory repos
+    pub fn to_saved(&self, id: i64) -> AuthNonce {
+        AuthNonce {
+            id,
+            nonce: self.nonce.clone(),
+            expires_at: self.expires_at,
+            used_at: None,
+        }
+    }
+}
+
+/// Only the token's hash is stored, the token itself is handed to the player once.
+#[derive(Clone, Debug, Serialize, Deserialize, Queryable)]
+#[diesel(table_name = ark_sessions)]
+pub struct Session {
+    pub id: i64,
+    token_hash: String,
+    pub player_address: String,
+    pub expires_at: i64,
+}
+
+impl Session {
+    pub fn is_for_token(&self, token: &str) -> bool {
+        self.token_hash == hash_session_token(token)
+    }
+}
+
+#[derive(Clone, Debug, Insertable)]
+#[diesel(table_name = ark_sessions)]
+pub struct UnsavedSession {
+    token_hash: String,
+    pub player_address: String,
+    pub expires_at: i64,
+}
+
+impl UnsavedSession {
+    /// Returns the session along with its token
+    pub fn new(player_address: &str, now: i64) -> (Self, String) {
+        let token = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+
+        let session = UnsavedSession {
+            token_hash: hash_session_token(&token),
+            player_address: player_address.to_lowercase(),
+            expires_at: now + SESSION_TTL_SECS,
+        };
+
+        (session, token)
+    }
+
+    /// For stores that assign ids themselves e.g. in-memory repos
+    pub fn to_saved(&self, id: i64) -> Session {
+        Session {
+            id,
+            token_hash: self.token_hash.clone(),
+            player_address: self.player_address.clone(),
+            expires_at: self.expires_at,
+        }
+    }
+}
+
+pub fn hash_session_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
@@ -8,6 +8,9 @@ use serde::{Deserialize, Serialize};
 pub struct TotalPaidOutReport {
     pub id: i64,
     amount: String,
+    pub chain_id: Option<i64>,
+    pub transaction_hash: Option<String>,
+    pub log_index: Option<i32>,
 }
 
 impl TotalPaidOutReport {
@@ -15,9 +18,7 @@ impl TotalPaidOutReport {
         let old_amount = self.get_amount();
         let new_amount = old_amount + amount;
 
-        UnsavedTotalPaidOutReport {
-            amount: new_amount.to_string(),
-        }
+        UnsavedTotalPaidOutReport::new(new_amount)
     }
     pub fn get_amount(&self) -> f64 {
         self.amount.parse().unwrap()
@@ -28,12 +29,37 @@ impl TotalPaidOutReport {
 #[diesel(table_name = ark_total_paid_out_reports)]
 pub struct UnsavedTotalPaidOutReport {
     amount: String,
+    chain_id: Option<i64>,
+    transaction_hash: Option<String>,
+    log_index: Option<i32>,
 }
 
 impl UnsavedTotalPaidOutReport {
     pub fn new(amount: f64) -> UnsavedTotalPaidOutReport {
         UnsavedTotalPaidOutReport {
             amount: amount.to_string(),
+            chain_id: None,
+            transaction_hash: None,
+            log_index: None,
+        }
+    }
+    /// Keys the report by the credit event that paid it out, so replays of the event don't
+    /// count it twice
+    pub fn for_event(mut self, chain_id: i64, transaction_hash: &str, log_index: i32) -> Self {
+        self.chain_id = Some(chain_id);
+        self.transaction_hash = Some(transaction_hash.to_lowercase());
+        self.log_index = Some(log_index);
+        self
+    }
+
+    /// For stores that assign ids themselves e.g. in-memory repos
+    pub fn to_saved(&self, id: i64) -> TotalPaidOutReport {
+        TotalPaidOutReport {
+            id,
+            amount: self.amount.clone(),
+            chain_id: self.chain_id,
+            transaction_hash: self.transaction_hash.clone(),
+            log_index: self.log_index,
         }
     }
 }
@@ -0,0 +1,36 @@
+use ark::environments::Environment;
+use ark::profiles::{Profile, Worker};
+use ark_web3::chains::ChainId;
+
+const ENVIRONMENTS: [Environment; 3] = [
+    Environment::Local,
+    Environment::Staging,
+    Environment::Production,
+];
+
+#[test]
+fn prices_every_chain_of_every_profile() {
+    for environment in ENVIRONMENTS {
+        let profile = Profile::get(&environment);
+
+        for chain_id in &profile.chains {
+            assert!(
+                profile
+                    .price_sources
+                    .iter()
+                    .any(|price_source| price_source.chain_id == *chain_id),
+                "{environment:?} has no price source for {chain_id:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn runs_staging_on_testnets_with_every_contract_and_worker() {
+    let profile = Profile::get(&Environment::Staging);
+
+    assert_eq!(profile.chains, vec![ChainId::Sepolia]);
+    assert!(profile.has_contract("Coinflip"));
+    assert!(profile.has_contract("wallets"));
+    assert!(Worker::all().into_iter().all(|worker| profile.has_worker(worker)));
+}
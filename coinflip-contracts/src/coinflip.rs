@@ -1,66 +1,65 @@
 mod event_handlers;
+pub mod events;
 mod states;
 
 use std::sync::Arc;
 
+use ark::configs::ArkConfig;
+use ark_contracts::quarantine::Quarantined;
 use ark_db::DBPool;
+use ark_utils::clocks::Clock;
 use chaindexing::Contract;
 
-use ark_web3::chains::ChainId;
+use ark_web3::events::TypedEvent;
 
 use event_handlers::{
     ExpiredGameRefundedHandler, GameCompletedEventHandler, GameCreatedEventHandler,
     GameExpiryAdjustedHandler, GamePlayChanceRevealedEventHandler, GamePlayCreatedEventHandler,
 };
 
+use events::{
+    ExpiredGameRefunded, GameCompleted, GameCreated, GameExpiryAdjusted, GamePlayChanceRevealed,
+    GamePlayCreated,
+};
 use states::{GameMigrations, GamePlayMigrations};
 
-pub fn get() -> Contract<Arc<DBPool>> {
+pub fn get(config: &ArkConfig, clock: Arc<dyn Clock>) -> Contract<Arc<DBPool>> {
     let contract = Contract::new("Coinflip")
         .add_event(
-            "event GameCreated(uint256 indexed gameID, address indexed creator, uint16 numberOfPlayers, uint256 expiryTimestamp, uint256 wager)",
-            GameCreatedEventHandler,
+            GameCreated::SIGNATURE,
+            Quarantined::new(GameCreatedEventHandler, clock.clone()),
+        )
+        .add_event(
+            GamePlayCreated::SIGNATURE,
+            Quarantined::new(GamePlayCreatedEventHandler, clock.clone()),
         )
         .add_event(
-            "event GamePlayCreated(uint256 indexed gameID, uint16 indexed gamePlayID, address indexed player, uint8 coinSide, bytes32 proofOfChance)",
-            GamePlayCreatedEventHandler,
+            GameCompleted::SIGNATURE,
+            Quarantined::new(GameCompletedEventHandler, clock.clone()),
         )
         .add_event(
-            "event GameCompleted(uint256 indexed gameID, uint8 coinSide, uint amountForEachWinner)",
-            GameCompletedEventHandler,
+            GamePlayChanceRevealed::SIGNATURE,
+            Quarantined::new(GamePlayChanceRevealedEventHandler, clock.clone()),
+        )
+        .add_event(
+            ExpiredGameRefunded::SIGNATURE,
+            Quarantined::new(ExpiredGameRefundedHandler, clock.clone()),
+        )
+        .add_event(
+            GameExpiryAdjusted::SIGNATURE,
+            Quarantined::new(GameExpiryAdjustedHandler, clock.clone()),
         )
-        .add_event("event GamePlayChanceRevealed(uint indexed gameID, uint16 indexed gamePlayID, bytes chanceAndSalt)", GamePlayChanceRevealedEventHandler)
-        .add_event("event ExpiredGameRefunded(uint indexed gameID, uint refundedAmountPerPlayer)", ExpiredGameRefundedHandler)
-        .add_event("event GameExpiryAdjusted(uint indexed gameID, uint expiryTimestamp)", GameExpiryAdjustedHandler)
         .add_state_migrations(GameMigrations)
         .add_state_migrations(GamePlayMigrations);
 
-    let current_environment = ark::environments::current();
-
-    if current_environment.is_local() {
-        contract.add_address(
-            &ChainId::Local.get_contract_address("COINFLIP"),
-            &chaindexing::ChainId::Dev,
-            0,
-        )
-    } else if current_environment.is_production() {
-        contract
-            .add_address(
-                &ChainId::Sepolia.get_contract_address("COINFLIP"),
-                &chaindexing::ChainId::Sepolia,
-                ChainId::Sepolia.get_start_block_number("COINFLIP"),
-            )
-            .add_address(
-                &ChainId::Polygon.get_contract_address("COINFLIP"),
-                &chaindexing::ChainId::Polygon,
-                ChainId::Polygon.get_start_block_number("COINFLIP"),
-            )
-            .add_address(
-                &ChainId::Ethereum.get_contract_address("COINFLIP"),
-                &chaindexing::ChainId::Mainnet,
-                ChainId::Ethereum.get_start_block_number("COINFLIP"),
+    config.get_contract_deployments("coinflip").into_iter().fold(
+        contract,
+        |contract, (chain_id, deployment)| {
+            contract.add_address(
+                &deployment.address,
+                &chain_id.into(),
+                deployment.start_block_number,
             )
-    } else {
-        contract
-    }
+        },
+    )
 }
@@ -11,3 +11,17 @@ pub use game_created::GameCreatedEventHandler;
 pub use game_expiry_adjusted::GameExpiryAdjustedHandler;
 pub use game_play_chance_revealed::GamePlayChanceRevealedEventHandler;
 pub use game_play_created::GamePlayCreatedEventHandler;
+
+use chaindexing::Event;
+use coinflip::GameActivityEvent;
+
+/// Keys activities by their source event so replayed events don't duplicate them
+pub(crate) fn get_game_activity_event(event: &Event) -> GameActivityEvent {
+    GameActivityEvent {
+        chain_id: event.chain_id,
+        block_number: event.block_number,
+        block_timestamp: event.block_timestamp,
+        transaction_hash: event.transaction_hash.clone(),
+        log_index: event.log_index,
+    }
+}
@@ -1,45 +1,51 @@
 use std::sync::Arc;
 
 use ark_db::DBPool;
-use chaindexing::{ContractState, EventContext, EventHandler};
-use coinflip::GamePlayStatus;
+use chaindexing::{ContractState, EventContext};
+use coinflip::{GamePlayStatus, GameRefundedActivityData, GameStatus, UnsavedGameActivity};
+use coinflip::{WalletCreditReason, WalletCreditedActivityData};
 
+use ark_contracts::quarantine::FallibleEventHandler;
+use ark_web3::events::{EventHandlerError, TypedEvent};
+
+use super::get_game_activity_event;
+use crate::coinflip::events::ExpiredGameRefunded;
 use crate::coinflip::states::{Game, GamePlay};
 
 pub struct ExpiredGameRefundedHandler;
 
 #[async_trait::async_trait]
-impl EventHandler for ExpiredGameRefundedHandler {
-    type SharedState = Arc<DBPool>;
+impl FallibleEventHandler for ExpiredGameRefundedHandler {
+    fn name(&self) -> &'static str {
+        "ExpiredGameRefundedHandler"
+    }
 
-    async fn handle_event<'a, 'b>(&self, event_context: EventContext<'a, 'b, Self::SharedState>) {
+    async fn handle_event<'a, 'b>(
+        &self,
+        event_context: EventContext<'a, 'b, Arc<DBPool>>,
+    ) -> Result<(), EventHandlerError> {
         let event = &event_context.event;
-        let event_params = event.get_params();
-
-        let game_id =
-            event_params.get("gameID").unwrap().clone().into_uint().unwrap().as_u32() as i64;
-        let refunded_amount_per_player = event_params
-            .get("refundedAmountPerPlayer")
-            .unwrap()
-            .clone()
-            .into_uint()
-            .unwrap()
-            .to_string();
+
+        let ExpiredGameRefunded {
+            game_id,
+            refunded_amount_per_player,
+        } = ExpiredGameRefunded::from_event(event)?;
 
         let game = Game::read_one(
             [(("id".to_string(), game_id.to_string()))].into(),
             &event_context,
         )
         .await
-        .unwrap();
+        .ok_or_else(|| EventHandlerError::missing_state("Game", game_id))?;
 
         game.update(
             [
                 (
                     "refunded_amount_per_player".to_string(),
-                    refunded_amount_per_player,
+                    refunded_amount_per_player.clone(),
                 ),
                 ("refunded_at".to_string(), event.block_timestamp.to_string()),
+                game.get_status_transition(GameStatus::Refunded)?,
             ]
             .into(),
             &event_context,
@@ -54,7 +60,7 @@ impl EventHandler for ExpiredGameRefundedHandler {
         )
         .await;
 
-        for game_play in game_plays {
+        for game_play in game_plays.iter() {
             game_play
                 .update(
                     [("status".to_string(), GamePlayStatus::Expired.into())].into(),
@@ -62,5 +68,39 @@ impl EventHandler for ExpiredGameRefundedHandler {
                 )
                 .await;
         }
+
+        let pool = event_context.get_shared_state().await;
+        let mut conn = pool.get_owned().await.map_err(EventHandlerError::unavailable)?;
+
+        let game_activity_event = get_game_activity_event(event);
+
+        let game_activity = UnsavedGameActivity::new_game_refunded(
+            game_id,
+            &game.creator_address,
+            &game_activity_event,
+            GameRefundedActivityData {
+                refunded_amount_per_player: refunded_amount_per_player.clone(),
+            },
+        );
+        coinflip_repo::create_game_activity(&mut conn, &game_activity)
+            .await
+            .map_err(EventHandlerError::unavailable)?;
+
+        for game_play in game_plays.iter() {
+            let game_activity = UnsavedGameActivity::new_wallet_credited(
+                game_id,
+                &game_play.player_address,
+                &game_activity_event,
+                WalletCreditedActivityData {
+                    amount: refunded_amount_per_player.clone(),
+                    reason: WalletCreditReason::Refunded,
+                },
+            );
+            coinflip_repo::create_game_activity(&mut conn, &game_activity)
+                .await
+                .map_err(EventHandlerError::unavailable)?;
+        }
+
+        Ok(())
     }
 }
@@ -2,44 +2,55 @@ use std::sync::Arc;
 
 use ark_db::DBPool;
 
-use chaindexing::{ContractState, EventContext, EventHandler};
+use chaindexing::{ContractState, EventContext};
 
+use ark_contracts::quarantine::FallibleEventHandler;
+use ark_web3::events::{EventHandlerError, TypedEvent};
+
+use super::get_game_activity_event;
+use crate::coinflip::events::GameCompleted;
 use crate::coinflip::states::{Game, GamePlay};
-use coinflip::GamePlayStatus;
+use coinflip::{GameCompletedActivityData, GamePlayStatus, GameStatus, UnsavedGameActivity};
+use coinflip::{WalletCreditReason, WalletCreditedActivityData};
 
 pub struct GameCompletedEventHandler;
 
 #[async_trait::async_trait]
-impl EventHandler for GameCompletedEventHandler {
-    type SharedState = Arc<DBPool>;
+impl FallibleEventHandler for GameCompletedEventHandler {
+    fn name(&self) -> &'static str {
+        "GameCompletedEventHandler"
+    }
 
-    async fn handle_event<'a, 'b>(&self, event_context: EventContext<'a, 'b, Self::SharedState>) {
+    async fn handle_event<'a, 'b>(
+        &self,
+        event_context: EventContext<'a, 'b, Arc<DBPool>>,
+    ) -> Result<(), EventHandlerError> {
         let event = &event_context.event;
-        let event_params = event.get_params();
 
-        let game_id = event_params.get("gameID").unwrap().clone().into_uint().unwrap().as_u64();
-        let amount_for_each_winner =
-            event_params.get("amountForEachWinner").unwrap().clone().into_uint().unwrap();
-        let outcome_coin_side =
-            event_params.get("coinSide").unwrap().clone().into_uint().unwrap().as_usize() as u8;
+        let GameCompleted {
+            game_id,
+            coin_side: outcome_coin_side,
+            amount_for_each_winner,
+        } = GameCompleted::from_event(event)?;
 
         let game = Game::read_one(
             [("id".to_string(), game_id.to_string())].into(),
             &event_context,
         )
         .await
-        .unwrap();
+        .ok_or_else(|| EventHandlerError::missing_state("Game", game_id))?;
         game.update(
             [
                 ("outcome".to_string(), outcome_coin_side.to_string()),
                 (
                     "amount_for_each_winner".to_string(),
-                    amount_for_each_winner.to_string(),
+                    amount_for_each_winner.clone(),
                 ),
                 (
                     "completed_at".to_string(),
                     event_context.event.block_timestamp.to_string(),
                 ),
+                game.get_status_transition(GameStatus::Completed)?,
             ]
             .into(),
             &event_context,
@@ -54,6 +65,12 @@ impl EventHandler for GameCompletedEventHandler {
         )
         .await;
 
+        let winner_addresses: Vec<_> = game_plays
+            .iter()
+            .filter(|game_play| game_play.coin_side == outcome_coin_side)
+            .map(|game_play| game_play.player_address.clone())
+            .collect();
+
         for game_play in game_plays {
             let game_play_status = if game_play.coin_side == outcome_coin_side {
                 GamePlayStatus::Won
@@ -68,5 +85,41 @@ impl EventHandler for GameCompletedEventHandler {
                 )
                 .await;
         }
+
+        let pool = event_context.get_shared_state().await;
+        let mut conn = pool.get_owned().await.map_err(EventHandlerError::unavailable)?;
+
+        let game_activity_event = get_game_activity_event(event);
+
+        let game_activity = UnsavedGameActivity::new_game_completed(
+            game_id,
+            &game.creator_address,
+            &game_activity_event,
+            GameCompletedActivityData {
+                outcome: outcome_coin_side,
+                winner_addresses: winner_addresses.clone(),
+                amount_for_each_winner: amount_for_each_winner.clone(),
+            },
+        );
+        coinflip_repo::create_game_activity(&mut conn, &game_activity)
+            .await
+            .map_err(EventHandlerError::unavailable)?;
+
+        for winner_address in winner_addresses.iter() {
+            let game_activity = UnsavedGameActivity::new_wallet_credited(
+                game_id,
+                winner_address,
+                &game_activity_event,
+                WalletCreditedActivityData {
+                    amount: amount_for_each_winner.clone(),
+                    reason: WalletCreditReason::Won,
+                },
+            );
+            coinflip_repo::create_game_activity(&mut conn, &game_activity)
+                .await
+                .map_err(EventHandlerError::unavailable)?;
+        }
+
+        Ok(())
     }
 }
@@ -2,47 +2,43 @@ use std::sync::Arc;
 
 use ark_db::DBPool;
 
-use chaindexing::{utils::address_to_string, ContractState, EventContext, EventHandler};
+use chaindexing::{ContractState, EventContext};
 
+use ark_contracts::quarantine::FallibleEventHandler;
+use ark_web3::events::{EventHandlerError, TypedEvent};
+
+use super::get_game_activity_event;
+use crate::coinflip::events::GameCreated;
 use crate::coinflip::states::Game;
-use coinflip::UnsavedGameActivity;
+use coinflip::{GameStatus, UnsavedGameActivity};
 
 pub struct GameCreatedEventHandler;
 
 #[async_trait::async_trait]
-impl EventHandler for GameCreatedEventHandler {
-    type SharedState = Arc<DBPool>;
+impl FallibleEventHandler for GameCreatedEventHandler {
+    fn name(&self) -> &'static str {
+        "GameCreatedEventHandler"
+    }
 
-    async fn handle_event<'a, 'b>(&self, event_context: EventContext<'a, 'b, Self::SharedState>) {
+    async fn handle_event<'a, 'b>(
+        &self,
+        event_context: EventContext<'a, 'b, Arc<DBPool>>,
+    ) -> Result<(), EventHandlerError> {
         let event = &event_context.event;
-        let event_params = event.get_params();
 
         let pool = event_context.get_shared_state().await;
 
-        let id = event_params.get("gameID").unwrap().clone().into_uint().unwrap().as_u64();
-        let number_of_players = event_params
-            .get("numberOfPlayers")
-            .unwrap()
-            .clone()
-            .into_uint()
-            .unwrap()
-            .as_u32();
-        let expiry_timestamp = event_params
-            .get("expiryTimestamp")
-            .unwrap()
-            .clone()
-            .into_uint()
-            .unwrap()
-            .as_u64();
-        let creator_address = address_to_string(
-            &event_params.get("creator").unwrap().clone().into_address().unwrap(),
-        )
-        .to_lowercase();
-        let wager = event_params.get("wager").unwrap().clone().into_uint().unwrap().to_string();
+        let GameCreated {
+            game_id: id,
+            creator_address,
+            number_of_players,
+            expiry_timestamp,
+            wager,
+        } = GameCreated::from_event(event)?;
 
         Game {
             id,
-            number_of_players,
+            number_of_players: number_of_players as u32,
             expiry_timestamp,
             creator_address: creator_address.clone(),
             wager,
@@ -51,18 +47,23 @@ impl EventHandler for GameCreatedEventHandler {
             tail_play_count: 0,
             unavailable_coin_side: None,
             winner_address: None,
+            created_at: event.block_timestamp as u64,
+            status: GameStatus::AwaitingPlayers.into(),
+            all_seats_filled_at: None,
         }
         .create(&event_context)
         .await;
 
-        let mut conn = pool.get_owned().await.unwrap();
+        let mut conn = pool.get_owned().await.map_err(EventHandlerError::unavailable)?;
         let game_activity = UnsavedGameActivity::new_game_created(
             id,
-            event.chain_id,
-            creator_address.clone(),
-            event.block_timestamp,
-            event.transaction_hash.clone(),
+            &creator_address,
+            &get_game_activity_event(event),
         );
-        coinflip_repo::create_game_activity(&mut conn, &game_activity).await;
+        coinflip_repo::create_game_activity(&mut conn, &game_activity)
+            .await
+            .map_err(EventHandlerError::unavailable)?;
+
+        Ok(())
     }
 }
@@ -1,41 +1,67 @@
 use std::sync::Arc;
 
 use ark_db::DBPool;
-use chaindexing::{ContractState, EventContext, EventHandler};
+use chaindexing::{ContractState, EventContext};
+use coinflip::{GameExpiryAdjustedActivityData, UnsavedGameActivity};
 
+use ark_contracts::quarantine::FallibleEventHandler;
+use ark_web3::events::{EventHandlerError, TypedEvent};
+
+use super::get_game_activity_event;
+use crate::coinflip::events::GameExpiryAdjusted;
 use crate::coinflip::states::Game;
 
 pub struct GameExpiryAdjustedHandler;
 
 #[async_trait::async_trait]
-impl EventHandler for GameExpiryAdjustedHandler {
-    type SharedState = Arc<DBPool>;
+impl FallibleEventHandler for GameExpiryAdjustedHandler {
+    fn name(&self) -> &'static str {
+        "GameExpiryAdjustedHandler"
+    }
 
-    async fn handle_event<'a, 'b>(&self, event_context: EventContext<'a, 'b, Self::SharedState>) {
+    async fn handle_event<'a, 'b>(
+        &self,
+        event_context: EventContext<'a, 'b, Arc<DBPool>>,
+    ) -> Result<(), EventHandlerError> {
         let event = &event_context.event;
-        let event_params = event.get_params();
-
-        let game_id =
-            event_params.get("gameID").unwrap().clone().into_uint().unwrap().as_u64() as i64;
-        let expiry_timestamp = event_params
-            .get("expiryTimestamp")
-            .unwrap()
-            .clone()
-            .into_uint()
-            .unwrap()
-            .as_u64();
+
+        let GameExpiryAdjusted {
+            game_id,
+            expiry_timestamp,
+        } = GameExpiryAdjusted::from_event(event)?;
 
         let game = Game::read_one(
             [("id".to_string(), game_id.to_string())].into(),
             &event_context,
         )
         .await
-        .unwrap();
+        .ok_or_else(|| EventHandlerError::missing_state("Game", game_id))?;
 
+        // Expiry is derived from `expiry_timestamp`, so expired games come back to life with it
         game.update(
             [("expiry_timestamp".to_string(), expiry_timestamp.to_string())].into(),
             &event_context,
         )
         .await;
+
+        let pool = event_context.get_shared_state().await;
+        let mut conn = pool.get_owned().await.map_err(EventHandlerError::unavailable)?;
+
+        let game_activity_event = get_game_activity_event(event);
+
+        let game_activity = UnsavedGameActivity::new_game_expiry_adjusted(
+            game_id,
+            &game.creator_address,
+            &game_activity_event,
+            GameExpiryAdjustedActivityData {
+                old_expiry_timestamp: game.expiry_timestamp as i64,
+                new_expiry_timestamp: expiry_timestamp as i64,
+            },
+        );
+        coinflip_repo::create_game_activity(&mut conn, &game_activity)
+            .await
+            .map_err(EventHandlerError::unavailable)?;
+
+        Ok(())
     }
 }
@@ -1,28 +1,36 @@
 use std::sync::Arc;
 
 use ark_db::DBPool;
-use chaindexing::{EventContext, EventHandler};
-use coinflip::{GameActivityKind, UnsavedGameActivity};
-use coinflip_repo::GetGameActivityParams;
+use chaindexing::EventContext;
+use coinflip::UnsavedGameActivity;
 
+use ark_contracts::quarantine::FallibleEventHandler;
+use ark_web3::events::{EventHandlerError, TypedEvent};
+
+use super::get_game_activity_event;
+use crate::coinflip::events::GamePlayChanceRevealed;
 use crate::coinflip::states::GamePlay;
 
 use chaindexing::ContractState;
 pub struct GamePlayChanceRevealedEventHandler;
 
 #[async_trait::async_trait]
-impl EventHandler for GamePlayChanceRevealedEventHandler {
-    type SharedState = Arc<DBPool>;
+impl FallibleEventHandler for GamePlayChanceRevealedEventHandler {
+    fn name(&self) -> &'static str {
+        "GamePlayChanceRevealedEventHandler"
+    }
 
-    async fn handle_event<'a, 'b>(&self, event_context: EventContext<'a, 'b, Self::SharedState>) {
+    async fn handle_event<'a, 'b>(
+        &self,
+        event_context: EventContext<'a, 'b, Arc<DBPool>>,
+    ) -> Result<(), EventHandlerError> {
         let event = &event_context.event;
-        let event_params = event.get_params();
 
-        let game_id =
-            event_params.get("gameID").unwrap().clone().into_uint().unwrap().as_u32() as i64;
-        let game_play_id =
-            event_params.get("gamePlayID").unwrap().clone().into_uint().unwrap().as_u32() as i32;
-        let chance_and_salt = event_params.get("chanceAndSalt").unwrap().to_string();
+        let GamePlayChanceRevealed {
+            game_id,
+            game_play_id,
+            chance_and_salt,
+        } = GamePlayChanceRevealed::from_event(event)?;
 
         let game_play = GamePlay::read_one(
             [
@@ -33,7 +41,9 @@ impl EventHandler for GamePlayChanceRevealedEventHandler {
             &event_context,
         )
         .await
-        .unwrap();
+        .ok_or_else(|| {
+            EventHandlerError::missing_state("GamePlay", format!("{game_id}-{game_play_id}"))
+        })?;
 
         game_play
             .update(
@@ -47,34 +57,17 @@ impl EventHandler for GamePlayChanceRevealedEventHandler {
             .await;
 
         let pool = event_context.get_shared_state().await;
-        let mut conn = pool.get_owned().await.unwrap();
+        let mut conn = pool.get_owned().await.map_err(EventHandlerError::unavailable)?;
 
         let game_activity = UnsavedGameActivity::new_chance_revealed(
-            game_id as u64,
-            event.chain_id,
+            game_id,
             &game_play.player_address,
-        )
-        .with_transaction_hash(&event.transaction_hash);
+            &get_game_activity_event(event),
+        );
+        coinflip_repo::create_game_activity(&mut conn, &game_activity)
+            .await
+            .map_err(EventHandlerError::unavailable)?;
 
-        if let Some(game_activity) = coinflip_repo::get_game_activity(
-            &mut conn,
-            &GetGameActivityParams {
-                game_id: game_id as i64,
-                chain_id: event.chain_id as i64,
-                kind: GameActivityKind::GamePlayChanceRevealed.into(),
-                trigger_public_address: game_play.player_address.to_owned(),
-            },
-        )
-        .await
-        {
-            coinflip_repo::update_game_activity_transaction_hash(
-                &mut conn,
-                &game_activity,
-                &event.transaction_hash,
-            )
-            .await;
-        } else {
-            coinflip_repo::create_game_activity(&mut conn, &game_activity).await;
-        }
+        Ok(())
     }
 }
@@ -1,66 +1,92 @@
 use std::{collections::HashMap, sync::Arc};
 
 use ark_db::{DBConn, DBPool};
-use chaindexing::{utils::address_to_string, ContractState, Event, EventContext, EventHandler};
+use chaindexing::{ContractState, Event, EventContext};
+use diesel::QueryResult;
 
+use ark_contracts::quarantine::FallibleEventHandler;
+use ark_web3::events::{EventHandlerError, TypedEvent};
+
+use super::get_game_activity_event;
+use crate::coinflip::events::GamePlayCreated;
 use crate::coinflip::states::{Game, GamePlay};
-use coinflip::{CoinSide, GamePlayStatus, UnsavedGameActivity};
+use coinflip::{CoinSide, GamePlayStatus, GameStatus, UnsavedGameActivity};
 
 pub struct GamePlayCreatedEventHandler;
 
 #[async_trait::async_trait]
-impl EventHandler for GamePlayCreatedEventHandler {
-    type SharedState = Arc<DBPool>;
+impl FallibleEventHandler for GamePlayCreatedEventHandler {
+    fn name(&self) -> &'static str {
+        "GamePlayCreatedEventHandler"
+    }
 
-    async fn handle_event<'a, 'b>(&self, event_context: EventContext<'a, 'b, Self::SharedState>) {
+    async fn handle_event<'a, 'b>(
+        &self,
+        event_context: EventContext<'a, 'b, Arc<DBPool>>,
+    ) -> Result<(), EventHandlerError> {
         let event = &event_context.event;
-        let event_params = event.get_params();
-
-        let id =
-            event_params.get("gamePlayID").unwrap().clone().into_uint().unwrap().as_u32() as u16;
-        let game_id = event_params.get("gameID").unwrap().clone().into_uint().unwrap().as_u64();
-        let coin_side =
-            event_params.get("coinSide").unwrap().clone().into_uint().unwrap().as_usize() as u8;
-        let player_address =
-            address_to_string(&event_params.get("player").unwrap().clone().into_address().unwrap())
-                .to_lowercase();
-        let proof_of_chance = &event_params.get("proofOfChance").unwrap().clone().to_string();
+
+        let GamePlayCreated {
+            game_id,
+            game_play_id: id,
+            player_address,
+            coin_side,
+            proof_of_chance,
+        } = GamePlayCreated::from_event(event)?;
 
         let new_game_play = GamePlay {
             id,
             game_id,
             coin_side,
-            player_address: player_address.clone(),
-            proof_of_chance: proof_of_chance.clone(),
+            player_address,
+            proof_of_chance,
             status: GamePlayStatus::Pending.into(),
             chance_and_salt: None,
         };
 
-        new_game_play.create(&event_context).await;
-
-        update_game(&new_game_play, &event_context).await;
+        // Everything is validated before the first write, so a rejected event leaves no trace
+        let game = Game::read_one(
+            [("id".to_string(), game_id.to_string())].into(),
+            &event_context,
+        )
+        .await
+        .ok_or_else(|| EventHandlerError::missing_state("Game", game_id))?;
+
+        let game_plays = GamePlay::read_many(
+            [("game_id".to_string(), game_id.to_string())].into(),
+            &event_context,
+        )
+        .await;
+
+        // Retried or replayed events find their play already indexed
+        if !game_plays.iter().any(|game_play| game_play.id == new_game_play.id) {
+            let game_updates =
+                get_game_updates(&game, &game_plays, &new_game_play, event.block_timestamp)?;
+
+            new_game_play.create(&event_context).await;
+            game.update(game_updates, &event_context).await;
+        }
 
         let pool = event_context.get_shared_state().await;
-        let mut conn = pool.get_owned().await.unwrap();
+        let mut conn = pool.get_owned().await.map_err(EventHandlerError::unavailable)?;
+
+        create_game_activity(&mut conn, &new_game_play, event)
+            .await
+            .map_err(EventHandlerError::unavailable)?;
 
-        create_game_activity(&mut conn, &new_game_play, event).await;
+        Ok(())
     }
 }
 
-async fn update_game<'a, 'b, S: Send + Sync + Clone>(
+fn get_game_updates(
+    game: &Game,
+    game_plays: &[GamePlay],
     new_game_play: &GamePlay,
-    event_context: &EventContext<'a, 'b, S>,
-) {
-    let game = Game::read_one(
-        [("id".to_string(), new_game_play.game_id.to_string())].into(),
-        &event_context,
-    )
-    .await
-    .unwrap();
-
+    block_timestamp: i64,
+) -> Result<HashMap<String, String>, EventHandlerError> {
     let new_play_count = game.play_count + 1;
     let (new_head_play_count, new_tail_play_count) =
-        get_new_head_and_tail_play_counts(&new_game_play, &game);
+        get_new_head_and_tail_play_counts(new_game_play, game);
 
     let mut updates = HashMap::from([
         ("play_count".to_string(), new_play_count.to_string()),
@@ -73,28 +99,45 @@ async fn update_game<'a, 'b, S: Send + Sync + Clone>(
             new_tail_play_count.to_string(),
         ),
     ]);
-    if let Some(unavailable_coin_side) = get_unavailable_coin_side(&game, &event_context).await {
+    if new_play_count == game.number_of_players {
+        let (key, value) = game.get_status_transition(GameStatus::AwaitingRevealedChances)?;
+        updates.insert(key, value);
+        updates.insert(
+            "all_seats_filled_at".to_string(),
+            block_timestamp.to_string(),
+        );
+    }
+
+    let played_coin_sides = game_plays
+        .iter()
+        .chain(std::iter::once(new_game_play))
+        .map(|game_play| game_play.coin_side)
+        .collect();
+
+    if let Some(unavailable_coin_side) = game.get_unavailable_coin_side(&played_coin_sides) {
         updates.insert(
             "unavailable_coin_side".to_string(),
             (unavailable_coin_side as usize).to_string(),
         );
     }
 
-    game.update(updates, &event_context).await;
+    Ok(updates)
 }
 
-async fn create_game_activity<'a>(conn: &mut DBConn<'a>, new_game_play: &GamePlay, event: &Event) {
+async fn create_game_activity<'a>(
+    conn: &mut DBConn<'a>,
+    new_game_play: &GamePlay,
+    event: &Event,
+) -> QueryResult<()> {
     let game_activity = UnsavedGameActivity::new_game_play_created(
         new_game_play.game_id,
-        event.chain_id,
-        new_game_play.player_address.clone(),
-        event.block_timestamp,
-        event.transaction_hash.clone(),
+        &new_game_play.player_address,
+        &get_game_activity_event(event),
         new_game_play.coin_side,
         new_game_play.proof_of_chance.clone(),
     );
 
-    coinflip_repo::create_game_activity(conn, &game_activity).await;
+    coinflip_repo::create_game_activity(conn, &game_activity).await
 }
 
 fn get_new_head_and_tail_play_counts(new_game_play: &GamePlay, game: &Game) -> (u32, u32) {
@@ -104,7 +147,7 @@ fn get_new_head_and_tail_play_counts(new_game_play: &GamePlay, game: &Game) -> (
         game.head_play_count
     };
 
-    let new_tail_play_count = if CoinSide::is_head(new_game_play.coin_side) {
+    let new_tail_play_count = if CoinSide::is_tail(new_game_play.coin_side) {
         game.tail_play_count + 1
     } else {
         game.tail_play_count
@@ -112,18 +155,3 @@ fn get_new_head_and_tail_play_counts(new_game_play: &GamePlay, game: &Game) -> (
 
     (new_head_play_count, new_tail_play_count)
 }
-
-async fn get_unavailable_coin_side<'a, 'b, S: Send + Sync + Clone>(
-    game: &Game,
-    event_context: &EventContext<'a, 'b, S>,
-) -> Option<u8> {
-    let game_plays = GamePlay::read_many(
-        [("game_id".to_string(), game.id.to_string())].into(),
-        &event_context,
-    )
-    .await;
-
-    let played_coin_sides = game_plays.iter().map(|game_play| game_play.coin_side).collect();
-
-    game.get_unavailable_coin_side(&played_coin_sides)
-}
@@ -0,0 +1,127 @@
+use ark_web3::events::{EventDecodeError, EventParams, TypedEvent};
+
+pub struct GameCreated {
+    pub game_id: u64,
+    pub creator_address: String,
+    pub number_of_players: u16,
+    pub expiry_timestamp: u64,
+    /// In wei
+    pub wager: String,
+}
+
+impl TypedEvent for GameCreated {
+    const NAME: &'static str = "GameCreated";
+    const SIGNATURE: &'static str = "event GameCreated(uint256 indexed gameID, address indexed creator, uint16 numberOfPlayers, uint256 expiryTimestamp, uint256 wager)";
+
+    fn decode(params: &EventParams) -> Result<Self, EventDecodeError> {
+        Ok(GameCreated {
+            game_id: params.get_u64("gameID")?,
+            creator_address: params.get_address("creator")?,
+            number_of_players: params.get_u16("numberOfPlayers")?,
+            expiry_timestamp: params.get_u64("expiryTimestamp")?,
+            wager: params.get_uint_string("wager")?,
+        })
+    }
+}
+
+pub struct GamePlayCreated {
+    pub game_id: u64,
+    pub game_play_id: u16,
+    pub player_address: String,
+    pub coin_side: u8,
+    /// Hex encoded without a `0x` prefix
+    pub proof_of_chance: String,
+}
+
+impl TypedEvent for GamePlayCreated {
+    const NAME: &'static str = "GamePlayCreated";
+    const SIGNATURE: &'static str = "event GamePlayCreated(uint256 indexed gameID, uint16 indexed gamePlayID, address indexed player, uint8 coinSide, bytes32 proofOfChance)";
+
+    fn decode(params: &EventParams) -> Result<Self, EventDecodeError> {
+        Ok(GamePlayCreated {
+            game_id: params.get_u64("gameID")?,
+            game_play_id: params.get_u16("gamePlayID")?,
+            player_address: params.get_address("player")?,
+            coin_side: params.get_u8("coinSide")?,
+            proof_of_chance: params.get_bytes_hex("proofOfChance")?,
+        })
+    }
+}
+
+pub struct GameCompleted {
+    pub game_id: u64,
+    pub coin_side: u8,
+    /// In wei
+    pub amount_for_each_winner: String,
+}
+
+impl TypedEvent for GameCompleted {
+    const NAME: &'static str = "GameCompleted";
+    const SIGNATURE: &'static str =
+        "event GameCompleted(uint256 indexed gameID, uint8 coinSide, uint amountForEachWinner)";
+
+    fn decode(params: &EventParams) -> Result<Self, EventDecodeError> {
+        Ok(GameCompleted {
+            game_id: params.get_u64("gameID")?,
+            coin_side: params.get_u8("coinSide")?,
+            amount_for_each_winner: params.get_uint_string("amountForEachWinner")?,
+        })
+    }
+}
+
+pub struct GamePlayChanceRevealed {
+    pub game_id: u64,
+    pub game_play_id: u16,
+    /// Hex encoded without a `0x` prefix
+    pub chance_and_salt: String,
+}
+
+impl TypedEvent for GamePlayChanceRevealed {
+    const NAME: &'static str = "GamePlayChanceRevealed";
+    const SIGNATURE: &'static str = "event GamePlayChanceRevealed(uint indexed gameID, uint16 indexed gamePlayID, bytes chanceAndSalt)";
+
+    fn decode(params: &EventParams) -> Result<Self, EventDecodeError> {
+        Ok(GamePlayChanceRevealed {
+            game_id: params.get_u64("gameID")?,
+            game_play_id: params.get_u16("gamePlayID")?,
+            chance_and_salt: params.get_bytes_hex("chanceAndSalt")?,
+        })
+    }
+}
+
+pub struct ExpiredGameRefunded {
+    pub game_id: u64,
+    /// In wei
+    pub refunded_amount_per_player: String,
+}
+
+impl TypedEvent for ExpiredGameRefunded {
+    const NAME: &'static str = "ExpiredGameRefunded";
+    const SIGNATURE: &'static str =
+        "event ExpiredGameRefunded(uint indexed gameID, uint refundedAmountPerPlayer)";
+
+    fn decode(params: &EventParams) -> Result<Self, EventDecodeError> {
+        Ok(ExpiredGameRefunded {
+            game_id: params.get_u64("gameID")?,
+            refunded_amount_per_player: params.get_uint_string("refundedAmountPerPlayer")?,
+        })
+    }
+}
+
+pub struct GameExpiryAdjusted {
+    pub game_id: u64,
+    pub expiry_timestamp: u64,
+}
+
+impl TypedEvent for GameExpiryAdjusted {
+    const NAME: &'static str = "GameExpiryAdjusted";
+    const SIGNATURE: &'static str =
+        "event GameExpiryAdjusted(uint indexed gameID, uint expiryTimestamp)";
+
+    fn decode(params: &EventParams) -> Result<Self, EventDecodeError> {
+        Ok(GameExpiryAdjusted {
+            game_id: params.get_u64("gameID")?,
+            expiry_timestamp: params.get_u64("expiryTimestamp")?,
+        })
+    }
+}
@@ -2,7 +2,8 @@ use chaindexing::{ContractState, ContractStateMigrations};
 
 use serde::{Deserialize, Serialize};
 
-use coinflip::CoinSides;
+use ark_web3::events::EventHandlerError;
+use coinflip::{CoinSides, GameStatus, UnknownGameStatus};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Game {
@@ -16,6 +17,9 @@ pub struct Game {
     pub tail_play_count: u32,
     pub unavailable_coin_side: Option<u8>,
     pub winner_address: Option<String>,
+    pub created_at: u64,
+    pub status: String,
+    pub all_seats_filled_at: Option<u64>,
 }
 
 impl ContractState for Game {
@@ -25,6 +29,19 @@ impl ContractState for Game {
 }
 
 impl Game {
+    pub fn get_status(&self) -> Result<GameStatus, UnknownGameStatus> {
+        self.status.parse()
+    }
+    /// Returns the validated `status` update for transitioning to the given status.
+    /// Fails for transitions the lifecycle doesn't allow e.g. replayed or reordered events.
+    pub fn get_status_transition(
+        &self,
+        to: GameStatus,
+    ) -> Result<(String, String), EventHandlerError> {
+        let status = self.get_status()?.transition_to(to)?;
+
+        Ok(("status".to_string(), status.into()))
+    }
     pub fn get_unavailable_coin_side(&self, coin_sides: &Vec<u8>) -> Option<u8> {
         self.unavailable_coin_side.or_else(|| {
             if CoinSides::is_all_same(coin_sides) && self.has_one_play_left(coin_sides) {
@@ -39,6 +56,9 @@ impl Game {
     }
 }
 
+/// `coinflip_games` is created and dropped (on reset) by chaindexing, so its column
+/// changes and indexes live here rather than in ark-db's migrations.
+/// These run on every boot, so each of them must be idempotent.
 pub struct GameMigrations;
 
 impl ContractStateMigrations for GameMigrations {
@@ -60,8 +80,59 @@ impl ContractStateMigrations for GameMigrations {
                 winner_address VARCHAR,
                 refunded_amount_per_player VARCHAR,
                 refunded_at BIGINT,
-                chain_agnostic_index BIGSERIAL
+                chain_agnostic_index BIGSERIAL,
+                created_at BIGINT NOT NULL,
+                status VARCHAR NOT NULL,
+                all_seats_filled_at BIGINT
             )",
+            // Games indexed before these columns existed keep a `created_at` of 0
+            // and no `all_seats_filled_at` until the next reset reindexes them
+            "ALTER TABLE coinflip_games
+                ADD COLUMN IF NOT EXISTS created_at BIGINT NOT NULL DEFAULT 0",
+            "ALTER TABLE coinflip_games
+                ADD COLUMN IF NOT EXISTS all_seats_filled_at BIGINT",
+            "ALTER TABLE coinflip_games ADD COLUMN IF NOT EXISTS status VARCHAR",
+            "UPDATE coinflip_games
+                SET status = CASE
+                    WHEN refunded_at IS NOT NULL THEN 'refunded'
+                    WHEN completed_at IS NOT NULL THEN 'completed'
+                    WHEN play_count = number_of_players THEN 'awaiting_revealed_chances'
+                    ELSE 'awaiting_players'
+                END
+                WHERE status IS NULL",
+            "ALTER TABLE coinflip_games ALTER COLUMN status SET NOT NULL",
+            // chaindexing keeps every version of a game in a table with the same columns
+            "ALTER TABLE chaindexing_state_versions_for_coinflip_games
+                ADD COLUMN IF NOT EXISTS created_at BIGINT NOT NULL DEFAULT 0",
+            "ALTER TABLE chaindexing_state_versions_for_coinflip_games
+                ADD COLUMN IF NOT EXISTS all_seats_filled_at BIGINT",
+            "ALTER TABLE chaindexing_state_versions_for_coinflip_games
+                ADD COLUMN IF NOT EXISTS status VARCHAR",
+            "UPDATE chaindexing_state_versions_for_coinflip_games
+                SET status = CASE
+                    WHEN refunded_at IS NOT NULL THEN 'refunded'
+                    WHEN completed_at IS NOT NULL THEN 'completed'
+                    WHEN play_count = number_of_players THEN 'awaiting_revealed_chances'
+                    ELSE 'awaiting_players'
+                END
+                WHERE status IS NULL",
+            "ALTER TABLE chaindexing_state_versions_for_coinflip_games
+                ALTER COLUMN status SET NOT NULL",
+            "CREATE INDEX IF NOT EXISTS coinflip_games_chain_id_id_index
+                ON coinflip_games(chain_id, id)",
+            "CREATE INDEX IF NOT EXISTS coinflip_games_chain_agnostic_index_index
+                ON coinflip_games(chain_agnostic_index)",
+            "CREATE INDEX IF NOT EXISTS coinflip_games_status_expiry_timestamp_index
+                ON coinflip_games(status, expiry_timestamp)",
+            "CREATE INDEX IF NOT EXISTS coinflip_games_all_seats_filled_at_index
+                ON coinflip_games(all_seats_filled_at)
+                WHERE status = 'awaiting_revealed_chances'",
+            "CREATE INDEX IF NOT EXISTS coinflip_games_lobby_wager_index
+                ON coinflip_games((CAST(wager AS NUMERIC)))
+                WHERE status = 'awaiting_players'",
+            "CREATE INDEX IF NOT EXISTS coinflip_games_lobby_players_left_index
+                ON coinflip_games((number_of_players - play_count))
+                WHERE status = 'awaiting_players'",
         ]
     }
 }
@@ -97,6 +168,10 @@ impl ContractStateMigrations for GamePlayMigrations {
                 status VARCHAR NOT NULL,
                 chance_and_salt VARCHAR,
             )",
+            "CREATE INDEX IF NOT EXISTS coinflip_game_plays_game_id_chain_id_player_address_index
+                ON coinflip_game_plays(game_id, chain_id, player_address)",
+            "CREATE INDEX IF NOT EXISTS coinflip_game_plays_player_address_index
+                ON coinflip_game_plays(player_address, game_id, chain_id)",
         ]
     }
 }
@@ -0,0 +1,274 @@
+mod harness;
+
+use ark_utils::clocks::Clock;
+use coinflip::GameStatus;
+use harness::fixtures::*;
+use harness::{Harness, GENESIS_TIMESTAMP};
+
+const GAME_ID: u64 = 1;
+const EXPIRY_TIMESTAMP: i64 = GENESIS_TIMESTAMP + 60 * 60;
+
+#[tokio::test]
+async fn completes_a_game_once_all_chances_are_revealed() {
+    let mut harness = Harness::new().await;
+
+    harness
+        .handle(vec![
+            game_created(GAME_ID, ALICE, 2, EXPIRY_TIMESTAMP, ONE_ETHER),
+            game_play_created(GAME_ID, 0, ALICE, HEAD),
+            game_play_created(GAME_ID, 1, BOB, TAIL),
+        ])
+        .await;
+
+    let game = harness.get_game(GAME_ID).await;
+    assert_eq!(game.status, GameStatus::AwaitingRevealedChances);
+    assert_eq!(game.play_count, 2);
+    assert!(game.all_seats_filled_at.is_some());
+
+    harness
+        .handle(vec![
+            game_play_chance_revealed(GAME_ID, 0),
+            game_play_chance_revealed(GAME_ID, 1),
+            game_completed(GAME_ID, HEAD, "1900000000000000000"),
+        ])
+        .await;
+
+    harness.assert_nothing_quarantined().await;
+
+    let game = harness.get_game(GAME_ID).await;
+    assert_eq!(game.status, GameStatus::Completed);
+    assert_eq!(game.outcome, Some(HEAD as i32));
+    assert_eq!(
+        game.amount_for_each_winner.as_deref(),
+        Some("1900000000000000000")
+    );
+    assert_eq!(game.completed_at, Some(harness.get_block_timestamp()));
+
+    let mut game_plays = harness.get_game_plays(GAME_ID).await;
+    game_plays.sort_by_key(|game_play| game_play.id);
+    let game_play_statuses: Vec<_> =
+        game_plays.iter().map(|game_play| game_play.status.as_str()).collect();
+    assert_eq!(game_play_statuses, vec!["won", "lost"]);
+    assert!(game_plays.iter().all(|game_play| game_play.chance_and_salt.is_some()));
+
+    assert_eq!(
+        harness.get_game_activity_kinds(GAME_ID).await,
+        vec![
+            "game_created",
+            "game_play_created",
+            "game_play_created",
+            "game_play_chance_revealed",
+            "game_play_chance_revealed",
+            "game_completed",
+            "wallet_credited",
+        ]
+    );
+
+    harness.teardown().await;
+}
+
+#[tokio::test]
+async fn counts_head_and_tail_plays_separately() {
+    let mut harness = Harness::new().await;
+
+    harness
+        .handle(vec![
+            game_created(GAME_ID, ALICE, 3, EXPIRY_TIMESTAMP, ONE_ETHER),
+            game_play_created(GAME_ID, 0, ALICE, HEAD),
+        ])
+        .await;
+
+    let game = harness.get_game(GAME_ID).await;
+    assert_eq!((game.head_play_count, game.tail_play_count), (1, 0));
+
+    harness.handle(vec![game_play_created(GAME_ID, 1, BOB, TAIL)]).await;
+
+    let game = harness.get_game(GAME_ID).await;
+    assert_eq!((game.head_play_count, game.tail_play_count), (1, 1));
+    assert_eq!(game.unavailable_coin_side, None);
+
+    harness.handle(vec![game_play_created(GAME_ID, 2, CAROL, TAIL)]).await;
+
+    let game = harness.get_game(GAME_ID).await;
+    assert_eq!((game.head_play_count, game.tail_play_count), (1, 2));
+    assert_eq!(game.play_count, 3);
+
+    harness.assert_nothing_quarantined().await;
+    harness.teardown().await;
+}
+
+#[tokio::test]
+async fn makes_a_coin_side_unavailable_when_every_other_player_picked_it() {
+    let mut harness = Harness::new().await;
+
+    harness
+        .handle(vec![
+            game_created(GAME_ID, ALICE, 3, EXPIRY_TIMESTAMP, ONE_ETHER),
+            game_play_created(GAME_ID, 0, ALICE, TAIL),
+            game_play_created(GAME_ID, 1, BOB, TAIL),
+        ])
+        .await;
+
+    let game = harness.get_game(GAME_ID).await;
+    assert_eq!(game.unavailable_coin_side, Some(TAIL as i32));
+    assert_eq!(game.status, GameStatus::AwaitingPlayers);
+
+    harness.assert_nothing_quarantined().await;
+    harness.teardown().await;
+}
+
+#[tokio::test]
+async fn refunds_every_player_of_an_expired_game() {
+    let mut harness = Harness::new().await;
+
+    harness
+        .handle(vec![
+            game_created(GAME_ID, ALICE, 3, EXPIRY_TIMESTAMP, ONE_ETHER),
+            game_play_created(GAME_ID, 0, ALICE, HEAD),
+            game_play_created(GAME_ID, 1, BOB, HEAD),
+            expired_game_refunded(GAME_ID, ONE_ETHER),
+        ])
+        .await;
+
+    harness.assert_nothing_quarantined().await;
+
+    let game = harness.get_game(GAME_ID).await;
+    assert_eq!(game.status, GameStatus::Refunded);
+    assert_eq!(game.refunded_amount_per_player.as_deref(), Some(ONE_ETHER));
+    assert_eq!(game.refunded_at, Some(harness.get_block_timestamp()));
+
+    let game_plays = harness.get_game_plays(GAME_ID).await;
+    assert_eq!(game_plays.len(), 2);
+    assert!(game_plays.iter().all(|game_play| game_play.status == "expired"));
+
+    assert_eq!(
+        harness.get_game_activity_kinds(GAME_ID).await,
+        vec![
+            "game_created",
+            "game_play_created",
+            "game_play_created",
+            "game_refunded",
+            "wallet_credited",
+            "wallet_credited",
+        ]
+    );
+
+    harness.teardown().await;
+}
+
+#[tokio::test]
+async fn adjusts_a_game_expiry() {
+    let mut harness = Harness::new().await;
+    let new_expiry_timestamp = EXPIRY_TIMESTAMP + 60 * 60;
+
+    harness
+        .handle(vec![
+            game_created(GAME_ID, ALICE, 2, EXPIRY_TIMESTAMP, ONE_ETHER),
+            game_expiry_adjusted(GAME_ID, new_expiry_timestamp),
+        ])
+        .await;
+
+    harness.assert_nothing_quarantined().await;
+
+    let game = harness.get_game(GAME_ID).await;
+    assert_eq!(game.expiry_timestamp, new_expiry_timestamp);
+    assert_eq!(game.status, GameStatus::AwaitingPlayers);
+
+    assert_eq!(
+        harness.get_game_activity_kinds(GAME_ID).await,
+        vec!["game_created", "game_expiry_adjusted"]
+    );
+
+    harness.teardown().await;
+}
+
+#[tokio::test]
+async fn quarantines_plays_for_unknown_games_and_keeps_indexing() {
+    let mut harness = Harness::new().await;
+    let unknown_game_id = GAME_ID + 1;
+
+    harness
+        .handle(vec![
+            game_play_created(unknown_game_id, 0, ALICE, HEAD),
+            game_created(GAME_ID, ALICE, 2, EXPIRY_TIMESTAMP, ONE_ETHER),
+        ])
+        .await;
+
+    let quarantined_events = harness.get_quarantined_events().await;
+    assert_eq!(quarantined_events.len(), 1);
+    assert_eq!(
+        quarantined_events[0].handler_name,
+        "GamePlayCreatedEventHandler"
+    );
+    assert_eq!(
+        quarantined_events[0].error,
+        format!("Game:{unknown_game_id} has not been indexed")
+    );
+    assert_eq!(quarantined_events[0].first_failed_at, harness.clock.now());
+    assert!(harness.get_game_plays(unknown_game_id).await.is_empty());
+
+    assert_eq!(
+        harness.get_game(GAME_ID).await.status,
+        GameStatus::AwaitingPlayers
+    );
+
+    harness.teardown().await;
+}
+
+#[tokio::test]
+async fn quarantines_events_the_game_lifecycle_rejects() {
+    let mut harness = Harness::new().await;
+
+    harness
+        .handle(vec![
+            game_created(GAME_ID, ALICE, 2, EXPIRY_TIMESTAMP, ONE_ETHER),
+            game_play_created(GAME_ID, 0, ALICE, HEAD),
+            expired_game_refunded(GAME_ID, ONE_ETHER),
+            game_completed(GAME_ID, HEAD, "1900000000000000000"),
+        ])
+        .await;
+
+    let quarantined_events = harness.get_quarantined_events().await;
+    assert_eq!(quarantined_events.len(), 1);
+    assert_eq!(
+        quarantined_events[0].handler_name,
+        "GameCompletedEventHandler"
+    );
+    assert_eq!(
+        quarantined_events[0].error,
+        "Invalid state: Invalid game status transition from Refunded to Completed"
+    );
+
+    let game = harness.get_game(GAME_ID).await;
+    assert_eq!(game.status, GameStatus::Refunded);
+    assert_eq!(game.outcome, None);
+
+    harness.teardown().await;
+}
+
+#[tokio::test]
+async fn handles_a_retried_play_only_once() {
+    let mut harness = Harness::new().await;
+
+    harness
+        .handle(vec![
+            game_created(GAME_ID, ALICE, 2, EXPIRY_TIMESTAMP, ONE_ETHER),
+            game_play_created(GAME_ID, 0, ALICE, HEAD),
+        ])
+        .await;
+    harness.handle_again(game_play_created(GAME_ID, 0, ALICE, HEAD)).await;
+
+    harness.assert_nothing_quarantined().await;
+
+    let game = harness.get_game(GAME_ID).await;
+    assert_eq!(game.status, GameStatus::AwaitingPlayers);
+    assert_eq!(game.play_count, 1);
+    assert_eq!(game.head_play_count, 1);
+    assert_eq!(harness.get_game_plays(GAME_ID).await.len(), 1);
+    assert_eq!(
+        harness.get_game_activity_kinds(GAME_ID).await,
+        vec!["game_created", "game_play_created"]
+    );
+
+    harness.teardown().await;
+}
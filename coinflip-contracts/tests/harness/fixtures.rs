@@ -0,0 +1,144 @@
+use ark_contracts::events::{Credit, Debit};
+use ark_web3::events::TypedEvent;
+use coinflip_contracts::coinflip::events::{
+    ExpiredGameRefunded, GameCompleted, GameCreated, GameExpiryAdjusted, GamePlayChanceRevealed,
+    GamePlayCreated,
+};
+use ethers::abi::Token;
+use ethers::types::{Address, U256};
+
+pub const ALICE: &str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+pub const BOB: &str = "0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc";
+pub const CAROL: &str = "0x90f79bf6eb2c4f870365e785982e1f101e93b906";
+
+pub const COINFLIP_CONTRACT_ADDRESS: &str = "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512";
+pub const WALLETS_CONTRACT_ADDRESS: &str = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+
+pub const HEAD: u8 = 0;
+pub const TAIL: u8 = 1;
+
+/// 1 ether in wei
+pub const ONE_ETHER: &str = "1000000000000000000";
+
+/// A contract event with its params in ABI order
+#[derive(Clone, Debug)]
+pub struct EventFixture {
+    pub contract_name: &'static str,
+    pub signature: &'static str,
+    pub tokens: Vec<Token>,
+}
+
+impl EventFixture {
+    pub fn get_contract_address(&self) -> String {
+        match self.contract_name {
+            "Coinflip" => COINFLIP_CONTRACT_ADDRESS.to_lowercase(),
+            "Wallets" => WALLETS_CONTRACT_ADDRESS.to_lowercase(),
+            contract_name => unimplemented!("No address for Contract:{contract_name}"),
+        }
+    }
+}
+
+pub fn game_created(
+    game_id: u64,
+    creator_address: &str,
+    number_of_players: u16,
+    expiry_timestamp: i64,
+    wager: &str,
+) -> EventFixture {
+    coinflip_event(
+        GameCreated::SIGNATURE,
+        vec![
+            uint(game_id),
+            address(creator_address),
+            uint(number_of_players),
+            uint(expiry_timestamp as u64),
+            wei(wager),
+        ],
+    )
+}
+
+pub fn game_play_created(
+    game_id: u64,
+    game_play_id: u16,
+    player_address: &str,
+    coin_side: u8,
+) -> EventFixture {
+    coinflip_event(
+        GamePlayCreated::SIGNATURE,
+        vec![
+            uint(game_id),
+            uint(game_play_id),
+            address(player_address),
+            uint(coin_side),
+            Token::FixedBytes(vec![game_play_id as u8; 32]),
+        ],
+    )
+}
+
+pub fn game_play_chance_revealed(game_id: u64, game_play_id: u16) -> EventFixture {
+    coinflip_event(
+        GamePlayChanceRevealed::SIGNATURE,
+        vec![
+            uint(game_id),
+            uint(game_play_id),
+            Token::Bytes(vec![game_play_id as u8; 64]),
+        ],
+    )
+}
+
+pub fn game_completed(game_id: u64, coin_side: u8, amount_for_each_winner: &str) -> EventFixture {
+    coinflip_event(
+        GameCompleted::SIGNATURE,
+        vec![uint(game_id), uint(coin_side), wei(amount_for_each_winner)],
+    )
+}
+
+pub fn expired_game_refunded(game_id: u64, refunded_amount_per_player: &str) -> EventFixture {
+    coinflip_event(
+        ExpiredGameRefunded::SIGNATURE,
+        vec![uint(game_id), wei(refunded_amount_per_player)],
+    )
+}
+
+pub fn game_expiry_adjusted(game_id: u64, expiry_timestamp: i64) -> EventFixture {
+    coinflip_event(
+        GameExpiryAdjusted::SIGNATURE,
+        vec![uint(game_id), uint(expiry_timestamp as u64)],
+    )
+}
+
+pub fn credit(owner_address: &str, amount: &str) -> EventFixture {
+    wallets_event(Credit::SIGNATURE, vec![address(owner_address), wei(amount)])
+}
+
+pub fn debit(owner_address: &str, amount: &str) -> EventFixture {
+    wallets_event(Debit::SIGNATURE, vec![address(owner_address), wei(amount)])
+}
+
+fn coinflip_event(signature: &'static str, tokens: Vec<Token>) -> EventFixture {
+    EventFixture {
+        contract_name: "Coinflip",
+        signature,
+        tokens,
+    }
+}
+
+fn wallets_event(signature: &'static str, tokens: Vec<Token>) -> EventFixture {
+    EventFixture {
+        contract_name: "Wallets",
+        signature,
+        tokens,
+    }
+}
+
+fn uint(value: impl Into<U256>) -> Token {
+    Token::Uint(value.into())
+}
+
+fn wei(amount: &str) -> Token {
+    Token::Uint(U256::from_dec_str(amount).unwrap())
+}
+
+fn address(address: &str) -> Token {
+    Token::Address(address.parse::<Address>().unwrap())
+}
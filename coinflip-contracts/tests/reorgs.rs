@@ -0,0 +1,55 @@
+mod harness;
+
+use harness::fixtures::*;
+use harness::{Harness, GENESIS_TIMESTAMP};
+
+const GAME_ID: u64 = 1;
+const EXPIRY_TIMESTAMP: i64 = GENESIS_TIMESTAMP + 60 * 60;
+
+// The harness doesn't store `chaindexing_events`, so every activity after a reorg counts as dropped
+#[tokio::test]
+async fn rolls_back_game_activities_once_per_handled_reorg() {
+    let mut harness = Harness::new().await;
+
+    harness
+        .handle(vec![game_created(
+            GAME_ID,
+            ALICE,
+            2,
+            EXPIRY_TIMESTAMP,
+            ONE_ETHER,
+        )])
+        .await;
+
+    // Reorgs from before the first run may have had their events pruned since
+    harness.insert_reorged_block(1).await;
+    harness.handle_reorged_blocks().await;
+    assert_eq!(rollback_reorged_game_activities(&harness).await, 0);
+
+    harness
+        .handle(vec![
+            game_play_created(GAME_ID, 0, ALICE, HEAD),
+            game_play_created(GAME_ID, 1, BOB, TAIL),
+        ])
+        .await;
+
+    harness.insert_reorged_block(3).await;
+    assert_eq!(rollback_reorged_game_activities(&harness).await, 0);
+
+    harness.handle_reorged_blocks().await;
+    assert_eq!(rollback_reorged_game_activities(&harness).await, 1);
+    assert_eq!(rollback_reorged_game_activities(&harness).await, 0);
+
+    assert_eq!(
+        harness.get_game_activity_kinds(GAME_ID).await,
+        vec!["game_created", "game_play_created"]
+    );
+
+    harness.teardown().await;
+}
+
+async fn rollback_reorged_game_activities(harness: &Harness) -> usize {
+    coinflip_repo::rollback_reorged_game_activities(&mut harness.get_conn().await)
+        .await
+        .unwrap()
+}
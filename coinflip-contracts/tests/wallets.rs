@@ -0,0 +1,60 @@
+mod harness;
+
+use diesel_async::SimpleAsyncConnection;
+use harness::fixtures::*;
+use harness::Harness;
+
+#[tokio::test]
+async fn credits_and_debits_wallet_balances() {
+    let mut harness = Harness::new().await;
+
+    harness
+        .handle(vec![
+            credit(ALICE, "2500000000000000000"),
+            credit(BOB, ONE_ETHER),
+            debit(ALICE, ONE_ETHER),
+        ])
+        .await;
+
+    harness.assert_nothing_quarantined().await;
+
+    let alice_wallet = harness.get_wallet(ALICE).await.unwrap();
+    assert_eq!(alice_wallet.get_balance_ether(), 1.5);
+
+    let bob_wallet = harness.get_wallet(BOB).await.unwrap();
+    assert_eq!(bob_wallet.get_balance_ether(), 1.0);
+
+    assert!(harness.get_wallet(CAROL).await.is_none());
+
+    harness.teardown().await;
+}
+
+#[tokio::test]
+async fn quarantines_credits_without_crediting_when_their_currency_is_missing() {
+    let mut harness = Harness::new().await;
+    harness
+        .get_conn()
+        .await
+        .batch_execute("DELETE FROM ark_chain_currencies")
+        .await
+        .unwrap();
+
+    harness.handle(vec![credit(ALICE, ONE_ETHER)]).await;
+
+    let quarantined_events = harness.get_quarantined_events().await;
+    assert_eq!(quarantined_events.len(), 1);
+    assert_eq!(
+        quarantined_events[0].handler_name,
+        "CreditWalletEventHandler"
+    );
+
+    // Retrying it later must not find the credit already applied
+    assert!(harness.get_wallet(ALICE).await.is_none());
+    let last_total_paid_out_report =
+        ark_repo::get_last_total_paid_out_report(&mut harness.get_conn().await)
+            .await
+            .unwrap();
+    assert!(last_total_paid_out_report.is_none());
+
+    harness.teardown().await;
+}
@@ -0,0 +1,189 @@
+use ark_db::schema::coinflip_games;
+use ark_web3::chains::ChainId;
+use coinflip::{GameSort, GameStatus, GetGamesParams, MAX_GAMES_COUNT};
+
+use diesel::dsl::{not, sql};
+use diesel::pg::Pg;
+use diesel::sql_types::{Bool, Numeric, Text};
+use diesel::{BoolExpressionMethods, BoxableExpression, ExpressionMethods, QueryDsl};
+
+/// Every param is an independent filter, so any subset of them combines
+pub fn get_games_query<'a>(
+    params: &GetGamesParams,
+    now: i64,
+) -> coinflip_games::BoxedQuery<'a, Pg> {
+    use ark_db::schema::coinflip_games::dsl::*;
+
+    let mut query = coinflip_games.into_boxed();
+
+    if let Some(status_) = params.status {
+        query = query.filter(status_filter(status_, now));
+    }
+    if let Some(reject_status) = params.reject_status {
+        query = query.filter(not(status_filter(reject_status, now)));
+    }
+    if let Some(is_completed) = params.is_completed {
+        query = if is_completed {
+            query.filter(completed_at.is_not_null())
+        } else {
+            query.filter(completed_at.is_null())
+        };
+    }
+    if let Some(is_refunded) = params.is_refunded {
+        query = if is_refunded {
+            query.filter(refunded_at.is_not_null())
+        } else {
+            query.filter(refunded_at.is_null())
+        };
+    }
+    if let Some(player_address) = &params.player_address {
+        query = query.filter(
+            sql::<Bool>(
+                "EXISTS (SELECT 1 FROM coinflip_game_plays
+                    WHERE coinflip_game_plays.game_id = coinflip_games.id
+                    AND coinflip_game_plays.chain_id = coinflip_games.chain_id
+                    AND coinflip_game_plays.player_address = ",
+            )
+            .bind::<Text, _>(player_address.to_lowercase())
+            .sql(")"),
+        );
+    }
+    if let Some(creator_address_) = &params.creator_address {
+        query = query.filter(creator_address.eq(creator_address_.to_lowercase()));
+    }
+    if let Some(chain_ids) = &params.chain_ids {
+        query = query.filter(chain_id.eq_any(chain_ids.clone()));
+    }
+    if let Some(chain_ids_to_ignore) = &params.chain_ids_to_ignore {
+        query = query.filter(chain_id.ne_all(chain_ids_to_ignore.clone()));
+    }
+    if let Some(chain_id_to_ignore) = params.chain_id_to_ignore {
+        query = query.filter(chain_id.ne(chain_id_to_ignore));
+    }
+    if let Some(min_wager) = params.min_wager {
+        query = query.filter(wager_filter(">=", min_wager));
+    }
+    if let Some(max_wager) = params.max_wager {
+        query = query.filter(wager_filter("<=", max_wager));
+    }
+    if let Some(min_wager_usd) = params.min_wager_usd {
+        query = query.filter(wager_usd_filter(">=", min_wager_usd));
+    }
+    if let Some(max_wager_usd) = params.max_wager_usd {
+        query = query.filter(wager_usd_filter("<=", max_wager_usd));
+    }
+    if let Some(number_of_players_) = params.number_of_players {
+        query = query.filter(number_of_players.eq(number_of_players_));
+    }
+    if let Some(coin_side) = params.available_coin_side {
+        query =
+            query.filter(unavailable_coin_side.is_null().or(unavailable_coin_side.ne(coin_side)));
+    }
+    if let Some(min_expires_in_secs) = params.min_expires_in_secs {
+        query = query.filter(expiry_timestamp.ge(now + min_expires_in_secs));
+    }
+    if let Some(max_expires_in_secs) = params.max_expires_in_secs {
+        query = query.filter(expiry_timestamp.le(now + max_expires_in_secs));
+    }
+    if let Some(created_after) = params.created_after {
+        query = query.filter(created_at.gt(created_after));
+    }
+    if let Some(created_before) = params.created_before {
+        query = query.filter(created_at.lt(created_before));
+    }
+    if let Some(id_to_ignore) = params.id_to_ignore {
+        query = query.filter(id.ne(id_to_ignore));
+    }
+
+    query = match params.sort.unwrap_or_default() {
+        GameSort::Newest => query.order_by(chain_agnostic_index.desc()),
+        GameSort::Oldest => query.order_by(chain_agnostic_index.asc()),
+        GameSort::SoonestExpiry => query
+            .order_by(expiry_timestamp.asc())
+            .then_order_by(chain_agnostic_index.desc()),
+        GameSort::LargestWager => query
+            .order_by(sql::<Numeric>("CAST(coinflip_games.wager AS NUMERIC)").desc())
+            .then_order_by(chain_agnostic_index.desc()),
+        GameSort::FewestPlayersLeft => query
+            .order_by((number_of_players - play_count).asc())
+            .then_order_by(chain_agnostic_index.desc()),
+    };
+
+    if !params.is_unbounded {
+        let page_size = params.page_size.unwrap_or(MAX_GAMES_COUNT).clamp(1, MAX_GAMES_COUNT);
+        query = query.limit(page_size);
+    }
+    if let Some(offset) = params.offset {
+        query = query.offset(offset as i64);
+    }
+
+    query
+}
+
+type GamesFilter = Box<dyn BoxableExpression<coinflip_games::table, Pg, SqlType = Bool>>;
+
+fn status_filter(status: GameStatus, now: i64) -> GamesFilter {
+    let filtered_statuses: Vec<_> = status
+        .get_filtered_statuses()
+        .into_iter()
+        .map(|status| format!("'{}'", <GameStatus as Into<&str>>::into(status)))
+        .collect();
+
+    Box::new(sql::<Bool>(&format!(
+        "{} IN ({})",
+        get_status_at_sql(now),
+        filtered_statuses.join(", ")
+    )))
+}
+
+/// A game's status at `now` in SQL, the one place `coinflip::Game::get_status_at` is mirrored.
+/// Expiry is never stored, so it is derived from awaiting games that are past their expiry.
+pub(crate) fn get_status_at_sql(now: i64) -> String {
+    let status = |status: GameStatus| -> &'static str { status.into() };
+
+    format!(
+        "CASE
+            WHEN coinflip_games.status IN ('{awaiting_players}', '{awaiting_revealed_chances}')
+                AND coinflip_games.expiry_timestamp <= {now}
+                THEN '{expired_pending_refund}'
+            ELSE coinflip_games.status
+        END",
+        awaiting_players = status(GameStatus::AwaitingPlayers),
+        awaiting_revealed_chances = status(GameStatus::AwaitingRevealedChances),
+        expired_pending_refund = status(GameStatus::ExpiredPendingRefund),
+    )
+}
+
+/// `wager` is stored as a wei string, so it is compared numerically.
+/// Converted to wei in SQL, as `f64` can't hold wei amounts exactly.
+fn wager_filter(operator: &str, wager_ether: f64) -> GamesFilter {
+    Box::new(
+        sql::<Bool>(&format!(
+            "CAST(coinflip_games.wager AS NUMERIC) {operator} CAST("
+        ))
+        .bind::<Text, _>(wager_ether.to_string())
+        .sql(" AS NUMERIC) * 1e18"),
+    )
+}
+
+/// Local chains are priced with Ethereum's currency, see `coinflip::Game::get_chain_id`
+fn wager_usd_filter(operator: &str, wager_usd: f64) -> GamesFilter {
+    Box::new(
+        sql::<Bool>(&format!(
+            "CAST(coinflip_games.wager AS NUMERIC) / 1e18 * (
+                SELECT CAST(ark_chain_currencies.unit_usd_price AS NUMERIC)
+                FROM ark_chain_currencies
+                WHERE ark_chain_currencies.chain_id = CASE
+                    WHEN coinflip_games.chain_id IN ({local}, {local_alt}) THEN {ethereum}
+                    ELSE coinflip_games.chain_id
+                END
+                LIMIT 1
+            ) {operator} CAST(",
+            local = ChainId::Local as i64,
+            local_alt = ChainId::LocalAlt as i64,
+            ethereum = ChainId::Ethereum as i64,
+        ))
+        .bind::<Text, _>(wager_usd.to_string())
+        .sql(" AS NUMERIC)"),
+    )
+}
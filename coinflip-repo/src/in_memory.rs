@@ -0,0 +1,637 @@
+use std::sync::{Arc, RwLock};
+
+use ark_db::RepoResult;
+use ark_repo::InMemoryArkRepo;
+use ark_utils::clocks::Clock;
+use async_trait::async_trait;
+
+use coinflip::{ActivityRepo, GamePlayRepo, GameRepo, GameSort, GetGamesParams, MAX_GAMES_COUNT};
+use coinflip::{Game, GameActivity, GamePlay, GameStatus, UnsavedGameActivity};
+use coinflip::{
+    GetHouseRevenueReportsParams, GetPlayerActionsParams, GetPlayerGameActivitiesParams,
+};
+use coinflip::{HouseRevenueReport, PlayerAction, PlayerActionKind};
+use coinflip::{PendingReveal, RevealReminder, RevealReminderRepo, UnsavedRevealReminder};
+use coinflip::{ServiceCharge, ServiceChargeSchedule, SubmittedChance, UnsavedSubmittedChance};
+
+/// Mirrors `PostgresCoinflipRepo` without a database e.g. for handler and worker tests.
+/// Games and their plays are only ever written by the indexer, so they are seeded directly.
+pub struct InMemoryCoinflipRepo {
+    games: RwLock<Vec<Game>>,
+    game_plays: RwLock<Vec<GamePlay>>,
+    submitted_chances: RwLock<Vec<SubmittedChance>>,
+    /// By chain id, lowercase player address and nonce
+    used_reveal_nonces: RwLock<Vec<(i64, String, String)>>,
+    game_activities: RwLock<Vec<GameActivity>>,
+    service_charges: RwLock<Vec<ServiceCharge>>,
+    reveal_reminders: RwLock<Vec<RevealReminder>>,
+    /// Prices wagers for the `min_wager_usd` and `max_wager_usd` filters
+    ark_repo: Arc<InMemoryArkRepo>,
+}
+
+impl InMemoryCoinflipRepo {
+    pub fn new(ark_repo: Arc<InMemoryArkRepo>) -> Self {
+        Self {
+            games: Default::default(),
+            game_plays: Default::default(),
+            submitted_chances: Default::default(),
+            used_reveal_nonces: Default::default(),
+            game_activities: Default::default(),
+            service_charges: Default::default(),
+            reveal_reminders: Default::default(),
+            ark_repo,
+        }
+    }
+
+    pub fn insert_game(&self, game: Game) {
+        self.games.write().unwrap().push(game);
+    }
+    pub fn insert_game_play(&self, game_play: GamePlay) {
+        self.game_plays.write().unwrap().push(game_play);
+    }
+    pub fn insert_service_charge(&self, service_charge: ServiceCharge) {
+        self.service_charges.write().unwrap().push(service_charge);
+    }
+
+    fn matches(&self, params: &GetGamesParams, game: &Game, now: i64) -> bool {
+        let status = game.get_status_at(now);
+
+        params
+            .status
+            .is_none_or(|status_| status_.get_filtered_statuses().contains(&status))
+            && params.reject_status.is_none_or(|reject_status| {
+                !reject_status.get_filtered_statuses().contains(&status)
+            })
+            && params
+                .is_completed
+                .is_none_or(|is_completed| game.completed_at.is_some() == is_completed)
+            && params
+                .is_refunded
+                .is_none_or(|is_refunded| game.refunded_at.is_some() == is_refunded)
+            && params
+                .player_address
+                .as_ref()
+                .is_none_or(|player_address| self.has_player(game, &player_address.to_lowercase()))
+            && params.creator_address.as_ref().is_none_or(|creator_address| {
+                game.creator_address == creator_address.to_lowercase()
+            })
+            && params
+                .chain_ids
+                .as_ref()
+                .is_none_or(|chain_ids| chain_ids.contains(&game.chain_id))
+            && params
+                .chain_ids_to_ignore
+                .as_ref()
+                .is_none_or(|chain_ids| !chain_ids.contains(&game.chain_id))
+            && params.chain_id_to_ignore.is_none_or(|chain_id| game.chain_id != chain_id)
+            && params
+                .min_wager
+                .is_none_or(|min_wager| get_wager_wei(game) >= to_wei(min_wager))
+            && params
+                .max_wager
+                .is_none_or(|max_wager| get_wager_wei(game) <= to_wei(max_wager))
+            && params.min_wager_usd.is_none_or(|min_wager_usd| {
+                self.get_wager_usd(game).is_some_and(|wager_usd| wager_usd >= min_wager_usd)
+            })
+            && params.max_wager_usd.is_none_or(|max_wager_usd| {
+                self.get_wager_usd(game).is_some_and(|wager_usd| wager_usd <= max_wager_usd)
+            })
+            && params
+                .number_of_players
+                .is_none_or(|number_of_players| game.number_of_players == number_of_players)
+            && params
+                .available_coin_side
+                .is_none_or(|coin_side| game.unavailable_coin_side != Some(coin_side))
+            && params.min_expires_in_secs.is_none_or(|min_expires_in_secs| {
+                game.expiry_timestamp >= now + min_expires_in_secs
+            })
+            && params.max_expires_in_secs.is_none_or(|max_expires_in_secs| {
+                game.expiry_timestamp <= now + max_expires_in_secs
+            })
+            && params.created_after.is_none_or(|created_after| game.created_at > created_after)
+            && params
+                .created_before
+                .is_none_or(|created_before| game.created_at < created_before)
+            && params.id_to_ignore.is_none_or(|id_to_ignore| game.id != id_to_ignore)
+    }
+
+    fn has_player(&self, game: &Game, player_address: &str) -> bool {
+        self.game_plays.read().unwrap().iter().any(|game_play| {
+            game_play.game_id == game.id
+                && game_play.chain_id == game.chain_id
+                && game_play.player_address == player_address
+        })
+    }
+
+    fn has_submitted_chance(&self, game_play: &GamePlay) -> bool {
+        self.submitted_chances.read().unwrap().iter().any(|submitted_chance| {
+            submitted_chance.game_id == game_play.game_id
+                && submitted_chance.chain_id == game_play.chain_id
+                && submitted_chance.game_play_id == game_play.id
+        })
+    }
+
+    /// The player's plays along with their games
+    fn get_player_games(&self, player_address: &str) -> Vec<(Game, GamePlay)> {
+        let player_address = player_address.to_lowercase();
+        let games = self.games.read().unwrap();
+
+        self.game_plays
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|game_play| game_play.player_address == player_address)
+            .filter_map(|game_play| {
+                games
+                    .iter()
+                    .find(|game| {
+                        game.id == game_play.game_id && game.chain_id == game_play.chain_id
+                    })
+                    .map(|game| (game.clone(), game_play.clone()))
+            })
+            .collect()
+    }
+
+    /// Local chains are priced with Ethereum's currency, see `coinflip::Game::get_chain_id`
+    fn get_wager_usd(&self, game: &Game) -> Option<f64> {
+        self.ark_repo
+            .find_chain_currency(game.get_chain_id())
+            .map(|chain_currency| chain_currency.convert_to_usd(game.get_wager_ether()))
+    }
+}
+
+#[async_trait]
+impl GameRepo for InMemoryCoinflipRepo {
+    async fn get_game(&self, id: i64, chain_id: i64) -> RepoResult<Option<Game>> {
+        Ok(self
+            .games
+            .read()
+            .unwrap()
+            .iter()
+            .find(|game| game.id == id && game.chain_id == chain_id)
+            .cloned())
+    }
+    async fn get_games(&self, params: &GetGamesParams, clock: &dyn Clock) -> RepoResult<Vec<Game>> {
+        let now = clock.now();
+
+        let mut games: Vec<_> = self
+            .games
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|game| self.matches(params, game, now))
+            .cloned()
+            .collect();
+
+        let newest_first = |a: &Game, b: &Game| b.chain_agnostic_index.cmp(&a.chain_agnostic_index);
+        match params.sort.unwrap_or_default() {
+            GameSort::Newest => games.sort_by(newest_first),
+            GameSort::Oldest => games.sort_by_key(|game| game.chain_agnostic_index),
+            GameSort::SoonestExpiry => games.sort_by(|a, b| {
+                a.expiry_timestamp.cmp(&b.expiry_timestamp).then(newest_first(a, b))
+            }),
+            GameSort::LargestWager => games
+                .sort_by(|a, b| get_wager_wei(b).cmp(&get_wager_wei(a)).then(newest_first(a, b))),
+            GameSort::FewestPlayersLeft => games.sort_by(|a, b| {
+                (a.number_of_players - a.play_count)
+                    .cmp(&(b.number_of_players - b.play_count))
+                    .then(newest_first(a, b))
+            }),
+        }
+
+        let games = games.into_iter().skip(params.offset.unwrap_or(0) as usize);
+
+        if params.is_unbounded {
+            Ok(games.collect())
+        } else {
+            let page_size = params.page_size.unwrap_or(MAX_GAMES_COUNT).clamp(1, MAX_GAMES_COUNT);
+            Ok(games.take(page_size as usize).collect())
+        }
+    }
+    async fn get_total_games_count(&self) -> RepoResult<u64> {
+        Ok(self.games.read().unwrap().len() as u64)
+    }
+    async fn get_total_completed_games_count(&self) -> RepoResult<u64> {
+        Ok(self.games.read().unwrap().iter().filter(|game| game.is_completed()).count() as u64)
+    }
+    async fn get_service_charge_schedule(
+        &self,
+        chain_ids: &[i64],
+    ) -> RepoResult<ServiceChargeSchedule> {
+        Ok(ServiceChargeSchedule::new(
+            self.service_charges
+                .read()
+                .unwrap()
+                .iter()
+                .filter(|service_charge| chain_ids.contains(&service_charge.chain_id))
+                .cloned()
+                .collect(),
+        ))
+    }
+    async fn get_player_actions(
+        &self,
+        params: &GetPlayerActionsParams,
+    ) -> RepoResult<Vec<PlayerAction>> {
+        let now = params.now;
+
+        let mut player_actions: Vec<_> = self
+            .get_player_games(&params.player_address)
+            .into_iter()
+            .filter_map(|(game, game_play)| {
+                let (kind, action_at) = match game.get_status() {
+                    GameStatus::AwaitingRevealedChances
+                        if game.expiry_timestamp > now
+                            && game_play.chance_and_salt.is_none()
+                            && !self.has_submitted_chance(&game_play) =>
+                    {
+                        (
+                            PlayerActionKind::MustReveal,
+                            game.all_seats_filled_at.unwrap_or(game.created_at),
+                        )
+                    }
+                    GameStatus::AwaitingPlayers
+                        if game.expiry_timestamp > now
+                            && game.expiry_timestamp <= now + params.expiring_within_secs =>
+                    {
+                        (PlayerActionKind::ExpiringSoon, game.expiry_timestamp)
+                    }
+                    GameStatus::Refunded => (
+                        PlayerActionKind::Refunded,
+                        game.refunded_at.unwrap_or_default(),
+                    ),
+                    GameStatus::Completed if game.outcome == Some(game_play.coin_side) => {
+                        (PlayerActionKind::Won, game.completed_at.unwrap_or_default())
+                    }
+                    _ => return None,
+                };
+
+                let is_credit = matches!(kind, PlayerActionKind::Refunded | PlayerActionKind::Won);
+                if is_credit && action_at <= params.credited_since {
+                    return None;
+                }
+
+                Some(PlayerAction::new(game, game_play.id, kind, action_at))
+            })
+            .collect();
+
+        // Credits are newest first, the rest soonest first
+        player_actions.sort_by_key(|player_action| match player_action.get_kind() {
+            PlayerActionKind::MustReveal => (0, player_action.action_at),
+            PlayerActionKind::ExpiringSoon => (1, player_action.action_at),
+            PlayerActionKind::Refunded | PlayerActionKind::Won => (2, -player_action.action_at),
+        });
+
+        Ok(player_actions)
+    }
+    async fn get_house_revenue_reports(
+        &self,
+        params: &GetHouseRevenueReportsParams,
+    ) -> RepoResult<Vec<HouseRevenueReport>> {
+        let period = params.period.unwrap_or_default();
+        let service_charge_schedule =
+            ServiceChargeSchedule::new(self.service_charges.read().unwrap().clone());
+
+        let mut reports: Vec<HouseRevenueReport> = vec![];
+
+        for game in self.games.read().unwrap().iter() {
+            let Some(completed_at) = game.completed_at else {
+                continue;
+            };
+
+            if game.get_status() != GameStatus::Completed
+                || params.chain_id.is_some_and(|chain_id| game.chain_id != chain_id)
+                || params.from.is_some_and(|from| completed_at < from)
+                || params.to.is_some_and(|to| completed_at >= to)
+            {
+                continue;
+            }
+
+            let period_start = period.get_period_start(completed_at);
+            let fees_collected = game.get_wager_ether()
+                * game.number_of_players as f64
+                * service_charge_schedule.get_percent_for_game(game) as f64
+                / 100.0;
+
+            match reports.iter_mut().find(|report| {
+                report.chain_id == game.chain_id && report.period_start == period_start
+            }) {
+                Some(report) => {
+                    report.games_count += 1;
+                    report.fees_collected += fees_collected;
+                }
+                None => reports.push(HouseRevenueReport {
+                    chain_id: game.chain_id,
+                    period_start,
+                    games_count: 1,
+                    fees_collected,
+                }),
+            }
+        }
+
+        reports
+            .sort_by(|a, b| b.period_start.cmp(&a.period_start).then(a.chain_id.cmp(&b.chain_id)));
+
+        Ok(reports)
+    }
+}
+
+#[async_trait]
+impl GamePlayRepo for InMemoryCoinflipRepo {
+    async fn get_game_plays(&self, game_id: i64, chain_id: i64) -> RepoResult<Vec<GamePlay>> {
+        Ok(self
+            .game_plays
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|game_play| game_play.game_id == game_id && game_play.chain_id == chain_id)
+            .cloned()
+            .collect())
+    }
+    async fn get_game_play(
+        &self,
+        game_id: i64,
+        chain_id: i64,
+        player_address: &str,
+    ) -> RepoResult<Option<GamePlay>> {
+        let player_address = player_address.to_lowercase();
+
+        Ok(self
+            .game_plays
+            .read()
+            .unwrap()
+            .iter()
+            .find(|game_play| {
+                game_play.game_id == game_id
+                    && game_play.chain_id == chain_id
+                    && game_play.player_address == player_address
+            })
+            .cloned())
+    }
+    async fn use_reveal_nonce(
+        &self,
+        chain_id: i64,
+        player_address: &str,
+        nonce: &str,
+        _now: i64,
+    ) -> RepoResult<bool> {
+        let mut used_reveal_nonces = self.used_reveal_nonces.write().unwrap();
+        let used_reveal_nonce = (chain_id, player_address.to_lowercase(), nonce.to_string());
+
+        if used_reveal_nonces.contains(&used_reveal_nonce) {
+            Ok(false)
+        } else {
+            used_reveal_nonces.push(used_reveal_nonce);
+            Ok(true)
+        }
+    }
+    async fn create_submitted_chance(
+        &self,
+        submitted_chance: &UnsavedSubmittedChance,
+    ) -> RepoResult<bool> {
+        let mut submitted_chances = self.submitted_chances.write().unwrap();
+        let submitted_chance = submitted_chance.to_saved(submitted_chances.len() as i64 + 1);
+
+        let already_submitted = submitted_chances.iter().any(|saved_submitted_chance| {
+            saved_submitted_chance.game_id == submitted_chance.game_id
+                && saved_submitted_chance.chain_id == submitted_chance.chain_id
+                && saved_submitted_chance.game_play_id == submitted_chance.game_play_id
+        });
+
+        if !already_submitted {
+            submitted_chances.push(submitted_chance);
+        }
+
+        Ok(!already_submitted)
+    }
+    async fn get_submitted_chance(
+        &self,
+        game_play: &GamePlay,
+    ) -> RepoResult<Option<SubmittedChance>> {
+        Ok(self
+            .submitted_chances
+            .read()
+            .unwrap()
+            .iter()
+            .find(|submitted_chance| {
+                submitted_chance.game_id == game_play.game_id
+                    && submitted_chance.chain_id == game_play.chain_id
+                    && submitted_chance.game_play_id == game_play.id
+            })
+            .cloned())
+    }
+    async fn get_submitted_chances(
+        &self,
+        game_and_chain_ids: &[(i64, i64)],
+    ) -> RepoResult<Vec<SubmittedChance>> {
+        Ok(self
+            .submitted_chances
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|submitted_chance| {
+                game_and_chain_ids.contains(&(submitted_chance.game_id, submitted_chance.chain_id))
+            })
+            .cloned()
+            .collect())
+    }
+}
+
+#[async_trait]
+impl ActivityRepo for InMemoryCoinflipRepo {
+    /// Like the unique index, activities without a source event never conflict
+    async fn create_game_activity(&self, game_activity: &UnsavedGameActivity) -> RepoResult<()> {
+        let mut game_activities = self.game_activities.write().unwrap();
+
+        let is_replay = game_activity.transaction_hash.is_some()
+            && game_activity.log_index.is_some()
+            && game_activities.iter().any(|saved_game_activity| {
+                saved_game_activity.chain_id == game_activity.chain_id
+                    && saved_game_activity.transaction_hash == game_activity.transaction_hash
+                    && saved_game_activity.log_index == game_activity.log_index
+                    && saved_game_activity.kind == game_activity.kind
+                    && saved_game_activity.trigger_public_address
+                        == game_activity.trigger_public_address
+            });
+
+        if !is_replay {
+            let id = game_activities.len() as i64 + 1;
+
+            game_activities.push(GameActivity {
+                id,
+                game_id: game_activity.game_id,
+                chain_id: game_activity.chain_id,
+                trigger_public_address: game_activity.trigger_public_address.clone(),
+                kind: game_activity.kind.clone(),
+                data: game_activity.data.clone().unwrap_or_else(|| serde_json::json!({})),
+                occurred_at: game_activity.occurred_at,
+                transaction_hash: game_activity.transaction_hash.clone(),
+                log_index: game_activity.log_index,
+                block_number: game_activity.block_number,
+            });
+        }
+
+        Ok(())
+    }
+    async fn get_game_activities(
+        &self,
+        game_ids: &[i64],
+        chain_ids: &[i64],
+    ) -> RepoResult<Vec<GameActivity>> {
+        let mut game_activities: Vec<_> = self
+            .game_activities
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|game_activity| {
+                game_ids.contains(&game_activity.game_id)
+                    && chain_ids.contains(&game_activity.chain_id)
+            })
+            .cloned()
+            .collect();
+
+        game_activities.sort_by(|a, b| b.id.cmp(&a.id));
+
+        Ok(game_activities)
+    }
+    async fn get_player_game_activities(
+        &self,
+        player_address: &str,
+        game_status: Option<GameStatus>,
+        now: i64,
+        params: &GetPlayerGameActivitiesParams,
+    ) -> RepoResult<Vec<GameActivity>> {
+        let player_game_and_chain_ids: Vec<_> = self
+            .get_player_games(player_address)
+            .into_iter()
+            .filter(|(game, _game_play)| {
+                game_status.is_none_or(|game_status| game.get_status_at(now) == game_status)
+            })
+            .map(|(game, _game_play)| (game.id, game.chain_id))
+            .collect();
+
+        let mut game_activities: Vec<_> = self
+            .game_activities
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|game_activity| {
+                player_game_and_chain_ids.contains(&(game_activity.game_id, game_activity.chain_id))
+                    && params.cursor.is_none_or(|cursor| game_activity.id < cursor)
+            })
+            .cloned()
+            .collect();
+
+        game_activities.sort_by(|a, b| b.id.cmp(&a.id));
+        game_activities.truncate(params.get_page_size() as usize);
+
+        Ok(game_activities)
+    }
+}
+
+#[async_trait]
+impl RevealReminderRepo for InMemoryCoinflipRepo {
+    async fn get_pending_reveals(
+        &self,
+        now: i64,
+        blocked_before: i64,
+        player_address: Option<&str>,
+    ) -> RepoResult<Vec<PendingReveal>> {
+        let player_address = player_address.map(|address| address.to_lowercase());
+        let games = self.games.read().unwrap();
+
+        let mut pending_reveals: Vec<_> = self
+            .game_plays
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|game_play| {
+                game_play.chance_and_salt.is_none()
+                    && player_address
+                        .as_ref()
+                        .is_none_or(|player_address| &game_play.player_address == player_address)
+                    && !self.has_submitted_chance(game_play)
+            })
+            .filter_map(|game_play| {
+                let game = games.iter().find(|game| {
+                    game.id == game_play.game_id && game.chain_id == game_play.chain_id
+                })?;
+                let blocked_since = game.all_seats_filled_at?;
+
+                let is_pending = game.get_status() == GameStatus::AwaitingRevealedChances
+                    && game.expiry_timestamp > now
+                    && blocked_since <= blocked_before;
+
+                is_pending.then(|| PendingReveal {
+                    game_id: game_play.game_id,
+                    chain_id: game_play.chain_id,
+                    game_play_id: game_play.id,
+                    player_address: game_play.player_address.clone(),
+                    blocked_since,
+                })
+            })
+            .collect();
+
+        pending_reveals.sort_by_key(|pending_reveal| pending_reveal.blocked_since);
+
+        Ok(pending_reveals)
+    }
+    async fn create_or_update_reveal_reminders(
+        &self,
+        reveal_reminders: &[UnsavedRevealReminder],
+    ) -> RepoResult<()> {
+        let mut saved_reveal_reminders = self.reveal_reminders.write().unwrap();
+
+        for reveal_reminder in reveal_reminders {
+            match saved_reveal_reminders.iter_mut().find(|saved_reveal_reminder| {
+                saved_reveal_reminder.game_id == reveal_reminder.game_id
+                    && saved_reveal_reminder.chain_id == reveal_reminder.chain_id
+                    && saved_reveal_reminder.game_play_id == reveal_reminder.game_play_id
+            }) {
+                Some(saved_reveal_reminder) => {
+                    saved_reveal_reminder.last_detected_at = reveal_reminder.last_detected_at;
+                    saved_reveal_reminder.resolved_at = None;
+                }
+                None => {
+                    let id = saved_reveal_reminders.len() as i64 + 1;
+                    saved_reveal_reminders.push(reveal_reminder.to_saved(id));
+                }
+            }
+        }
+
+        Ok(())
+    }
+    async fn resolve_reveal_reminders(&self, now: i64) -> RepoResult<usize> {
+        let mut resolved_count = 0;
+
+        for reveal_reminder in self.reveal_reminders.write().unwrap().iter_mut() {
+            if reveal_reminder.resolved_at.is_none() && reveal_reminder.last_detected_at < now {
+                reveal_reminder.resolved_at = Some(now);
+                resolved_count += 1;
+            }
+        }
+
+        Ok(resolved_count)
+    }
+    async fn get_unresolved_reveal_reminders(&self) -> RepoResult<Vec<RevealReminder>> {
+        let mut reveal_reminders: Vec<_> = self
+            .reveal_reminders
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|reveal_reminder| reveal_reminder.resolved_at.is_none())
+            .cloned()
+            .collect();
+
+        reveal_reminders.sort_by_key(|reveal_reminder| reveal_reminder.blocked_since);
+
+        Ok(reveal_reminders)
+    }
+}
+
+fn get_wager_wei(game: &Game) -> u128 {
+    game.wager.parse().unwrap_or_default()
+}
+
+fn to_wei(amount_ether: f64) -> u128 {
+    format!("{:.0}", amount_ether * 1e18).parse().unwrap()
+}
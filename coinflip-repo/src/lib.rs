@@ -1,3 +1,9 @@
+mod games_query;
+mod in_memory;
 mod repo;
+mod repos;
 
+pub use games_query::*;
+pub use in_memory::*;
 pub use repo::*;
+pub use repos::*;
@@ -1,56 +1,36 @@
-use ark_db::schema;
 use ark_db::DBConn;
-
-use coinflip::UnsavedGameActivity;
-use coinflip::{Game, GameActivity, GamePlay, GameStatus};
-
-use diesel::{BoolExpressionMethods, ExpressionMethods, JoinOnDsl, OptionalExtension, QueryDsl};
+use ark_utils::clocks::Clock;
+
+use coinflip::DEFAULT_SERVICE_CHARGE_PERCENT;
+use coinflip::{Game, GameActivity, GamePlay, GamePlayStatus, GameStatus, GameWin};
+use coinflip::{GetGamesParams, GetHouseRevenueReportsParams, HouseRevenueReport};
+use coinflip::{GetPlayerActionsParams, GetPlayerGameActivitiesParams};
+use coinflip::{PendingReveal, RevealReminder, UnsavedRevealReminder};
+use coinflip::{PlayerAction, PlayerActionKind};
+use coinflip::{ServiceCharge, ServiceChargeSchedule, UnsavedGameActivity, UnsavedServiceCharge};
+use coinflip::{SubmittedChance, UnsavedSubmittedChance};
+
+use diesel::dsl::sql;
+use diesel::sql_types::{BigInt, Integer, Nullable, Text};
+use diesel::upsert::excluded;
+use diesel::{BoolExpressionMethods, ExpressionMethods, OptionalExtension, QueryDsl, QueryResult};
 use diesel_async::RunQueryDsl;
 
 use serde::Deserialize;
 
+use crate::games_query::get_status_at_sql;
+
 #[derive(Debug, Deserialize)]
 pub enum Order {
     Asc,
     Desc,
 }
 
-#[derive(Debug, Deserialize, Default)]
-pub struct GetGamesParams {
-    pub player_address: Option<String>,
-    pub page_size: Option<i64>,
-    pub id_to_ignore: Option<i64>,
-    pub status: Option<GameStatus>,
-    pub reject_status: Option<GameStatus>,
-    pub is_completed: Option<bool>,
-    pub is_refunded: Option<bool>,
-    pub chain_id_to_ignore: Option<i64>,
-    pub offset: Option<u64>,
-}
-
-impl GetGamesParams {
-    pub fn new() -> Self {
-        Default::default()
-    }
-    pub fn expired(mut self) -> Self {
-        self.status = Some(GameStatus::Expired);
-        self
-    }
-    pub fn not_expired(mut self) -> Self {
-        self.reject_status = Some(GameStatus::Expired);
-        self
-    }
-    pub fn not_refunded(mut self) -> Self {
-        self.is_refunded = Some(false);
-        self
-    }
-    pub fn only_incomplete(mut self) -> Self {
-        self.is_completed = Some(false);
-        self
-    }
-}
-
-pub async fn get_game<'a>(conn: &mut DBConn<'a>, id_: i64, chain_id_: i64) -> Option<Game> {
+pub async fn get_game<'a>(
+    conn: &mut DBConn<'a>,
+    id_: i64,
+    chain_id_: i64,
+) -> QueryResult<Option<Game>> {
     use ark_db::schema::coinflip_games::dsl::*;
 
     coinflip_games
@@ -59,207 +39,38 @@ pub async fn get_game<'a>(conn: &mut DBConn<'a>, id_: i64, chain_id_: i64) -> Op
         .first(conn)
         .await
         .optional()
-        .unwrap()
 }
 
-const MAX_GAMES_COUNT: i64 = 40;
-pub async fn get_games<'a>(conn: &mut DBConn<'a>, params: &GetGamesParams) -> Vec<Game> {
-    use ark_db::schema::coinflip_games::dsl::*;
-
-    let now = chrono::offset::Utc::now().timestamp();
-
-    match params {
-        GetGamesParams {
-            status: Some(GameStatus::Expired),
-            is_refunded: Some(false),
-            ..
-        } => coinflip_games
-            .filter(expiry_timestamp.lt(now))
-            .filter(refunded_at.is_null())
-            .filter(completed_at.is_null())
-            .load(conn)
-            .await
-            .unwrap(),
-
-        GetGamesParams {
-            reject_status: Some(GameStatus::Expired),
-            is_completed: Some(false),
-            ..
-        } => coinflip_games
-            .filter(expiry_timestamp.gt(now))
-            .filter(completed_at.is_null())
-            .load(conn)
-            .await
-            .unwrap(),
-
-        GetGamesParams {
-            player_address: None,
-            page_size,
-            id_to_ignore,
-            status: Some(GameStatus::AwaitingPlayers),
-            ..
-        } => {
-            let page_size = page_size.unwrap_or(MAX_GAMES_COUNT);
-
-            let mut query = coinflip_games
-                .filter(expiry_timestamp.gt(now))
-                .filter(completed_at.is_null())
-                .order_by(chain_agnostic_index.desc())
-                .limit(page_size)
-                .into_boxed();
-
-            query = if let Some(id_to_ignore) = id_to_ignore {
-                query.filter(id.ne(id_to_ignore))
-            } else {
-                query
-            };
-
-            query.load(conn).await.unwrap()
-        }
-
-        GetGamesParams {
-            player_address: Some(player_address_),
-            status: Some(GameStatus::AwaitingPlayers),
-            ..
-        } => {
-            use ark_db::schema::coinflip_game_plays::dsl::*;
-
-            coinflip_games
-                .inner_join(
-                    coinflip_game_plays.on(game_id
-                        .eq(schema::coinflip_games::id)
-                        .and(chain_id.eq(schema::coinflip_games::chain_id))
-                        .and(player_address.eq(player_address_.to_lowercase()))),
-                )
-                .filter(completed_at.is_null())
-                .filter(expiry_timestamp.gt(now))
-                .order_by(chain_agnostic_index.desc())
-                .select(schema::coinflip_games::all_columns)
-                // TODO: Post MVP pagination
-                .limit(MAX_GAMES_COUNT)
-                .load(conn)
-                .await
-                .unwrap()
-        }
-
-        GetGamesParams {
-            player_address: None,
-            status: Some(GameStatus::Completed),
-            ..
-        } => {
-            coinflip_games
-                .filter(completed_at.is_not_null().or(expiry_timestamp.le(now)))
-                .order_by(chain_agnostic_index.desc())
-                // TODO: Post MVP pagination
-                .limit(MAX_GAMES_COUNT)
-                .load(conn)
-                .await
-                .unwrap()
-        }
-
-        GetGamesParams {
-            player_address: Some(player_address_),
-            status: Some(GameStatus::Completed),
-            ..
-        } => {
-            use ark_db::schema::coinflip_game_plays::dsl::*;
-
-            coinflip_games
-                .inner_join(
-                    coinflip_game_plays.on(game_id
-                        .eq(schema::coinflip_games::id)
-                        .and(chain_id.eq(schema::coinflip_games::chain_id))
-                        .and(player_address.eq(player_address_.to_lowercase()))),
-                )
-                .filter(completed_at.is_not_null().or(expiry_timestamp.le(now)))
-                .filter(expiry_timestamp.gt(now))
-                .order_by(chain_agnostic_index.desc())
-                .select(schema::coinflip_games::all_columns)
-                // TODO: Post MVP pagination
-                .limit(MAX_GAMES_COUNT)
-                .load(conn)
-                .await
-                .unwrap()
-        }
-
-        GetGamesParams {
-            player_address: None,
-            chain_id_to_ignore,
-            offset,
-            ..
-        } => {
-            let offset = offset.unwrap_or(0);
-
-            let mut query = coinflip_games
-                .order_by(chain_agnostic_index.desc())
-                .limit(MAX_GAMES_COUNT)
-                .offset(offset as i64)
-                .into_boxed();
-
-            query = if let Some(chain_id_to_ignore) = chain_id_to_ignore {
-                query.filter(chain_id.ne(chain_id_to_ignore))
-            } else {
-                query
-            };
-
-            query.load(conn).await.unwrap()
-        }
-
-        GetGamesParams {
-            player_address: Some(player_address_),
-            chain_id_to_ignore,
-            offset,
-            ..
-        } => {
-            let offset = offset.unwrap_or(0);
-            use ark_db::schema::coinflip_game_plays::dsl::*;
-
-            let mut query = coinflip_games
-                .inner_join(
-                    coinflip_game_plays.on(game_id
-                        .eq(schema::coinflip_games::id)
-                        .and(chain_id.eq(schema::coinflip_games::chain_id))
-                        .and(player_address.eq(player_address_.to_lowercase()))),
-                )
-                .order_by(chain_agnostic_index.desc())
-                .select(schema::coinflip_games::all_columns)
-                .limit(MAX_GAMES_COUNT)
-                .offset(offset as i64)
-                .into_boxed();
-
-            query = if let Some(chain_id_to_ignore) = chain_id_to_ignore {
-                query.filter(chain_id.eq(chain_id_to_ignore))
-            } else {
-                query
-            };
-
-            query.load(conn).await.unwrap()
-        }
-    }
+pub async fn get_games<'a>(
+    conn: &mut DBConn<'a>,
+    params: &GetGamesParams,
+    clock: &dyn Clock,
+) -> QueryResult<Vec<Game>> {
+    crate::get_games_query(params, clock.now()).load(conn).await
 }
 
-pub async fn get_total_games_count<'a>(conn: &mut DBConn<'a>) -> u64 {
+pub async fn get_total_games_count<'a>(conn: &mut DBConn<'a>) -> QueryResult<u64> {
     use ark_db::schema::coinflip_games::dsl::*;
 
-    coinflip_games.count().get_result::<i64>(conn).await.unwrap() as u64
+    coinflip_games.count().get_result::<i64>(conn).await.map(|count| count as u64)
 }
 
-pub async fn get_total_completed_games_count<'a>(conn: &mut DBConn<'a>) -> u64 {
+pub async fn get_total_completed_games_count<'a>(conn: &mut DBConn<'a>) -> QueryResult<u64> {
     use ark_db::schema::coinflip_games::dsl::*;
 
     coinflip_games
-        .filter(completed_at.is_not_null())
+        .filter(status.eq(<GameStatus as Into<&str>>::into(GameStatus::Completed)))
         .count()
         .get_result::<i64>(conn)
         .await
-        .unwrap() as u64
+        .map(|count| count as u64)
 }
 
 pub async fn get_game_plays<'a>(
     conn: &mut DBConn<'a>,
     game_id_: i64,
     chain_id_: i64,
-) -> Vec<GamePlay> {
+) -> QueryResult<Vec<GamePlay>> {
     use ark_db::schema::coinflip_game_plays::dsl::*;
 
     coinflip_game_plays
@@ -267,39 +78,18 @@ pub async fn get_game_plays<'a>(
         .filter(chain_id.eq(chain_id_))
         .load(conn)
         .await
-        .unwrap()
-}
-
-pub async fn get_all_game_plays_with_proofs<'a>(
-    conn: &mut DBConn<'a>,
-    game_and_chain_ids: &Vec<(i64, i64)>,
-) -> Vec<GamePlay> {
-    use ark_db::schema::coinflip_game_plays::dsl::*;
-
-    if game_and_chain_ids.is_empty() {
-        vec![]
-    } else {
-        let mut query = coinflip_game_plays.into_boxed();
-
-        for (game_id_, chain_id_) in game_and_chain_ids.iter() {
-            query = query.or_filter(game_id.eq(game_id_).and(chain_id.eq(chain_id_)))
-        }
-
-        query.filter(chance_and_salt.is_not_null()).load(conn).await.unwrap()
-    }
 }
 
 pub async fn get_game_plays_for_player<'a>(
     conn: &mut DBConn<'a>,
     player_address_: &str,
-) -> Vec<GamePlay> {
+) -> QueryResult<Vec<GamePlay>> {
     use ark_db::schema::coinflip_game_plays::dsl::*;
 
     coinflip_game_plays
         .filter(player_address.eq(player_address_.to_lowercase()))
         .load(conn)
         .await
-        .unwrap()
 }
 
 pub async fn get_game_play<'a>(
@@ -307,7 +97,7 @@ pub async fn get_game_play<'a>(
     game_id_: i64,
     chain_id_: i64,
     player_address_: &str,
-) -> Option<GamePlay> {
+) -> QueryResult<Option<GamePlay>> {
     use ark_db::schema::coinflip_game_plays::dsl::*;
 
     coinflip_game_plays
@@ -317,7 +107,6 @@ pub async fn get_game_play<'a>(
         .first(conn)
         .await
         .optional()
-        .unwrap()
 }
 
 pub async fn get_game_play_by_id<'a>(
@@ -325,7 +114,7 @@ pub async fn get_game_play_by_id<'a>(
     game_id_: i64,
     chain_id_: i64,
     game_play_id: i32,
-) -> Option<GamePlay> {
+) -> QueryResult<Option<GamePlay>> {
     use ark_db::schema::coinflip_game_plays::dsl::*;
 
     coinflip_game_plays
@@ -335,87 +124,507 @@ pub async fn get_game_play_by_id<'a>(
         .first(conn)
         .await
         .optional()
-        .unwrap()
 }
 
-pub async fn update_game_play_chance_and_salt<'a>(
+pub async fn create_submitted_chance<'a>(
+    conn: &mut DBConn<'a>,
+    submitted_chance: &UnsavedSubmittedChance,
+) -> QueryResult<bool> {
+    use ark_db::schema::coinflip_submitted_chances::dsl::*;
+
+    let inserted_count = diesel::insert_into(coinflip_submitted_chances)
+        .values(submitted_chance)
+        .on_conflict((game_id, chain_id, game_play_id))
+        .do_nothing()
+        .execute(conn)
+        .await?;
+
+    Ok(inserted_count == 1)
+}
+
+pub async fn get_submitted_chance<'a>(
     conn: &mut DBConn<'a>,
     game_play: &GamePlay,
-    chance_and_salt_: String,
-) {
-    use ark_db::schema::coinflip_game_plays::dsl::*;
+) -> QueryResult<Option<SubmittedChance>> {
+    use ark_db::schema::coinflip_submitted_chances::dsl::*;
 
-    diesel::update(coinflip_game_plays)
-        .filter(id.eq(game_play.id))
+    coinflip_submitted_chances
         .filter(game_id.eq(game_play.game_id))
         .filter(chain_id.eq(game_play.chain_id))
-        .set(chance_and_salt.eq(chance_and_salt_))
-        .execute(conn)
+        .filter(game_play_id.eq(game_play.id))
+        .first(conn)
         .await
-        .unwrap();
+        .optional()
+}
+
+pub async fn get_submitted_chances<'a>(
+    conn: &mut DBConn<'a>,
+    game_and_chain_ids: &[(i64, i64)],
+) -> QueryResult<Vec<SubmittedChance>> {
+    use ark_db::schema::coinflip_submitted_chances::dsl::*;
+
+    if game_and_chain_ids.is_empty() {
+        Ok(vec![])
+    } else {
+        let mut query = coinflip_submitted_chances.into_boxed();
+
+        for (game_id_, chain_id_) in game_and_chain_ids.iter() {
+            query = query.or_filter(game_id.eq(game_id_).and(chain_id.eq(chain_id_)))
+        }
+
+        query.load(conn).await
+    }
 }
 
-pub async fn create_game_activity<'a>(conn: &mut DBConn<'a>, game_activity: &UnsavedGameActivity) {
+/// Returns false when the nonce was already used by the player i.e. a replay.
+/// `nonce_` must be canonical e.g. `U256::to_string()`, so equal nonces collide.
+pub async fn use_reveal_nonce<'a>(
+    conn: &mut DBConn<'a>,
+    chain_id_: i64,
+    player_address_: &str,
+    nonce_: &str,
+    now: i64,
+) -> QueryResult<bool> {
+    use ark_db::schema::coinflip_used_reveal_nonces::dsl::*;
+
+    let inserted_count = diesel::insert_into(coinflip_used_reveal_nonces)
+        .values((
+            chain_id.eq(chain_id_),
+            player_address.eq(player_address_.to_lowercase()),
+            nonce.eq(nonce_),
+            used_at.eq(now),
+        ))
+        .on_conflict((chain_id, player_address, nonce))
+        .do_nothing()
+        .execute(conn)
+        .await?;
+
+    Ok(inserted_count == 1)
+}
+
+/// Replaying an event is a no-op since activities are unique per source event, kind and trigger
+pub async fn create_game_activity<'a>(
+    conn: &mut DBConn<'a>,
+    game_activity: &UnsavedGameActivity,
+) -> QueryResult<()> {
     use ark_db::schema::coinflip_game_activities::dsl::*;
 
     diesel::insert_into(coinflip_game_activities)
         .values(game_activity)
+        .on_conflict((
+            chain_id,
+            transaction_hash,
+            log_index,
+            kind,
+            trigger_public_address,
+        ))
+        .do_nothing()
         .execute(conn)
-        .await
-        .unwrap();
+        .await?;
+
+    Ok(())
 }
 
-pub struct GetGameActivityParams {
-    pub game_id: i64,
-    pub chain_id: i64,
-    pub trigger_public_address: String,
-    pub kind: String,
+/// Deletes activities whose source events chaindexing dropped while handling reorged blocks.
+/// Re-indexed events keep their activities since they match the same transaction hash and log index.
+/// Every reorged block is processed once, in id order, up to the first one chaindexing hasn't
+/// handled yet. The watermark only advances after the deletes, so a failed run is simply redone.
+pub async fn rollback_reorged_game_activities<'a>(conn: &mut DBConn<'a>) -> QueryResult<usize> {
+    use ark_db::schema::coinflip_reorg_watermarks::dsl::*;
+
+    // Starts from the latest reorg, since older ones may have had their events pruned since
+    diesel::sql_query(
+        "INSERT INTO coinflip_reorg_watermarks (id, last_reorged_block_id)
+        SELECT 1, COALESCE(MAX(id), 0) FROM chaindexing_reorged_blocks
+        ON CONFLICT (id) DO NOTHING",
+    )
+    .execute(conn)
+    .await?;
+
+    let watermark: i64 =
+        coinflip_reorg_watermarks.select(last_reorged_block_id).first(conn).await?;
+
+    let next_watermark: i64 = diesel::select(
+        sql::<BigInt>(
+            "CAST(COALESCE(
+                (SELECT MIN(id) - 1 FROM chaindexing_reorged_blocks
+                    WHERE handled_at IS NULL AND id > ",
+        )
+        .bind::<BigInt, _>(watermark)
+        .sql("), (SELECT MAX(id) FROM chaindexing_reorged_blocks), 0) AS BIGINT)"),
+    )
+    .get_result(conn)
+    .await?;
+
+    if next_watermark <= watermark {
+        return Ok(0);
+    }
+
+    let rolled_back_count = diesel::sql_query(
+        "
+        DELETE FROM coinflip_game_activities
+        WHERE transaction_hash IS NOT NULL
+        AND EXISTS (
+            SELECT 1 FROM chaindexing_reorged_blocks
+            WHERE chaindexing_reorged_blocks.chain_id = coinflip_game_activities.chain_id
+            AND chaindexing_reorged_blocks.block_number <= coinflip_game_activities.block_number
+            AND chaindexing_reorged_blocks.id > $1
+            AND chaindexing_reorged_blocks.id <= $2
+        )
+        AND NOT EXISTS (
+            SELECT 1 FROM chaindexing_events
+            WHERE chaindexing_events.chain_id = coinflip_game_activities.chain_id
+            AND LOWER(chaindexing_events.transaction_hash) = coinflip_game_activities.transaction_hash
+            AND chaindexing_events.log_index = coinflip_game_activities.log_index
+        )
+        ",
+    )
+    .bind::<BigInt, _>(watermark)
+    .bind::<BigInt, _>(next_watermark)
+    .execute(conn)
+    .await?;
+
+    diesel::update(coinflip_reorg_watermarks)
+        .set(last_reorged_block_id.eq(next_watermark))
+        .execute(conn)
+        .await?;
+
+    Ok(rolled_back_count)
 }
 
-pub async fn get_game_activity<'a>(
+pub async fn get_game_activities<'a>(
     conn: &mut DBConn<'a>,
-    params: &GetGameActivityParams,
-) -> Option<GameActivity> {
+    game_ids: &[i64],
+    chain_ids: &[i64],
+) -> QueryResult<Vec<GameActivity>> {
     use ark_db::schema::coinflip_game_activities::dsl::*;
 
     coinflip_game_activities
-        .filter(game_id.eq(params.game_id))
-        .filter(chain_id.eq(params.chain_id))
-        .filter(trigger_public_address.eq(&params.trigger_public_address))
-        .filter(kind.eq(&params.kind))
+        .filter(game_id.eq_any(game_ids))
+        .filter(chain_id.eq_any(chain_ids))
+        .order_by(id.desc())
+        .load(conn)
+        .await
+}
+
+/// Activities in every game the player is part of, newest first.
+/// `game_status` is matched like `coinflip::Game::get_status_at(now)`.
+pub async fn get_player_game_activities<'a>(
+    conn: &mut DBConn<'a>,
+    player_address: &str,
+    game_status: Option<GameStatus>,
+    now: i64,
+    params: &GetPlayerGameActivitiesParams,
+) -> QueryResult<Vec<GameActivity>> {
+    diesel::sql_query(format!(
+        "SELECT coinflip_game_activities.*
+        FROM coinflip_game_activities
+        INNER JOIN coinflip_game_plays
+            ON coinflip_game_plays.game_id = coinflip_game_activities.game_id
+            AND coinflip_game_plays.chain_id = coinflip_game_activities.chain_id
+        INNER JOIN coinflip_games
+            ON coinflip_games.id = coinflip_game_activities.game_id
+            AND coinflip_games.chain_id = coinflip_game_activities.chain_id
+        WHERE coinflip_game_plays.player_address = $1
+        AND ($2 IS NULL OR {status_at} = $2)
+        AND ($3 IS NULL OR coinflip_game_activities.id < $3)
+        ORDER BY coinflip_game_activities.id DESC
+        LIMIT $4",
+        status_at = get_status_at_sql(now),
+    ))
+    .bind::<Text, _>(player_address.to_lowercase())
+    .bind::<Nullable<Text>, _>(game_status.map(<GameStatus as Into<&str>>::into))
+    .bind::<Nullable<BigInt>, _>(params.cursor)
+    .bind::<BigInt, _>(params.get_page_size())
+    .load(conn)
+    .await
+}
+
+pub async fn get_service_charge_schedule<'a>(
+    conn: &mut DBConn<'a>,
+    chain_ids: &[i64],
+) -> QueryResult<ServiceChargeSchedule> {
+    use ark_db::schema::coinflip_service_charges::dsl::*;
+
+    let service_charges =
+        coinflip_service_charges.filter(chain_id.eq_any(chain_ids)).load(conn).await?;
+
+    Ok(ServiceChargeSchedule::new(service_charges))
+}
+
+pub async fn get_last_service_charge<'a>(
+    conn: &mut DBConn<'a>,
+    chain_id_: i64,
+) -> QueryResult<Option<ServiceCharge>> {
+    use ark_db::schema::coinflip_service_charges::dsl::*;
+
+    coinflip_service_charges
+        .filter(chain_id.eq(chain_id_))
+        .order_by(effective_from_block_number.desc())
         .first(conn)
         .await
         .optional()
-        .unwrap()
 }
 
-pub async fn update_game_activity_transaction_hash<'a>(
+pub async fn create_service_charge<'a>(
     conn: &mut DBConn<'a>,
-    game_activity: &GameActivity,
-    transaction_hash_: &str,
-) {
-    use ark_db::schema::coinflip_game_activities::dsl::*;
+    service_charge: &UnsavedServiceCharge,
+) -> QueryResult<()> {
+    use ark_db::schema::coinflip_service_charges::dsl::*;
+
+    diesel::insert_into(coinflip_service_charges)
+        .values(service_charge)
+        .on_conflict((chain_id, effective_from_block_number))
+        .do_nothing()
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Sums the service charge of every completed game, using the fee schedule
+/// effective at the block each game was created in.
+pub async fn get_house_revenue_reports<'a>(
+    conn: &mut DBConn<'a>,
+    params: &GetHouseRevenueReportsParams,
+) -> QueryResult<Vec<HouseRevenueReport>> {
+    let period: &str = params.period.unwrap_or_default().into();
+
+    diesel::sql_query(
+        "SELECT
+            coinflip_games.chain_id,
+            CAST(EXTRACT(EPOCH FROM date_trunc($1, to_timestamp(coinflip_games.completed_at))) AS BIGINT) AS period_start,
+            COUNT(*) AS games_count,
+            CAST(SUM(
+                CAST(coinflip_games.wager AS NUMERIC) * coinflip_games.number_of_players / 1e18
+                * COALESCE((
+                    SELECT coinflip_service_charges.percent
+                    FROM coinflip_service_charges
+                    WHERE coinflip_service_charges.chain_id = coinflip_games.chain_id
+                    AND coinflip_service_charges.effective_from_block_number <= coinflip_games.block_number
+                    ORDER BY coinflip_service_charges.effective_from_block_number DESC
+                    LIMIT 1
+                ), $2) / 100
+            ) AS DOUBLE PRECISION) AS fees_collected
+        FROM coinflip_games
+        WHERE coinflip_games.status = $3
+        AND ($4 IS NULL OR coinflip_games.chain_id = $4)
+        AND ($5 IS NULL OR coinflip_games.completed_at >= $5)
+        AND ($6 IS NULL OR coinflip_games.completed_at < $6)
+        GROUP BY coinflip_games.chain_id, period_start
+        ORDER BY period_start DESC, coinflip_games.chain_id",
+    )
+    .bind::<Text, _>(period)
+    .bind::<Integer, _>(DEFAULT_SERVICE_CHARGE_PERCENT)
+    .bind::<Text, _>(<GameStatus as Into<&str>>::into(GameStatus::Completed))
+    .bind::<Nullable<BigInt>, _>(params.chain_id)
+    .bind::<Nullable<BigInt>, _>(params.from)
+    .bind::<Nullable<BigInt>, _>(params.to)
+    .load(conn)
+    .await
+}
+
+/// Plays in unexpired games that filled up at or before `blocked_before` and still lack a chance
+pub async fn get_pending_reveals<'a>(
+    conn: &mut DBConn<'a>,
+    now: i64,
+    blocked_before: i64,
+    player_address: Option<&str>,
+) -> QueryResult<Vec<PendingReveal>> {
+    diesel::sql_query(
+        "SELECT
+            coinflip_game_plays.game_id,
+            coinflip_game_plays.chain_id,
+            coinflip_game_plays.id AS game_play_id,
+            coinflip_game_plays.player_address,
+            coinflip_games.all_seats_filled_at AS blocked_since
+        FROM coinflip_game_plays
+        INNER JOIN coinflip_games
+            ON coinflip_games.id = coinflip_game_plays.game_id
+            AND coinflip_games.chain_id = coinflip_game_plays.chain_id
+        WHERE coinflip_games.status = $1
+        AND coinflip_games.expiry_timestamp > $4
+        AND coinflip_games.all_seats_filled_at <= $2
+        AND coinflip_game_plays.chance_and_salt IS NULL
+        AND NOT EXISTS (
+            SELECT 1 FROM coinflip_submitted_chances
+            WHERE coinflip_submitted_chances.game_id = coinflip_game_plays.game_id
+            AND coinflip_submitted_chances.chain_id = coinflip_game_plays.chain_id
+            AND coinflip_submitted_chances.game_play_id = coinflip_game_plays.id
+        )
+        AND ($3 IS NULL OR coinflip_game_plays.player_address = $3)
+        ORDER BY coinflip_games.all_seats_filled_at ASC",
+    )
+    .bind::<Text, _>(<GameStatus as Into<&str>>::into(
+        GameStatus::AwaitingRevealedChances,
+    ))
+    .bind::<BigInt, _>(blocked_before)
+    .bind::<Nullable<Text>, _>(player_address.map(|address| address.to_lowercase()))
+    .bind::<BigInt, _>(now)
+    .load(conn)
+    .await
+}
 
-    diesel::update(coinflip_game_activities)
-        .filter(id.eq(game_activity.id))
-        .set(transaction_hash.eq(transaction_hash_))
+/// Records newly detected reminders and refreshes already known ones
+pub async fn create_or_update_reveal_reminders<'a>(
+    conn: &mut DBConn<'a>,
+    reveal_reminders: &[UnsavedRevealReminder],
+) -> QueryResult<()> {
+    use ark_db::schema::coinflip_reveal_reminders::dsl::*;
+
+    diesel::insert_into(coinflip_reveal_reminders)
+        .values(reveal_reminders)
+        .on_conflict((game_id, chain_id, game_play_id))
+        .do_update()
+        .set((
+            last_detected_at.eq(excluded(last_detected_at)),
+            resolved_at.eq(None::<i64>),
+        ))
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Resolves reminders that were not detected again in the run at `now`
+pub async fn resolve_reveal_reminders<'a>(conn: &mut DBConn<'a>, now: i64) -> QueryResult<usize> {
+    use ark_db::schema::coinflip_reveal_reminders::dsl::*;
+
+    diesel::update(coinflip_reveal_reminders)
+        .filter(resolved_at.is_null())
+        .filter(last_detected_at.lt(now))
+        .set(resolved_at.eq(Some(now)))
         .execute(conn)
         .await
-        .unwrap();
 }
 
-pub async fn get_game_activities<'a>(
+pub async fn get_unresolved_reveal_reminders<'a>(
     conn: &mut DBConn<'a>,
-    game_ids: &Vec<i64>,
-    chain_ids: &Vec<i64>,
-) -> Vec<GameActivity> {
-    use ark_db::schema::coinflip_game_activities::dsl::*;
+) -> QueryResult<Vec<RevealReminder>> {
+    use ark_db::schema::coinflip_reveal_reminders::dsl::*;
 
-    coinflip_game_activities
-        .filter(game_id.eq_any(game_ids))
-        .filter(chain_id.eq_any(chain_ids))
-        .order_by(id.desc())
+    coinflip_reveal_reminders
+        .filter(resolved_at.is_null())
+        .order_by(blocked_since.asc())
         .load(conn)
         .await
-        .unwrap()
+}
+
+/// Every game needing the player's attention in a single query,
+/// ordered by pending reveals, then games expiring soonest, then the latest credits
+pub async fn get_player_actions<'a>(
+    conn: &mut DBConn<'a>,
+    params: &GetPlayerActionsParams,
+) -> QueryResult<Vec<PlayerAction>> {
+    let kind = |kind: PlayerActionKind| -> &'static str { kind.into() };
+    let status = |status: GameStatus| -> &'static str { status.into() };
+
+    diesel::sql_query(
+        "SELECT * FROM (
+            SELECT coinflip_games.*, coinflip_game_plays.id AS game_play_id,
+                $1 AS kind,
+                COALESCE(coinflip_games.all_seats_filled_at, coinflip_games.created_at) AS action_at,
+                0 AS priority
+            FROM coinflip_games
+            INNER JOIN coinflip_game_plays
+                ON coinflip_game_plays.game_id = coinflip_games.id
+                AND coinflip_game_plays.chain_id = coinflip_games.chain_id
+            WHERE coinflip_game_plays.player_address = $2
+            AND coinflip_games.status = $3
+            AND coinflip_games.expiry_timestamp > $6
+            AND coinflip_game_plays.chance_and_salt IS NULL
+            AND NOT EXISTS (
+                SELECT 1 FROM coinflip_submitted_chances
+                WHERE coinflip_submitted_chances.game_id = coinflip_game_plays.game_id
+                AND coinflip_submitted_chances.chain_id = coinflip_game_plays.chain_id
+                AND coinflip_submitted_chances.game_play_id = coinflip_game_plays.id
+            )
+
+            UNION ALL
+
+            SELECT coinflip_games.*, coinflip_game_plays.id AS game_play_id,
+                $4 AS kind, coinflip_games.expiry_timestamp AS action_at, 1 AS priority
+            FROM coinflip_games
+            INNER JOIN coinflip_game_plays
+                ON coinflip_game_plays.game_id = coinflip_games.id
+                AND coinflip_game_plays.chain_id = coinflip_games.chain_id
+            WHERE coinflip_game_plays.player_address = $2
+            AND coinflip_games.status = $5
+            AND coinflip_games.expiry_timestamp > $6
+            AND coinflip_games.expiry_timestamp <= $6 + $7
+
+            UNION ALL
+
+            SELECT coinflip_games.*, coinflip_game_plays.id AS game_play_id,
+                $8 AS kind, coinflip_games.refunded_at AS action_at, 2 AS priority
+            FROM coinflip_games
+            INNER JOIN coinflip_game_plays
+                ON coinflip_game_plays.game_id = coinflip_games.id
+                AND coinflip_game_plays.chain_id = coinflip_games.chain_id
+            WHERE coinflip_game_plays.player_address = $2
+            AND coinflip_games.status = $9
+            AND coinflip_games.refunded_at > $10
+
+            UNION ALL
+
+            SELECT coinflip_games.*, coinflip_game_plays.id AS game_play_id,
+                $11 AS kind, coinflip_games.completed_at AS action_at, 2 AS priority
+            FROM coinflip_games
+            INNER JOIN coinflip_game_plays
+                ON coinflip_game_plays.game_id = coinflip_games.id
+                AND coinflip_game_plays.chain_id = coinflip_games.chain_id
+            WHERE coinflip_game_plays.player_address = $2
+            AND coinflip_games.status = $12
+            AND coinflip_games.completed_at > $10
+            AND coinflip_game_plays.coin_side = coinflip_games.outcome
+        ) AS player_actions
+        ORDER BY priority ASC,
+            CASE WHEN priority = 2 THEN -action_at ELSE action_at END ASC",
+    )
+    .bind::<Text, _>(kind(PlayerActionKind::MustReveal))
+    .bind::<Text, _>(params.player_address.to_lowercase())
+    .bind::<Text, _>(status(GameStatus::AwaitingRevealedChances))
+    .bind::<Text, _>(kind(PlayerActionKind::ExpiringSoon))
+    .bind::<Text, _>(status(GameStatus::AwaitingPlayers))
+    .bind::<BigInt, _>(params.now)
+    .bind::<BigInt, _>(params.expiring_within_secs)
+    .bind::<Text, _>(kind(PlayerActionKind::Refunded))
+    .bind::<Text, _>(status(GameStatus::Refunded))
+    .bind::<BigInt, _>(params.credited_since)
+    .bind::<Text, _>(kind(PlayerActionKind::Won))
+    .bind::<Text, _>(status(GameStatus::Completed))
+    .load(conn)
+    .await
+}
+
+/// The latest winning plays across all chains, newest first
+pub async fn get_recent_game_wins<'a>(
+    conn: &mut DBConn<'a>,
+    limit: i64,
+) -> QueryResult<Vec<GameWin>> {
+    let status = |status: GameStatus| -> &'static str { status.into() };
+
+    diesel::sql_query(
+        "SELECT coinflip_games.id AS game_id, coinflip_games.chain_id,
+            coinflip_game_plays.id AS game_play_id,
+            coinflip_game_plays.player_address AS winner_address,
+            coinflip_games.amount_for_each_winner AS amount_won,
+            coinflip_games.completed_at
+        FROM coinflip_games
+        INNER JOIN coinflip_game_plays
+            ON coinflip_game_plays.game_id = coinflip_games.id
+            AND coinflip_game_plays.chain_id = coinflip_games.chain_id
+        WHERE coinflip_games.status = $1
+        AND coinflip_game_plays.status = $2
+        AND coinflip_games.amount_for_each_winner IS NOT NULL
+        AND coinflip_games.completed_at IS NOT NULL
+        ORDER BY coinflip_games.completed_at DESC, coinflip_games.chain_id DESC,
+            coinflip_games.id DESC, coinflip_game_plays.id DESC
+        LIMIT $3",
+    )
+    .bind::<Text, _>(status(GameStatus::Completed))
+    .bind::<Text, _>(GamePlayStatus::won_string())
+    .bind::<BigInt, _>(limit)
+    .load(conn)
+    .await
 }
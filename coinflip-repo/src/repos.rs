@@ -0,0 +1,213 @@
+use std::sync::Arc;
+
+use ark_db::{DBConn, DBPool, RepoError, RepoResult};
+use ark_utils::clocks::Clock;
+use async_trait::async_trait;
+
+use coinflip::{ActivityRepo, GamePlayRepo, GameRepo, GetGamesParams, RevealReminderRepo};
+use coinflip::{Game, GameActivity, GamePlay, ServiceChargeSchedule, UnsavedGameActivity};
+use coinflip::{GameStatus, GetPlayerGameActivitiesParams, HouseRevenueReport, PlayerAction};
+use coinflip::{GetHouseRevenueReportsParams, GetPlayerActionsParams};
+use coinflip::{PendingReveal, RevealReminder, UnsavedRevealReminder};
+use coinflip::{SubmittedChance, UnsavedSubmittedChance};
+
+/// Runs the free functions in this crate on a fresh connection per call
+#[derive(Clone)]
+pub struct PostgresCoinflipRepo {
+    pool: Arc<DBPool>,
+}
+
+impl PostgresCoinflipRepo {
+    pub fn new(pool: Arc<DBPool>) -> Self {
+        Self { pool }
+    }
+
+    async fn get_conn(&self) -> RepoResult<DBConn<'_>> {
+        self.pool.get().await.map_err(RepoError::from)
+    }
+}
+
+#[async_trait]
+impl GameRepo for PostgresCoinflipRepo {
+    async fn get_game(&self, id: i64, chain_id: i64) -> RepoResult<Option<Game>> {
+        crate::get_game(&mut self.get_conn().await?, id, chain_id)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_games(&self, params: &GetGamesParams, clock: &dyn Clock) -> RepoResult<Vec<Game>> {
+        crate::get_games(&mut self.get_conn().await?, params, clock)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_total_games_count(&self) -> RepoResult<u64> {
+        crate::get_total_games_count(&mut self.get_conn().await?)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_total_completed_games_count(&self) -> RepoResult<u64> {
+        crate::get_total_completed_games_count(&mut self.get_conn().await?)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_service_charge_schedule(
+        &self,
+        chain_ids: &[i64],
+    ) -> RepoResult<ServiceChargeSchedule> {
+        crate::get_service_charge_schedule(&mut self.get_conn().await?, chain_ids)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_player_actions(
+        &self,
+        params: &GetPlayerActionsParams,
+    ) -> RepoResult<Vec<PlayerAction>> {
+        crate::get_player_actions(&mut self.get_conn().await?, params)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_house_revenue_reports(
+        &self,
+        params: &GetHouseRevenueReportsParams,
+    ) -> RepoResult<Vec<HouseRevenueReport>> {
+        crate::get_house_revenue_reports(&mut self.get_conn().await?, params)
+            .await
+            .map_err(RepoError::from)
+    }
+}
+
+#[async_trait]
+impl GamePlayRepo for PostgresCoinflipRepo {
+    async fn get_game_plays(&self, game_id: i64, chain_id: i64) -> RepoResult<Vec<GamePlay>> {
+        crate::get_game_plays(&mut self.get_conn().await?, game_id, chain_id)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_game_play(
+        &self,
+        game_id: i64,
+        chain_id: i64,
+        player_address: &str,
+    ) -> RepoResult<Option<GamePlay>> {
+        crate::get_game_play(
+            &mut self.get_conn().await?,
+            game_id,
+            chain_id,
+            player_address,
+        )
+        .await
+        .map_err(RepoError::from)
+    }
+    async fn use_reveal_nonce(
+        &self,
+        chain_id: i64,
+        player_address: &str,
+        nonce: &str,
+        now: i64,
+    ) -> RepoResult<bool> {
+        crate::use_reveal_nonce(
+            &mut self.get_conn().await?,
+            chain_id,
+            player_address,
+            nonce,
+            now,
+        )
+        .await
+        .map_err(RepoError::from)
+    }
+    async fn create_submitted_chance(
+        &self,
+        submitted_chance: &UnsavedSubmittedChance,
+    ) -> RepoResult<bool> {
+        crate::create_submitted_chance(&mut self.get_conn().await?, submitted_chance)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_submitted_chance(
+        &self,
+        game_play: &GamePlay,
+    ) -> RepoResult<Option<SubmittedChance>> {
+        crate::get_submitted_chance(&mut self.get_conn().await?, game_play)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_submitted_chances(
+        &self,
+        game_and_chain_ids: &[(i64, i64)],
+    ) -> RepoResult<Vec<SubmittedChance>> {
+        crate::get_submitted_chances(&mut self.get_conn().await?, game_and_chain_ids)
+            .await
+            .map_err(RepoError::from)
+    }
+}
+
+#[async_trait]
+impl ActivityRepo for PostgresCoinflipRepo {
+    async fn create_game_activity(&self, game_activity: &UnsavedGameActivity) -> RepoResult<()> {
+        crate::create_game_activity(&mut self.get_conn().await?, game_activity)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_game_activities(
+        &self,
+        game_ids: &[i64],
+        chain_ids: &[i64],
+    ) -> RepoResult<Vec<GameActivity>> {
+        crate::get_game_activities(&mut self.get_conn().await?, game_ids, chain_ids)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_player_game_activities(
+        &self,
+        player_address: &str,
+        game_status: Option<GameStatus>,
+        now: i64,
+        params: &GetPlayerGameActivitiesParams,
+    ) -> RepoResult<Vec<GameActivity>> {
+        crate::get_player_game_activities(
+            &mut self.get_conn().await?,
+            player_address,
+            game_status,
+            now,
+            params,
+        )
+        .await
+        .map_err(RepoError::from)
+    }
+}
+
+#[async_trait]
+impl RevealReminderRepo for PostgresCoinflipRepo {
+    async fn get_pending_reveals(
+        &self,
+        now: i64,
+        blocked_before: i64,
+        player_address: Option<&str>,
+    ) -> RepoResult<Vec<PendingReveal>> {
+        crate::get_pending_reveals(
+            &mut self.get_conn().await?,
+            now,
+            blocked_before,
+            player_address,
+        )
+        .await
+        .map_err(RepoError::from)
+    }
+    async fn create_or_update_reveal_reminders(
+        &self,
+        reveal_reminders: &[UnsavedRevealReminder],
+    ) -> RepoResult<()> {
+        crate::create_or_update_reveal_reminders(&mut self.get_conn().await?, reveal_reminders)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn resolve_reveal_reminders(&self, now: i64) -> RepoResult<usize> {
+        crate::resolve_reveal_reminders(&mut self.get_conn().await?, now)
+            .await
+            .map_err(RepoError::from)
+    }
+    async fn get_unresolved_reveal_reminders(&self) -> RepoResult<Vec<RevealReminder>> {
+        crate::get_unresolved_reveal_reminders(&mut self.get_conn().await?)
+            .await
+            .map_err(RepoError::from)
+    }
+}
@@ -1,22 +1,25 @@
-use ark_web_common::AppState;
-use axum::{
-    routing::{get, put},
-    Router,
-};
+use ark_web_common::{auth, AppState};
+use axum::{middleware, routing::get, Router};
 
-use crate::handlers::{game_activity_handler, game_handler, game_play_handler};
+use crate::handlers::{
+    feed_handler, game_activity_handler, game_handler, game_play_handler, house_revenue_handler,
+    player_handler, reveal_reminder_handler,
+};
 
 pub struct AppRouter {
     pub routes: Router<AppState>,
 }
 
 impl AppRouter {
-    pub fn new() -> Self {
+    pub fn new(app_state: &AppState) -> Self {
         Self {
             routes: Router::new()
                 .merge(Self::game_routes())
                 .merge(Self::game_play_routes())
-                .merge(Self::game_activty_routes()),
+                .merge(Self::game_activty_routes())
+                .merge(Self::feed_routes())
+                .merge(Self::player_routes())
+                .merge(Self::admin_routes(app_state)),
         }
     }
 
@@ -36,17 +39,64 @@ impl AppRouter {
     fn game_play_routes() -> Router<AppState> {
         Router::new().nest(
             "/game_plays/:game_id/:chain_id",
-            Router::new().route("/my_game_play", put(game_play_handler::update_my_game_play)),
+            Router::new()
+                .route(
+                    "/my_game_play",
+                    get(game_play_handler::get_my_game_play)
+                        .put(game_play_handler::update_my_game_play),
+                )
+                .route_layer(middleware::from_fn(auth::require_authenticated_player)),
         )
     }
 
     fn game_activty_routes() -> Router<AppState> {
         Router::new().nest(
             "/game_activities",
-            Router::new().route(
-                "/:game_status/:player_address",
-                get(game_activity_handler::get_all_game_activites),
-            ),
+            Router::new()
+                .route(
+                    "/:game_status/:player_address",
+                    get(game_activity_handler::get_all_game_activites),
+                )
+                .route_layer(middleware::from_fn(auth::require_authenticated_player)),
+        )
+    }
+
+    fn feed_routes() -> Router<AppState> {
+        Router::new().nest(
+            "/feed",
+            Router::new().route("/recent", get(feed_handler::get_recent_results)),
+        )
+    }
+
+    fn player_routes() -> Router<AppState> {
+        Router::new().nest(
+            "/players",
+            Router::new()
+                .route(
+                    "/me/pending_reveals",
+                    get(reveal_reminder_handler::get_my_pending_reveals),
+                )
+                .route("/:address/actions", get(player_handler::get_player_actions))
+                .route_layer(middleware::from_fn(auth::require_authenticated_player)),
+        )
+    }
+
+    fn admin_routes(app_state: &AppState) -> Router<AppState> {
+        Router::new().nest(
+            "/admin",
+            Router::new()
+                .route(
+                    "/reveal_reminders",
+                    get(reveal_reminder_handler::get_reveal_reminders),
+                )
+                .route(
+                    "/reports/house_revenue",
+                    get(house_revenue_handler::get_house_revenue_reports),
+                )
+                .route_layer(middleware::from_fn_with_state(
+                    app_state.clone(),
+                    auth::require_admin,
+                )),
         )
     }
 }
@@ -1,5 +1,9 @@
+pub mod detect_stuck_games;
 pub mod index_contracts;
+pub mod load_recent_results;
 pub mod refund_expired_game_players;
 pub mod reveal_game_play_chances;
+pub mod rollback_reorged_game_activities;
+pub mod sync_service_charges;
 
-mod caching;
+pub mod caching;
@@ -1,44 +1,52 @@
-use std::{collections::HashMap, hash::Hash};
+use std::{collections::HashMap, hash::Hash, sync::Arc};
 
-use chrono::Utc;
+use ark_utils::clocks::Clock;
 
+#[derive(Clone)]
 pub struct RecentCache<K: Hash + Eq + Clone, V> {
-    data: HashMap<K, (V, u64)>,
+    data: HashMap<K, (V, i64)>,
     stale_after: u64,
+    clock: Arc<dyn Clock>,
 }
 
 impl<K: Hash + Eq + Clone, V> RecentCache<K, V> {
-    pub fn new(stale_after: u64) -> Self {
+    pub fn new(stale_after: u64, clock: Arc<dyn Clock>) -> Self {
         Self {
             data: HashMap::new(),
             stale_after,
+            clock,
         }
     }
     pub fn insert(&mut self, key: K, value: V) {
-        self.data.insert(key, (value, now()));
+        self.data.insert(key, (value, self.clock.now()));
     }
     pub fn invalidate_all(&mut self) {
-        let keys: Vec<_> = self.data.keys().cloned().collect();
-        for key in keys.iter() {
-            self.invalidate(key);
-        }
+        self.data.clear();
     }
     pub fn invalidate(&mut self, key: &K) {
-        if let Some((_value, mut _inserted_at)) = self.data.get_mut(key) {
-            _inserted_at = 0;
-        }
+        self.data.remove(key);
+    }
+    /// Drops stale entries so keys that are never read again don't pile up
+    pub fn remove_stale(&mut self) {
+        let now = self.clock.now();
+        let stale_after = self.stale_after;
+
+        self.data
+            .retain(|_key, (_value, inserted_at)| Self::is_fresh(now, *inserted_at, stale_after));
     }
     pub fn get(&self, key: &K) -> Option<&V> {
+        let now = self.clock.now();
+
         self.data.get(key).and_then(|(value, inserted_at)| {
-            if now() - inserted_at < self.stale_after {
+            if Self::is_fresh(now, *inserted_at, self.stale_after) {
                 Some(value)
             } else {
                 None
             }
         })
     }
-}
 
-fn now() -> u64 {
-    Utc::now().timestamp() as u64
+    fn is_fresh(now: i64, inserted_at: i64, stale_after: u64) -> bool {
+        ((now - inserted_at).max(0) as u64) < stale_after
+    }
 }
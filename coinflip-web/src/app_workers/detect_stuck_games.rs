@@ -0,0 +1,96 @@
+use std::{sync::Arc, time::Duration};
+
+use ark::configs::ArkConfig;
+use ark::profiles::Worker;
+use ark_db::RepoResult;
+use ark_utils::clocks::Clock;
+use ark_web_common::workers::AppWorker;
+use ark_web_common::Repos;
+use async_trait::async_trait;
+use coinflip::UnsavedRevealReminder;
+use eyre::{Result, WrapErr};
+use tracing::{info, warn};
+
+const WORKER_INTERVAL_MS: u64 = 2 * 60 * 1_000;
+
+/// Records a reveal reminder for every play blocking a filled game past the stuck threshold,
+/// and resolves reminders for plays that have since been revealed or whose games moved on.
+#[derive(Clone)]
+pub struct DetectStuckGames {
+    repos: Repos,
+    clock: Arc<dyn Clock>,
+    config: Arc<ArkConfig>,
+}
+
+impl DetectStuckGames {
+    pub fn new(repos: Repos, clock: Arc<dyn Clock>, config: Arc<ArkConfig>) -> Self {
+        Self {
+            repos,
+            clock,
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl AppWorker for DetectStuckGames {
+    fn get_worker(&self) -> Worker {
+        Worker::DetectStuckGames
+    }
+
+    fn get_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(WORKER_INTERVAL_MS))
+    }
+
+    async fn run(&mut self) -> Result<()> {
+        info!("[DetectStuckGames]: running...");
+
+        let now = self.clock.now();
+
+        detect_stuck_games(&self.repos, now, self.config.stuck_game_threshold_secs)
+            .await
+            .wrap_err("Failed to detect stuck games")
+    }
+}
+
+async fn detect_stuck_games(
+    repos: &Repos,
+    now: i64,
+    stuck_game_threshold_secs: i64,
+) -> RepoResult<()> {
+    let pending_reveals = repos
+        .reveal_reminders
+        .get_pending_reveals(now, now - stuck_game_threshold_secs, None)
+        .await?;
+
+    for pending_reveal in pending_reveals.iter() {
+        warn!(
+            "[DetectStuckGames]: Game:{game_id} on Chain:{chain_id} blocked for {blocked_for_secs}s on GamePlay:{game_play_id}",
+            game_id = pending_reveal.game_id,
+            chain_id = pending_reveal.chain_id,
+            blocked_for_secs = pending_reveal.get_blocked_for_secs(now),
+            game_play_id = pending_reveal.game_play_id,
+        );
+    }
+
+    if !pending_reveals.is_empty() {
+        let reveal_reminders = pending_reveals
+            .iter()
+            .map(|pending_reveal| UnsavedRevealReminder::new(pending_reveal, now))
+            .collect::<Vec<_>>();
+
+        repos
+            .reveal_reminders
+            .create_or_update_reveal_reminders(&reveal_reminders)
+            .await?;
+    }
+
+    let resolved_count = repos.reveal_reminders.resolve_reveal_reminders(now).await?;
+
+    info!(
+        "[DetectStuckGames]: Found {} pending reveals, resolved {resolved_count} reminders...",
+        pending_reveals.len()
+    );
+
+    Ok(())
+}
@@ -1,49 +1,109 @@
 use std::sync::Arc;
+use std::time::Duration;
 
+use ark::configs::ArkConfig;
+use ark::profiles::Worker;
 use ark_db::DBPool;
-use ark_web3::json_rpcs;
-use chaindexing::{Chain, ChainId, Chaindexing, KeepNodeActiveRequest, OptimizationConfig, Repo};
+use ark_utils::clocks::Clock;
+use ark_web_common::workers::AppWorker;
+use async_trait::async_trait;
+use chaindexing::{Chain, Chaindexing, Contract, KeepNodeActiveRequest};
+use chaindexing::{OptimizationConfig, Repo};
+use eyre::{eyre, Result};
 
 // TODO: Move to ark-level
-pub fn start(pool: Arc<DBPool>, keep_chaindexing_node_active_request: KeepNodeActiveRequest) {
-    tokio::spawn(async move {
+/// Sets chaindexing up once, which then keeps indexing on its own tasks
+#[derive(Clone)]
+pub struct IndexContracts {
+    pool: Arc<DBPool>,
+    keep_chaindexing_node_active_request: KeepNodeActiveRequest,
+    clock: Arc<dyn Clock>,
+    config: Arc<ArkConfig>,
+}
+
+impl IndexContracts {
+    pub fn new(
+        pool: Arc<DBPool>,
+        keep_chaindexing_node_active_request: KeepNodeActiveRequest,
+        clock: Arc<dyn Clock>,
+        config: Arc<ArkConfig>,
+    ) -> Self {
+        Self {
+            pool,
+            keep_chaindexing_node_active_request,
+            clock,
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl AppWorker for IndexContracts {
+    fn get_worker(&self) -> Worker {
+        Worker::IndexContracts
+    }
+
+    fn get_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// `Chaindexing::index_states` spawns the indexing tasks without handing back a handle
+    fn is_detached(&self) -> bool {
+        true
+    }
+
+    async fn run(&mut self) -> Result<()> {
+        let config = &self.config;
+
         let optimization_config = OptimizationConfig {
-            keep_node_active_request: keep_chaindexing_node_active_request,
+            keep_node_active_request: self.keep_chaindexing_node_active_request.clone(),
             optimize_after_in_secs: 6 * 60,
         };
 
-        let config = chaindexing::Config::new(chaindexing::PostgresRepo::new(&ark_db::url()))
+        let chaindexing_config =
+            chaindexing::Config::new(chaindexing::PostgresRepo::new(&config.database_url));
+        let chaindexing_config = get_contracts(config, self.clock.clone())
+            .into_iter()
+            .fold(chaindexing_config, |chaindexing_config, contract| {
+                chaindexing_config.add_contract(contract)
+            })
             .with_ingestion_rate_ms(6_000)
-            .with_initial_state(pool)
-            .add_contract(coinflip_contracts::coinflip::get())
-            .add_contract(ark_contracts::wallets::get())
-            .reset(get_reset_count())
+            .with_initial_state(self.pool.clone())
+            .reset(config.chaindexing_reset_count)
             .add_reset_query("DELETE FROM coinflip_game_activities")
             .add_reset_query("DELETE FROM ark_total_paid_out_reports")
+            // Reorged block ids start over once chaindexing recreates its tables
+            .add_reset_query("DELETE FROM coinflip_reorg_watermarks")
             .enable_optimization(&optimization_config)
             .with_pruning();
 
-        let current_environment = ark::environments::current();
-
-        let config = if current_environment.is_local() {
-            config.add_chain(Chain::new(ChainId::Dev, &json_rpcs::get_local_url()))
-        } else if current_environment.is_production() {
+        let chaindexing_config =
             config
-                .add_chain(Chain::new(ChainId::Sepolia, &json_rpcs::get_sepolia_url()))
-                .add_chain(Chain::new(ChainId::Polygon, &json_rpcs::get_polygon_url()))
-                .add_chain(Chain::new(ChainId::Mainnet, &json_rpcs::get_ethereum_url()))
-        } else {
-            config
-        };
+                .chains
+                .iter()
+                .fold(chaindexing_config, |chaindexing_config, chain_config| {
+                    chaindexing_config.add_chain(Chain::new(
+                        chain_config.chain_id.into(),
+                        &chain_config.json_rpc_url,
+                    ))
+                });
 
-        Chaindexing::index_states(&config).await.unwrap();
-    });
+        Chaindexing::index_states(&chaindexing_config)
+            .await
+            .map_err(|err| eyre!("Failed to start indexing because:{err:?}"))
+    }
 }
 
-pub fn get_reset_count() -> u64 {
-    dotenvy::dotenv().ok();
+/// The profile's contracts, also used to look up handlers when retrying quarantined events
+pub fn get_contracts(config: &ArkConfig, clock: Arc<dyn Clock>) -> Vec<Contract<Arc<DBPool>>> {
+    let mut contracts = vec![];
+
+    if config.profile.has_contract("coinflip") {
+        contracts.push(coinflip_contracts::coinflip::get(config, clock.clone()));
+    }
+    if config.profile.has_contract("wallets") {
+        contracts.push(ark_contracts::wallets::get(config, clock));
+    }
 
-    std::env::var("CHAINDEXING_RESET_COUNT")
-        .map(|rc| rc.parse::<u64>().expect("CHAINDEXING_RESET_COUNT must be of type u64"))
-        .unwrap_or(0)
+    contracts
 }
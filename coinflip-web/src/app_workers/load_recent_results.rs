@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use ark::profiles::Worker;
+use ark_db::{DBConn, DBPool, RepoError, RepoResult};
+use ark_web_common::workers::AppWorker;
+use async_trait::async_trait;
+use coinflip::{RecentResult, RecentResults, RECENT_RESULTS_CAPACITY};
+use eyre::{Result, WrapErr};
+use tracing::info;
+
+const WORKER_INTERVAL_MS: u64 = 10 * 1_000;
+
+/// Keeps the recent results ticker in `AppState` fresh from committed games only,
+/// so wins from blocks chaindexing later rolls back never stick around.
+#[derive(Clone)]
+pub struct LoadRecentResults {
+    pool: Arc<DBPool>,
+    recent_results: Arc<RwLock<RecentResults>>,
+}
+
+impl LoadRecentResults {
+    pub fn new(pool: Arc<DBPool>, recent_results: Arc<RwLock<RecentResults>>) -> Self {
+        Self {
+            pool,
+            recent_results,
+        }
+    }
+}
+
+#[async_trait]
+impl AppWorker for LoadRecentResults {
+    fn get_worker(&self) -> Worker {
+        Worker::LoadRecentResults
+    }
+
+    fn get_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(WORKER_INTERVAL_MS))
+    }
+
+    async fn run(&mut self) -> Result<()> {
+        info!("[LoadRecentResults]: running...");
+
+        let mut conn = self.pool.get().await.map_err(RepoError::from)?;
+        let recent_results =
+            get_recent_results(&mut conn).await.wrap_err("Failed to load recent results")?;
+
+        self.recent_results.write().unwrap().reload(recent_results);
+
+        Ok(())
+    }
+}
+
+async fn get_recent_results<'a>(conn: &mut DBConn<'a>) -> RepoResult<Vec<RecentResult>> {
+    let game_wins =
+        coinflip_repo::get_recent_game_wins(conn, RECENT_RESULTS_CAPACITY as i64).await?;
+
+    let mut chain_ids: Vec<_> = game_wins.iter().map(|game_win| game_win.chain_id).collect();
+    chain_ids.sort_unstable();
+    chain_ids.dedup();
+    let chain_currencies = ark_repo::get_chain_currencies(conn, &chain_ids).await?;
+    let chain_currencies_by_chain_id: HashMap<_, _> = chain_currencies
+        .iter()
+        .map(|chain_currency| (chain_currency.chain_id, chain_currency))
+        .collect();
+
+    Ok(game_wins
+        .iter()
+        .map(|game_win| {
+            let chain_currency = chain_currencies_by_chain_id.get(&game_win.chain_id).copied();
+
+            RecentResult::new(game_win, chain_currency)
+        })
+        .collect())
+}
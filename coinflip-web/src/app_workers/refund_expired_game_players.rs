@@ -2,79 +2,117 @@ use std::collections::HashMap;
 use std::{sync::Arc, time::Duration};
 
 use super::caching::RecentCache;
-use ark_db::DBPool;
+use ark::configs::ArkConfig;
+use ark::profiles::Worker;
+use ark_db::RepoResult;
+use ark_utils::clocks::Clock;
 use ark_web3::chain_explorers::GasInfo;
-use ark_web3::{chain_explorers, json_rpcs, wallets, CHAIN_AGNOSTIC_MAX_GAS_PRICE};
+use ark_web3::{chain_explorers, wallets, CHAIN_AGNOSTIC_MAX_GAS_PRICE};
+use ark_web_common::workers::AppWorker;
+use ark_web_common::Repos;
+use async_trait::async_trait;
 use chaindexing::KeepNodeActiveRequest;
-use coinflip_repo::GetGamesParams;
-use eyre::Result;
-use tokio::time::{interval, sleep};
+use coinflip::{Game, GetGamesParams};
+use eyre::{eyre, Result, WrapErr};
 use tracing::info;
 
 const WORKER_INTERVAL_MS: u64 = 10 * 60 * 1_000;
+const CHAINDEXING_SETUP_GRACE_PERIOD_SECS: u64 = 1 * 60;
+
+/// Refunds the players of games that expired before filling up
+#[derive(Clone)]
+pub struct RefundExpiredGamePlayers {
+    repos: Repos,
+    keep_chaindexing_node_active_request: KeepNodeActiveRequest,
+    clock: Arc<dyn Clock>,
+    config: Arc<ArkConfig>,
+    cached_gas_infos: RecentCache<ChainId, GasInfo>,
+}
 
-pub fn start(pool: Arc<DBPool>, keep_chaindexing_node_active_request: KeepNodeActiveRequest) {
-    tokio::spawn(async move {
-        let mut has_once_waited_for_chaindexing_setup = false;
-        const CHAINDEXING_SETUP_GRACE_PERIOD_SECS: u64 = 1 * 60;
-
-        let mut interval = interval(Duration::from_millis(WORKER_INTERVAL_MS));
-
-        let pool = pool.clone();
-        let mut conn = pool.get().await.unwrap();
-
+impl RefundExpiredGamePlayers {
+    pub fn new(
+        repos: Repos,
+        keep_chaindexing_node_active_request: KeepNodeActiveRequest,
+        clock: Arc<dyn Clock>,
+        config: Arc<ArkConfig>,
+    ) -> Self {
         const TWENTY_MINS: u64 = 20 * 60;
-        let mut cached_gas_infos = RecentCache::new(TWENTY_MINS);
-
-        loop {
-            if !has_once_waited_for_chaindexing_setup {
-                sleep(Duration::from_secs(CHAINDEXING_SETUP_GRACE_PERIOD_SECS)).await;
-                has_once_waited_for_chaindexing_setup = true;
-            }
 
-            info!("[RefundExpiredGamePlayers]: running...");
-
-            let get_games_params = GetGamesParams::new().expired().not_refunded();
+        Self {
+            repos,
+            keep_chaindexing_node_active_request,
+            cached_gas_infos: RecentCache::new(TWENTY_MINS, clock.clone()),
+            clock,
+            config,
+        }
+    }
+}
 
-            let games = coinflip_repo::get_games(&mut conn, &get_games_params).await;
+#[async_trait]
+impl AppWorker for RefundExpiredGamePlayers {
+    fn get_worker(&self) -> Worker {
+        Worker::RefundExpiredGamePlayers
+    }
 
-            info!(
-                "[RefundExpiredGamePlayers]: Found {} games...",
-                &games.len()
-            );
+    fn get_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(WORKER_INTERVAL_MS))
+    }
 
-            let game_ids_by_chain_id =
-                games.iter().fold(HashMap::new(), |mut game_ids_by_chain_id, game| {
-                    match game_ids_by_chain_id.get(&game.chain_id) {
-                        None => {
-                            game_ids_by_chain_id.insert(game.chain_id, vec![game.id]);
-                        }
-                        Some(game_ids) => {
-                            let mut new_game_ids = game_ids.clone();
-                            new_game_ids.push(game.id);
-                            game_ids_by_chain_id.insert(game.chain_id, new_game_ids);
-                        }
-                    }
+    fn get_initial_delay(&self) -> Duration {
+        Duration::from_secs(CHAINDEXING_SETUP_GRACE_PERIOD_SECS)
+    }
 
-                    game_ids_by_chain_id
-                });
+    async fn run(&mut self) -> Result<()> {
+        info!("[RefundExpiredGamePlayers]: running...");
 
-            match refund_expired_game_players_for_all_games(
-                game_ids_by_chain_id,
-                &mut cached_gas_infos,
-            )
+        let games = get_expired_games(&self.repos, self.clock.as_ref())
             .await
-            {
-                Ok(()) => keep_chaindexing_node_active_request.refresh().await,
-                Err(err) => {
-                    cached_gas_infos.invalidate_all();
-                    dbg!(err.to_string());
+            .wrap_err("Failed to load games")?;
+
+        info!(
+            "[RefundExpiredGamePlayers]: Found {} games...",
+            &games.len()
+        );
+
+        let game_ids_by_chain_id =
+            games.iter().fold(HashMap::new(), |mut game_ids_by_chain_id, game| {
+                match game_ids_by_chain_id.get(&game.chain_id) {
+                    None => {
+                        game_ids_by_chain_id.insert(game.chain_id, vec![game.id]);
+                    }
+                    Some(game_ids) => {
+                        let mut new_game_ids = game_ids.clone();
+                        new_game_ids.push(game.id);
+                        game_ids_by_chain_id.insert(game.chain_id, new_game_ids);
+                    }
                 }
-            }
 
-            interval.tick().await;
+                game_ids_by_chain_id
+            });
+
+        match refund_expired_game_players_for_all_games(
+            game_ids_by_chain_id,
+            &self.config,
+            &mut self.cached_gas_infos,
+        )
+        .await
+        {
+            Ok(()) => {
+                self.keep_chaindexing_node_active_request.refresh().await;
+                Ok(())
+            }
+            Err(err) => {
+                self.cached_gas_infos.invalidate_all();
+                Err(err.wrap_err("Failed to refund expired game players"))
+            }
         }
-    });
+    }
+}
+
+async fn get_expired_games(repos: &Repos, clock: &dyn Clock) -> RepoResult<Vec<Game>> {
+    let get_games_params = GetGamesParams::new().expired().not_refunded().unbounded();
+
+    repos.games.get_games(&get_games_params, clock).await
 }
 
 use ethers::contract::abigen;
@@ -92,8 +130,10 @@ abigen!(
     ]"#,
 );
 
-async fn refund_expired_game_players_for_all_games(
+/// Signs and sends one refund per chain for all of its expired games
+pub async fn refund_expired_game_players_for_all_games(
     game_ids_by_chain_id: HashMap<i64, Vec<i64>>,
+    config: &ArkConfig,
     cached_gas_infos: &mut RecentCache<ChainId, GasInfo>,
 ) -> Result<()> {
     for (chain_id, game_ids) in game_ids_by_chain_id.iter() {
@@ -105,16 +145,19 @@ async fn refund_expired_game_players_for_all_games(
             LinearGasPrice::new(increase_by, every_min, max_price)
         };
 
-        let chain_id = &<u64 as Into<ChainId>>::into(*chain_id as u64);
-        let provider = Provider::<Http>::try_from(&json_rpcs::get_url(chain_id.into())).unwrap();
+        let chain_config = config
+            .get_chain(&(*chain_id as u64).into())
+            .ok_or_else(|| eyre!("Chain:{chain_id} is not configured"))?;
+        let chain_id = &chain_config.chain_id;
+        let provider = Provider::<Http>::try_from(&chain_config.json_rpc_url).unwrap();
         let provider = GasEscalatorMiddleware::new(provider, escalator, Frequency::PerBlock);
 
-        let wallet = wallets::get(chain_id);
+        let wallet = wallets::get(chain_config);
         let client = SignerMiddleware::new(provider, wallet);
         let client = Arc::new(client);
 
         let coinflip_contract_address: Address =
-            chain_id.get_contract_address("COINFLIP").parse().unwrap();
+            chain_config.get_contract_address("coinflip").parse().unwrap();
         let coinflip_contract = CoinflipContract::new(coinflip_contract_address, client);
         let game_ids: Vec<_> = game_ids.iter().map(|game_id| U256::from(*game_id as u64)).collect();
 
@@ -122,7 +165,7 @@ async fn refund_expired_game_players_for_all_games(
             if let Some(gas_info) = cached_gas_infos.get(chain_id) {
                 gas_info.clone()
             } else {
-                let gas_info = chain_explorers::get_gas_info(&chain_id).await?;
+                let gas_info = chain_explorers::get_gas_info(chain_config).await?;
                 cached_gas_infos.insert(*chain_id, gas_info.clone());
                 gas_info
             }
@@ -1,116 +1,187 @@
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use super::caching::RecentCache;
-use ark_db::DBPool;
+use ark::configs::ArkConfig;
+use ark::profiles::Worker;
+use ark_db::RepoResult;
+use ark_utils::clocks::Clock;
 use ark_web3::chain_explorers::GasInfo;
-use ark_web3::{chain_explorers, json_rpcs, wallets, CHAIN_AGNOSTIC_MAX_GAS_PRICE};
+use ark_web3::{chain_explorers, wallets, CHAIN_AGNOSTIC_MAX_GAS_PRICE};
+use ark_web_common::workers::AppWorker;
+use ark_web_common::Repos;
+use async_trait::async_trait;
 use chaindexing::KeepNodeActiveRequest;
-use coinflip::GamePlay;
-use coinflip_repo::GetGamesParams;
-use eyre::Result;
-use tokio::time::{interval, sleep};
-use tracing::{error, info};
-
-const WORKER_INTERVAL_MS: u64 = 1 * 60 * 1_000;
-
-pub fn start(pool: Arc<DBPool>, keep_chaindexing_node_active_request: KeepNodeActiveRequest) {
-    tokio::spawn(async move {
-        let mut has_once_waited_for_chaindexing_setup = false;
-        const CHAINDEXING_SETUP_GRACE_PERIOD_SECS: u64 = 1 * 60;
-
-        let mut interval = interval(Duration::from_millis(WORKER_INTERVAL_MS));
-
-        let pool = pool.clone();
-        let mut conn = pool.get().await.unwrap();
+use coinflip::{ChanceCipher, Game, GamePlay, GameStatus, GetGamesParams, SubmittedChance};
+use eyre::{Result, WrapErr};
+use tracing::{error, info, warn};
+
+// Short, since games with every chance escrowed can be revealed as soon as the last seat fills
+const WORKER_INTERVAL_MS: u64 = 15 * 1_000;
+const CHAINDEXING_SETUP_GRACE_PERIOD_SECS: u64 = 1 * 60;
+// A sent reveal is given this long to get indexed before it is resent
+const REVEAL_CONFIRMATION_TIMEOUT_SECS: u64 = 5 * 60;
+
+/// Reveals the chances of games whose seats have all filled, so their winners get credited
+#[derive(Clone)]
+pub struct RevealGamePlayChances {
+    repos: Repos,
+    keep_chaindexing_node_active_request: KeepNodeActiveRequest,
+    clock: Arc<dyn Clock>,
+    config: Arc<ArkConfig>,
+    chance_cipher: ChanceCipher,
+    cached_gas_infos: RecentCache<ChainId, GasInfo>,
+    /// Reveals sent per (game_id, chain_id), awaiting confirmation
+    sent_reveals: RecentCache<(i64, i64), ()>,
+}
 
+impl RevealGamePlayChances {
+    pub fn new(
+        repos: Repos,
+        keep_chaindexing_node_active_request: KeepNodeActiveRequest,
+        clock: Arc<dyn Clock>,
+        config: Arc<ArkConfig>,
+    ) -> Self {
         const FIFTEEN_MINS: u64 = 20 * 60;
-        let mut cached_gas_infos = RecentCache::new(FIFTEEN_MINS);
-
-        loop {
-            if !has_once_waited_for_chaindexing_setup {
-                sleep(Duration::from_secs(CHAINDEXING_SETUP_GRACE_PERIOD_SECS)).await;
-                has_once_waited_for_chaindexing_setup = true;
-            }
 
-            info!("[RevealGamePlayChances]: running...");
-
-            let get_games_params = GetGamesParams::new().not_expired().only_incomplete();
-
-            let games = coinflip_repo::get_games(&mut conn, &get_games_params).await;
-
-            info!("[RevealGamePlayChances]: Found {} games...", &games.len());
-
-            let game_and_chain_ids: Vec<_> =
-                games.clone().iter().map(|g| (g.id, g.chain_id)).collect();
-
-            let mut game_plays =
-                coinflip_repo::get_all_game_plays_with_proofs(&mut conn, &game_and_chain_ids).await;
-
-            // Sort to ensure chances_and_salts are in the expected ascending order in terms of their ids
-            game_plays.sort_by(|a, b| a.cmp(b));
+        Self {
+            repos,
+            keep_chaindexing_node_active_request,
+            chance_cipher: ChanceCipher::new(&config.chance_encryption_key),
+            cached_gas_infos: RecentCache::new(FIFTEEN_MINS, clock.clone()),
+            sent_reveals: RecentCache::new(REVEAL_CONFIRMATION_TIMEOUT_SECS, clock.clone()),
+            clock,
+            config,
+        }
+    }
+}
 
-            let chance_and_salts_per_game = game_plays.iter().fold(
-                HashMap::new(),
-                |mut chance_and_salts_per_game, game_play| {
-                    let game_id = game_play.game_id;
-                    let chain_id = game_play.chain_id;
-                    let game_and_chain_id = (game_id, chain_id);
+#[async_trait]
+impl AppWorker for RevealGamePlayChances {
+    fn get_worker(&self) -> Worker {
+        Worker::RevealGamePlayChances
+    }
+
+    fn get_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(WORKER_INTERVAL_MS))
+    }
+
+    fn get_initial_delay(&self) -> Duration {
+        Duration::from_secs(CHAINDEXING_SETUP_GRACE_PERIOD_SECS)
+    }
+
+    async fn run(&mut self) -> Result<()> {
+        info!("[RevealGamePlayChances]: running...");
+
+        // Confirmed reveals complete their games, so those games stop showing up below
+        self.sent_reveals.remove_stale();
+
+        let (games, mut submitted_chances) =
+            get_games_with_submitted_chances(&self.repos, self.clock.as_ref())
+                .await
+                .wrap_err("Failed to load games")?;
+
+        info!("[RevealGamePlayChances]: Found {} games...", &games.len());
+
+        // Sort to ensure chances_and_salts are in the expected ascending order in terms of their game play ids
+        submitted_chances.sort_by_key(|submitted_chance| submitted_chance.game_play_id);
+
+        let chance_cipher = &self.chance_cipher;
+        let chance_and_salts_per_game = submitted_chances.iter().fold(
+            HashMap::new(),
+            |mut chance_and_salts_per_game, submitted_chance| {
+                let game_id = submitted_chance.game_id;
+                let chain_id = submitted_chance.chain_id;
+                let game_and_chain_id = (game_id, chain_id);
+
+                let Some(chance_and_salt) = submitted_chance.get_chance_and_salt(chance_cipher)
+                else {
+                    error!("[RevealGamePlayChances]: Could not decrypt chance for GamePlay:{} of Game:{game_id} on Chain:{chain_id}", submitted_chance.game_play_id);
+                    return chance_and_salts_per_game;
+                };
+
+                let players_chance_and_salt: Bytes =
+                    GamePlay::get_chance_and_salt_bytes(&chance_and_salt).into();
+
+                match chance_and_salts_per_game.get(&game_and_chain_id) {
+                    None => {
+                        chance_and_salts_per_game
+                            .insert(game_and_chain_id, vec![players_chance_and_salt]);
+                    }
+                    Some(chance_and_salts) => {
+                        let mut new_chance_and_salts = chance_and_salts.clone();
+                        new_chance_and_salts.push(players_chance_and_salt);
 
-                    let players_chance_and_salt: Bytes = GamePlay::get_chance_and_salt_bytes(
-                        &game_play.chance_and_salt.clone().unwrap(),
-                    )
-                    .into();
+                        chance_and_salts_per_game.insert(game_and_chain_id, new_chance_and_salts);
+                    }
+                }
 
-                    match chance_and_salts_per_game.get(&game_and_chain_id) {
-                        None => {
-                            chance_and_salts_per_game
-                                .insert(game_and_chain_id, vec![players_chance_and_salt]);
-                        }
-                        Some(chance_and_salts) => {
-                            let mut new_chance_and_salts = chance_and_salts.clone();
-                            new_chance_and_salts.push(players_chance_and_salt);
+                chance_and_salts_per_game
+            },
+        );
+
+        let games_by_id_and_chain_id =
+            games.iter().fold(HashMap::new(), |mut games_by_id_and_chain_id, game| {
+                games_by_id_and_chain_id.insert((game.id, game.chain_id), game);
+                games_by_id_and_chain_id
+            });
+
+        for ((game_id, chain_id), chance_and_salts) in chance_and_salts_per_game.iter() {
+            let Some(game) = games_by_id_and_chain_id.get(&(*game_id, *chain_id)) else {
+                warn!("[RevealGamePlayChances]: Skipping chances for Game:{game_id} on Chain:{chain_id} since the game was not found");
+                continue;
+            };
+
+            if self.sent_reveals.get(&(*game_id, *chain_id)).is_some() {
+                info!("[RevealGamePlayChances]: Skipping Game:{game_id} on Chain:{chain_id} since its reveal is awaiting confirmation");
+                continue;
+            }
 
-                            chance_and_salts_per_game
-                                .insert(game_and_chain_id, new_chance_and_salts);
-                        }
+            if game.has_all_chances_uploaded(chance_and_salts.len()) {
+                let Some(chain_config) = self.config.get_chain(&(*chain_id as u64).into()) else {
+                    error!("[RevealGamePlayChances]: Chain:{chain_id} of Game:{game_id} is not configured");
+                    continue;
+                };
+
+                match reveal_chances_and_credit_winners(
+                    *game_id as u64,
+                    chain_config,
+                    chance_and_salts,
+                    &mut self.cached_gas_infos,
+                )
+                .await
+                {
+                    Ok(()) => {
+                        self.sent_reveals.insert((*game_id, *chain_id), ());
+                        self.keep_chaindexing_node_active_request.refresh().await;
+                        info!("[RevealGamePlayChances]: Revealed chances for Game:{game_id} on Chain:{chain_id}...");
                     }
-
-                    chance_and_salts_per_game
-                },
-            );
-
-            let games_by_id_and_chain_id =
-                games.iter().fold(HashMap::new(), |mut games_by_id_and_chain_id, game| {
-                    games_by_id_and_chain_id.insert((game.id, game.chain_id), game);
-                    games_by_id_and_chain_id
-                });
-
-            for ((game_id, chain_id), chance_and_salts) in chance_and_salts_per_game.iter() {
-                let game = games_by_id_and_chain_id.get(&(*game_id, *chain_id)).unwrap();
-                if game.has_all_chances_uploaded(chance_and_salts.len()) {
-                    match reveal_chances_and_credit_winners(
-                        *game_id as u64,
-                        *chain_id as u64,
-                        chance_and_salts,
-                        &mut cached_gas_infos,
-                    )
-                    .await
-                    {
-                        Ok(()) => {
-                            keep_chaindexing_node_active_request.refresh().await;
-                            info!("[RevealGamePlayChances]: Revealed chances for Game:{game_id} on Chain:{chain_id}...");
-                        }
-                        Err(err) => {
-                            cached_gas_infos.invalidate_all();
-                            error!("[RevealGamePlayChances]: Failed to reveal chances for Game:{game_id} on Chain:{chain_id} because:{err_str}", err_str=err.to_string());
-                        }
+                    Err(err) => {
+                        self.cached_gas_infos.invalidate_all();
+                        error!("[RevealGamePlayChances]: Failed to reveal chances for Game:{game_id} on Chain:{chain_id} because:{err_str}", err_str=err.to_string());
                     }
                 }
             }
-
-            interval.tick().await;
         }
-    });
+
+        Ok(())
+    }
+}
+
+/// Expired games are left out, for the refund worker
+async fn get_games_with_submitted_chances(
+    repos: &Repos,
+    clock: &dyn Clock,
+) -> RepoResult<(Vec<Game>, Vec<SubmittedChance>)> {
+    let get_games_params = GetGamesParams::new()
+        .with_status(GameStatus::AwaitingRevealedChances)
+        .unbounded();
+
+    let games = repos.games.get_games(&get_games_params, clock).await?;
+
+    let game_and_chain_ids: Vec<_> = games.iter().map(|g| (g.id, g.chain_id)).collect();
+    let submitted_chances = repos.game_plays.get_submitted_chances(&game_and_chain_ids).await?;
+
+    Ok((games, submitted_chances))
 }
 
 use ethers::contract::abigen;
@@ -120,7 +191,7 @@ use ethers::middleware::SignerMiddleware;
 use ethers::providers::{Http, Provider};
 use ethers::types::{Address, Bytes, U256};
 
-use ark_web3::chains::ChainId;
+use ark_web3::chains::{ChainConfig, ChainId};
 
 abigen!(
     CoinflipContract,
@@ -129,13 +200,14 @@ abigen!(
     ]"#,
 );
 
-async fn reveal_chances_and_credit_winners(
+/// Signs and sends one reveal for all of the game's chances
+pub async fn reveal_chances_and_credit_winners(
     game_id: u64,
-    chain_id: u64,
+    chain_config: &ChainConfig,
     chance_and_salts: &Vec<Bytes>,
     cached_gas_infos: &mut RecentCache<ChainId, GasInfo>,
 ) -> Result<()> {
-    let chain_id = &<u64 as Into<ChainId>>::into(chain_id);
+    let chain_id = &chain_config.chain_id;
     let escalator = {
         let every_secs: u64 = 60;
         let max_price: Option<u64> = Some(CHAIN_AGNOSTIC_MAX_GAS_PRICE);
@@ -143,22 +215,22 @@ async fn reveal_chances_and_credit_winners(
 
         GeometricGasPrice::new(coefficient, every_secs, max_price)
     };
-    let provider = Provider::<Http>::try_from(&json_rpcs::get_url(chain_id.into())).unwrap();
+    let provider = Provider::<Http>::try_from(&chain_config.json_rpc_url).unwrap();
     let provider = GasEscalatorMiddleware::new(provider, escalator, Frequency::PerBlock);
 
-    let wallet = wallets::get(chain_id.into());
+    let wallet = wallets::get(chain_config);
     let client = SignerMiddleware::new(provider, wallet);
     let client = Arc::new(client);
 
     let coinflip_contract_address: Address =
-        chain_id.get_contract_address("COINFLIP").parse().unwrap();
+        chain_config.get_contract_address("coinflip").parse().unwrap();
     let coinflip_contract = CoinflipContract::new(coinflip_contract_address, client);
 
     let cached_gas_info = {
         if let Some(gas_info) = cached_gas_infos.get(chain_id) {
             gas_info.clone()
         } else {
-            let gas_info = chain_explorers::get_gas_info(&chain_id).await?;
+            let gas_info = chain_explorers::get_gas_info(chain_config).await?;
             cached_gas_infos.insert(*chain_id, gas_info.clone());
             gas_info
         }
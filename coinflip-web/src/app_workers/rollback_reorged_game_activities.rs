@@ -0,0 +1,49 @@
+use std::{sync::Arc, time::Duration};
+
+use ark::profiles::Worker;
+use ark_db::{DBPool, RepoError};
+use ark_web_common::workers::AppWorker;
+use async_trait::async_trait;
+use eyre::{Result, WrapErr};
+use tracing::info;
+
+const WORKER_INTERVAL_MS: u64 = 60 * 1_000;
+
+/// Game activities live outside chaindexing's managed states, so chaindexing doesn't rewind them
+/// on reorgs. This drops the ones whose source events were removed once the reorg got handled.
+#[derive(Clone)]
+pub struct RollbackReorgedGameActivities {
+    pool: Arc<DBPool>,
+}
+
+impl RollbackReorgedGameActivities {
+    pub fn new(pool: Arc<DBPool>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AppWorker for RollbackReorgedGameActivities {
+    fn get_worker(&self) -> Worker {
+        Worker::RollbackReorgedGameActivities
+    }
+
+    fn get_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(WORKER_INTERVAL_MS))
+    }
+
+    async fn run(&mut self) -> Result<()> {
+        info!("[RollbackReorgedGameActivities]: running...");
+
+        let mut conn = self.pool.get().await.map_err(RepoError::from)?;
+        let rolled_back_count = coinflip_repo::rollback_reorged_game_activities(&mut conn)
+            .await
+            .wrap_err("Failed to roll back game activities")?;
+
+        if rolled_back_count > 0 {
+            info!("[RollbackReorgedGameActivities]: Rolled back {rolled_back_count} game activities...");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use std::{sync::Arc, time::Duration};
+
+use ark::configs::ArkConfig;
+use ark::profiles::Worker;
+use ark_db::{DBConn, DBPool, RepoError};
+use ark_web_common::workers::AppWorker;
+use async_trait::async_trait;
+use coinflip::{ServiceChargeSource, UnsavedServiceCharge};
+use eyre::Result;
+use tracing::{error, info};
+
+const WORKER_INTERVAL_MS: u64 = 30 * 60 * 1_000;
+
+/// Records the Coinflip contract's service charge whenever it changes.
+/// Chains whose contract doesn't expose `serviceChargePercent` keep their configured schedule.
+#[derive(Clone)]
+pub struct SyncServiceCharges {
+    pool: Arc<DBPool>,
+    config: Arc<ArkConfig>,
+}
+
+impl SyncServiceCharges {
+    pub fn new(pool: Arc<DBPool>, config: Arc<ArkConfig>) -> Self {
+        Self { pool, config }
+    }
+}
+
+#[async_trait]
+impl AppWorker for SyncServiceCharges {
+    fn get_worker(&self) -> Worker {
+        Worker::SyncServiceCharges
+    }
+
+    fn get_interval(&self) -> Option<Duration> {
+        Some(Duration::from_millis(WORKER_INTERVAL_MS))
+    }
+
+    async fn run(&mut self) -> Result<()> {
+        info!("[SyncServiceCharges]: running...");
+
+        let mut conn = self.pool.get().await.map_err(RepoError::from)?;
+
+        for chain_config in self.config.chains.iter() {
+            if let Err(err) = sync_service_charge(&mut conn, chain_config).await {
+                error!(
+                    "[SyncServiceCharges]: Failed to sync service charge for Chain:{chain_id} because:{err_str}",
+                    chain_id = chain_config.chain_id as u64,
+                    err_str = err.to_string()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+use ethers::contract::abigen;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::types::Address;
+
+use ark_web3::chains::ChainConfig;
+
+abigen!(
+    CoinflipContract,
+    r#"[
+        function serviceChargePercent() external view returns (uint8)
+    ]"#,
+);
+
+async fn sync_service_charge<'a>(conn: &mut DBConn<'a>, chain_config: &ChainConfig) -> Result<()> {
+    let provider = Provider::<Http>::try_from(&chain_config.json_rpc_url)?;
+    let provider = Arc::new(provider);
+
+    let coinflip_contract_address: Address =
+        chain_config.get_contract_address("coinflip").parse()?;
+    let coinflip_contract = CoinflipContract::new(coinflip_contract_address, provider.clone());
+
+    let percent = coinflip_contract.service_charge_percent().call().await? as i32;
+
+    let chain_id = chain_config.chain_id as i64;
+    let last_service_charge = coinflip_repo::get_last_service_charge(conn, chain_id).await?;
+
+    if last_service_charge.as_ref().map(|service_charge| service_charge.percent) != Some(percent) {
+        // The first percent observed has applied since deployment, so games played
+        // before the worker first ran are charged it too
+        let block_number = match last_service_charge {
+            None => chain_config.get_contract("coinflip").unwrap().start_block_number,
+            Some(_) => provider.get_block_number().await?.as_u64() as i64,
+        };
+
+        let service_charge = UnsavedServiceCharge::new(
+            chain_id,
+            percent,
+            block_number,
+            ServiceChargeSource::ContractView,
+        );
+
+        coinflip_repo::create_service_charge(conn, &service_charge).await?;
+    }
+
+    Ok(())
+}
@@ -1,13 +1,13 @@
-use std::sync::Arc;
-
-use http::StatusCode;
-
+pub mod feed_handler;
 pub mod game_activity_handler;
 pub mod game_handler;
 pub mod game_play_handler;
+pub mod house_revenue_handler;
+pub mod player_handler;
+pub mod reveal_reminder_handler;
 use serde::Serialize;
 
-pub type Error = (StatusCode, String);
+pub use ark_web_common::Error;
 
 #[derive(Debug, Serialize)]
 pub struct GenericMessage {
@@ -21,16 +21,3 @@ impl GenericMessage {
         }
     }
 }
-
-use ark_db::{DBConn, DBPool};
-
-pub async fn new_conn<'a>(pool: Arc<DBPool>) -> Result<DBConn<'a>, Error> {
-    Ok(pool.get_owned().await.map_err(internal_error)?)
-}
-
-pub fn internal_error<E>(err: E) -> Error
-where
-    E: std::error::Error,
-{
-    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
-}
@@ -0,0 +1,44 @@
+use ark_web_common::AppState;
+use axum::extract::{Json, Query, State};
+
+use coinflip::{RecentResult, RecentResultCursor, MAX_RECENT_RESULTS_COUNT};
+use serde::{Deserialize, Serialize};
+
+use crate::handlers::{self, Error};
+
+#[derive(Debug, Deserialize)]
+pub struct GetRecentResultsQuery {
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+    pub page_size: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaginatedRecentResults {
+    recent_results: Vec<RecentResult>,
+    /// Pass back as `cursor` for the next page, none when there are no more
+    next_cursor: Option<String>,
+}
+
+/// The latest wins across all chains, newest first.
+/// Served from memory, see `LoadRecentResults`
+pub async fn get_recent_results(
+    State(app_state): State<AppState>,
+    Query(GetRecentResultsQuery { cursor, page_size }): Query<GetRecentResultsQuery>,
+) -> Result<Json<PaginatedRecentResults>, handlers::Error> {
+    let cursor = match cursor {
+        Some(cursor) => {
+            Some(RecentResultCursor::parse(&cursor).ok_or(Error::validation("Invalid cursor"))?)
+        }
+        None => None,
+    };
+    let page_size = page_size.unwrap_or(MAX_RECENT_RESULTS_COUNT).min(MAX_RECENT_RESULTS_COUNT);
+
+    let (recent_results, next_cursor) =
+        app_state.recent_results.read().unwrap().get_page(cursor, page_size);
+
+    Ok(Json(PaginatedRecentResults {
+        recent_results,
+        next_cursor: next_cursor.map(|cursor| cursor.to_string()),
+    }))
+}
@@ -1,65 +1,66 @@
-use std::collections::HashSet;
-
-use crate::handlers;
+use crate::handlers::{self, Error};
+use ark_web_common::auth::AuthenticatedPlayer;
 use ark_web_common::AppState;
 use axum::{
-    extract::{Path, State},
-    Json,
+    extract::{Path, Query, State},
+    Extension, Json,
 };
 
-use coinflip::{GameActivity, GameStatus};
-use coinflip_repo::GetGamesParams;
+use coinflip::{GameActivity, GameStatus, GetPlayerGameActivitiesParams};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct PaginatedGameActivities {
+    game_activities: Vec<GameActivity>,
+    /// Pass back as `cursor` for the next page, none when there are no more
+    next_cursor: Option<i64>,
+}
 
-/// Returns all game activities in games the player is part of
-/// The client can then store the last block number of the read game activity to track
-/// game activities that are new or stale
+/// Returns a page of game activities in games the player is part of, newest first.
+/// Only the signed in player can read their own game activities
 /// TODO: Refactor and allow sending notifications via Websocket
 pub async fn get_all_game_activites(
     State(app_state): State<AppState>,
+    Extension(authenticated_player): Extension<AuthenticatedPlayer>,
     Path((game_status, player_address)): Path<(GameStatus, String)>,
-) -> Result<Json<Vec<GameActivity>>, handlers::Error> {
-    let mut conn = handlers::new_conn(app_state.db_pool).await?;
-
-    let all_games = coinflip_repo::get_games(
-        &mut conn,
-        &GetGamesParams {
-            status: Some(game_status),
-            ..Default::default()
-        },
-    )
-    .await;
+    Query(params): Query<GetPlayerGameActivitiesParams>,
+) -> Result<Json<PaginatedGameActivities>, handlers::Error> {
+    if !authenticated_player.is(&player_address) {
+        return Err(Error::forbidden("Not your game activities"));
+    }
 
-    let all_game_plays = coinflip_repo::get_game_plays_for_player(&mut conn, &player_address).await;
-    let game_ids_and_chain_ids_of_games_played: HashSet<_> = all_game_plays
-        .iter()
-        .map(|game_play| (game_play.game_id, game_play.chain_id))
-        .collect();
+    let game_activities = app_state
+        .repos
+        .activities
+        .get_player_game_activities(
+            &player_address,
+            Some(game_status),
+            app_state.clock.now(),
+            &params,
+        )
+        .await?;
 
-    let (game_ids, game_chain_ids): (Vec<_>, Vec<_>) = all_games
-        .iter()
-        .map(|game| (game.id, game.chain_id))
-        .filter(|(game_id, chain_id)| {
-            game_ids_and_chain_ids_of_games_played.contains(&(*game_id, *chain_id))
-        })
-        .unzip();
+    let next_cursor = if game_activities.len() as i64 == params.get_page_size() {
+        game_activities.last().map(|game_activity| game_activity.id)
+    } else {
+        None
+    };
 
-    let game_activities =
-        coinflip_repo::get_game_activities(&mut conn, &game_ids, &game_chain_ids).await;
-
-    Ok(Json(game_activities))
+    Ok(Json(PaginatedGameActivities {
+        game_activities,
+        next_cursor,
+    }))
 }
 
 pub async fn get_game_activities(
     State(app_state): State<AppState>,
     Path((game_id, chain_id)): Path<(u64, u64)>,
 ) -> Result<Json<Vec<GameActivity>>, handlers::Error> {
-    let mut conn = handlers::new_conn(app_state.db_pool).await?;
-
     let game_id = game_id as i64;
     let chain_id = chain_id as i64;
 
     let game_activities =
-        coinflip_repo::get_game_activities(&mut conn, &vec![game_id], &vec![chain_id]).await;
+        app_state.repos.activities.get_game_activities(&[game_id], &[chain_id]).await?;
 
     Ok(Json(game_activities))
 }
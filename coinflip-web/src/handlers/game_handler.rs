@@ -1,20 +1,21 @@
 use std::collections::HashMap;
 
+use ark_utils::clocks::Clock;
 use ark_utils::floats;
-use ark_web_common::AppState;
+use ark_web_common::auth::AuthenticatedPlayer;
+use ark_web_common::{AppState, Repos};
 
 use axum::{
     extract::{Path, Query, State},
-    Json,
+    Extension, Json,
 };
-use coinflip_repo::GetGamesParams;
 
 use ark_web3::chains::{ChainCurrency, ChainId};
-use coinflip::{Game, GamePlay, GameStatus, PlayerAddress};
-use http::StatusCode;
+use coinflip::{ChanceVisibility, CoinSide, Game, GamePlay, GameStatus, PayoutScenario};
+use coinflip::{GetGamesParams, Viewer};
 use serde::{Deserialize, Serialize};
 
-use crate::handlers;
+use crate::handlers::{self, Error};
 
 #[derive(Debug, Serialize)]
 pub struct PaginatedGames {
@@ -27,38 +28,25 @@ pub async fn get_games(
     State(app_state): State<AppState>,
     query_params: Query<GetGamesParams>,
 ) -> Result<Json<PaginatedGames>, handlers::Error> {
-    let mut conn = handlers::new_conn(app_state.db_pool).await?;
-
-    let games = coinflip_repo::get_games(&mut conn, &query_params).await;
+    if query_params
+        .available_coin_side
+        .is_some_and(|coin_side| !matches!(coin_side, 0 | 1))
+    {
+        return Err(Error::invalid_params(
+            "available_coin_side must be 0 for head or 1 for tail",
+        ));
+    }
 
-    let chain_ids: Vec<_> = games.iter().map(|game| game.get_chain_id()).collect();
+    let repos = &app_state.repos;
+    let clock = app_state.clock.as_ref();
 
-    let chain_currencies = ark_repo::get_chain_currencies(&mut conn, &chain_ids).await;
-    let chain_currencies_by_chain_id = chain_currencies.iter().fold(
-        HashMap::new(),
-        |mut chain_currencies_by_chain_id, chain_currency| {
-            chain_currencies_by_chain_id.insert(chain_currency.chain_id, chain_currency);
-
-            if chain_currency.chain_id == (ChainId::Ethereum as i64) {
-                chain_currencies_by_chain_id.insert(ChainId::Local as i64, chain_currency);
-                chain_currencies_by_chain_id.insert(ChainId::LocalAlt as i64, chain_currency);
-            }
-            chain_currencies_by_chain_id
-        },
-    );
+    let games = repos.games.get_games(&query_params, clock).await?;
 
-    let games = games
-        .iter()
-        .map(|game| {
-            let chain_currency = chain_currencies_by_chain_id.get(&game.chain_id).unwrap();
+    let games = get_game_responses(repos, &games, clock).await?;
+    let total_completed_games_count = repos.games.get_total_completed_games_count().await?;
+    let total_games_count = repos.games.get_total_games_count().await?;
 
-            GameResponse::new(game, *chain_currency)
-        })
-        .collect();
-    let total_completed_games_count =
-        coinflip_repo::get_total_completed_games_count(&mut conn).await;
-    let total_games_count = coinflip_repo::get_total_games_count(&mut conn).await;
-    let total_paid_out_report = ark_repo::get_last_total_paid_out_report(&mut conn).await;
+    let total_paid_out_report = repos.wallets.get_last_total_paid_out_report().await?;
     let total_paid_out_amount = total_paid_out_report.map(|r| r.get_amount()).unwrap_or_default();
 
     Ok(Json(PaginatedGames {
@@ -69,54 +57,123 @@ pub async fn get_games(
     }))
 }
 
-#[derive(Debug, Deserialize)]
-pub struct GetGameParams {
-    pub player_address: Option<String>,
-}
-
+/// Player specific fields e.g. `my_game_play_id` are only included for a signed in player
 pub async fn get_game(
     State(app_state): State<AppState>,
+    maybe_authenticated_player: Option<Extension<AuthenticatedPlayer>>,
     Path((id, chain_id)): Path<(u64, u64)>,
-    Query(GetGameParams { player_address }): Query<GetGameParams>,
 ) -> Result<Json<GameResponse>, handlers::Error> {
     let id = id as i64;
     let chain_id = chain_id as i64;
 
-    let mut conn = handlers::new_conn(app_state.db_pool).await?;
+    let repos = &app_state.repos;
 
-    let game = coinflip_repo::get_game(&mut conn, id, chain_id).await;
-    let chain_currency = ark_repo::get_chain_currency(&mut conn, chain_id).await.unwrap();
+    let game = repos.games.get_game(id, chain_id).await?;
 
     match game {
         Some(game) => {
-            let game_response = GameResponse::new(&game, &chain_currency);
-            let game_plays = coinflip_repo::get_game_plays(&mut conn, game.id, chain_id).await;
-
-            if let Some(player_address) = player_address {
+            let chain_currency = repos
+                .chain_currencies
+                .get_chain_currency(chain_id)
+                .await?
+                .ok_or(Error::upstream_unavailable(NO_USD_PRICE_MESSAGE))?;
+            let service_charge_schedule =
+                repos.games.get_service_charge_schedule(&[chain_id]).await?;
+            let service_charge_percent = service_charge_schedule.get_percent_for_game(&game);
+
+            let game_response = GameResponse::new(
+                &game,
+                &chain_currency,
+                service_charge_percent,
+                app_state.clock.as_ref(),
+            );
+            let game_plays = repos.game_plays.get_game_plays(game.id, chain_id).await?;
+
+            if let Some(Extension(authenticated_player)) = maybe_authenticated_player {
                 let maybe_game_play = game_plays
                     .iter()
-                    .find(|gp| PlayerAddress::do_both_match(&gp.player_address, &player_address))
+                    .find(|gp| authenticated_player.is(&gp.player_address))
                     .cloned();
 
+                let has_submitted_chance = match &maybe_game_play {
+                    Some(game_play) => {
+                        repos.game_plays.get_submitted_chance(game_play).await?.is_some()
+                    }
+                    None => false,
+                };
+
                 let game_response = game_response
-                    .maybe_set_is_awaiting_my_chance_reveal(&game, &maybe_game_play)
+                    .maybe_set_is_awaiting_my_chance_reveal(
+                        &game,
+                        &maybe_game_play,
+                        has_submitted_chance,
+                    )
                     .maybe_set_my_game_play_id(&maybe_game_play);
 
                 Ok(Json(game_response.maybe_include_completed_game_data(
+                    &game,
                     &game_plays,
                     &chain_currency,
+                    &Viewer::Player(authenticated_player.address),
                 )))
             } else {
                 Ok(Json(game_response.maybe_include_completed_game_data(
+                    &game,
                     &game_plays,
                     &chain_currency,
+                    &Viewer::Anonymous,
                 )))
             }
         }
-        None => Err((StatusCode::NOT_FOUND, "Game not found".to_string())),
+        None => Err(Error::not_found("Game not found")),
     }
 }
 
+const NO_USD_PRICE_MESSAGE: &str = "No USD price for chain yet";
+
+/// Prices each game in its chain's currency with the service charge it was created under
+pub(crate) async fn get_game_responses(
+    repos: &Repos,
+    games: &Vec<Game>,
+    clock: &dyn Clock,
+) -> Result<Vec<GameResponse>, Error> {
+    let chain_ids: Vec<_> = games.iter().map(|game| game.get_chain_id()).collect();
+
+    let chain_currencies = repos.chain_currencies.get_chain_currencies(&chain_ids).await?;
+    let chain_currencies_by_chain_id = chain_currencies.iter().fold(
+        HashMap::new(),
+        |mut chain_currencies_by_chain_id, chain_currency| {
+            chain_currencies_by_chain_id.insert(chain_currency.chain_id, chain_currency);
+
+            if chain_currency.chain_id == (ChainId::Ethereum as i64) {
+                chain_currencies_by_chain_id.insert(ChainId::Local as i64, chain_currency);
+                chain_currencies_by_chain_id.insert(ChainId::LocalAlt as i64, chain_currency);
+            }
+            chain_currencies_by_chain_id
+        },
+    );
+
+    let game_chain_ids: Vec<_> = games.iter().map(|game| game.chain_id).collect();
+    let service_charge_schedule = repos.games.get_service_charge_schedule(&game_chain_ids).await?;
+
+    games
+        .iter()
+        .map(|game| {
+            let chain_currency = chain_currencies_by_chain_id
+                .get(&game.chain_id)
+                .ok_or(Error::upstream_unavailable(NO_USD_PRICE_MESSAGE))?;
+            let service_charge_percent = service_charge_schedule.get_percent_for_game(game);
+
+            Ok(GameResponse::new(
+                game,
+                *chain_currency,
+                service_charge_percent,
+                clock,
+            ))
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RevealedProofOfChance {
     pub player_address: String,
@@ -124,6 +181,29 @@ pub struct RevealedProofOfChance {
     pub proof_of_chance: String,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PayoutScenarioResponse {
+    pub min_possible_win: f64,
+    pub min_possible_win_usd: f64,
+    pub max_possible_win: f64,
+    pub max_possible_win_usd: f64,
+}
+
+impl PayoutScenarioResponse {
+    fn new(payout_scenario: &PayoutScenario, chain_currency: &ChainCurrency) -> Self {
+        PayoutScenarioResponse {
+            min_possible_win: payout_scenario.min_possible_win,
+            min_possible_win_usd: floats::to_2dp(
+                chain_currency.convert_to_usd(payout_scenario.min_possible_win),
+            ),
+            max_possible_win: payout_scenario.max_possible_win,
+            max_possible_win_usd: floats::to_2dp(
+                chain_currency.convert_to_usd(payout_scenario.max_possible_win),
+            ),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GameResponse {
     id: u64,
@@ -135,6 +215,11 @@ pub struct GameResponse {
     wager: f64,
     wager_usd: f64,
     max_possible_win_usd: f64,
+    service_charge_percent: i32,
+    head_play_count: u32,
+    tail_play_count: u32,
+    head_payout: Option<PayoutScenarioResponse>,
+    tail_payout: Option<PayoutScenarioResponse>,
     players_left: u32,
     total_players_required: u32,
     unavailable_coin_side: Option<i32>,
@@ -153,7 +238,12 @@ pub struct GameResponse {
 }
 
 impl GameResponse {
-    fn new(game: &Game, chain_currency: &ChainCurrency) -> Self {
+    fn new(
+        game: &Game,
+        chain_currency: &ChainCurrency,
+        service_charge_percent: i32,
+        clock: &dyn Clock,
+    ) -> Self {
         let total_players_required = game.number_of_players as u32;
 
         let wager = game.get_wager_ether();
@@ -168,14 +258,21 @@ impl GameResponse {
             block_number: game.block_number as u64,
             outcome: game.outcome,
             completed_at: game.completed_at,
-            status: game.get_status(),
+            status: game.get_current_status(clock),
             wager,
             wager_usd,
-            // TODO: Should be calculated from the number of heads and tails so far (whichever has most)
-            // If no play yet, then it is total players required * wager usd
-            max_possible_win_usd: Game::deduct_service_charge(
-                total_players_required as f64 * wager_usd,
+            max_possible_win_usd: floats::to_2dp(
+                chain_currency.convert_to_usd(game.get_max_possible_win(service_charge_percent)),
+            ),
+            head_play_count: game.head_play_count as u32,
+            tail_play_count: game.tail_play_count as u32,
+            head_payout: game.get_payout_scenario(&CoinSide::Head, service_charge_percent).map(
+                |payout_scenario| PayoutScenarioResponse::new(&payout_scenario, chain_currency),
             ),
+            tail_payout: game.get_payout_scenario(&CoinSide::Tail, service_charge_percent).map(
+                |payout_scenario| PayoutScenarioResponse::new(&payout_scenario, chain_currency),
+            ),
+            service_charge_percent,
             players_left: game.get_players_left(),
             total_players_required,
             is_awaiting_my_chance_reveal: None, // view_count: 0,
@@ -196,16 +293,30 @@ impl GameResponse {
         mut self,
         game: &Game,
         maybe_game_play: &Option<GamePlay>,
+        has_submitted_chance: bool,
     ) -> Self {
         self.is_awaiting_my_chance_reveal = if !game.is_awaiting_revealed_chances() {
             None
         } else {
-            maybe_game_play.as_ref().map(|game_play| game_play.chance_and_salt.is_none())
+            maybe_game_play
+                .as_ref()
+                .map(|game_play| game_play.chance_and_salt.is_none() && !has_submitted_chance)
         };
 
         self
     }
 
+    pub(crate) fn set_my_game_play(
+        mut self,
+        my_game_play_id: i32,
+        is_awaiting_my_chance_reveal: Option<bool>,
+    ) -> Self {
+        self.my_game_play_id = Some(my_game_play_id);
+        self.is_awaiting_my_chance_reveal = is_awaiting_my_chance_reveal;
+
+        self
+    }
+
     fn maybe_set_my_game_play_id(mut self, maybe_game_play: &Option<GamePlay>) -> Self {
         if let Some(GamePlay { id, .. }) = maybe_game_play {
             self.my_game_play_id = Some(*id);
@@ -216,20 +327,27 @@ impl GameResponse {
 
     fn maybe_include_completed_game_data(
         self,
+        game: &Game,
         game_plays: &Vec<GamePlay>,
         chain_currency: &ChainCurrency,
+        viewer: &Viewer,
     ) -> Self {
-        if self.completed_at.is_some() {
-            self.include_game_plays(game_plays)
+        if game.is_completed() {
+            self.include_game_plays(game, game_plays, viewer)
                 .include_revealed_proof_of_chances(game_plays)
                 .include_amount_for_each_winner_usd(chain_currency)
                 .include_amounts_shared_with_winners(game_plays, chain_currency)
         } else {
-            self.include_game_plays(game_plays)
+            self.include_game_plays(game, game_plays, viewer)
         }
     }
-    fn include_game_plays(mut self, game_plays: &Vec<GamePlay>) -> Self {
-        self.game_plays = Some(game_plays.clone());
+    fn include_game_plays(
+        mut self,
+        game: &Game,
+        game_plays: &Vec<GamePlay>,
+        viewer: &Viewer,
+    ) -> Self {
+        self.game_plays = Some(ChanceVisibility::redact(game, game_plays, viewer));
 
         self
     }
@@ -237,10 +355,12 @@ impl GameResponse {
         self.revealed_proof_of_chances = Some(
             game_plays
                 .into_iter()
-                .map(|gp| RevealedProofOfChance {
-                    player_address: gp.player_address.to_owned(),
-                    chance_and_salt: gp.chance_and_salt.clone().unwrap(),
-                    proof_of_chance: gp.proof_of_chance.to_owned(),
+                .filter_map(|gp| {
+                    gp.chance_and_salt.clone().map(|chance_and_salt| RevealedProofOfChance {
+                        player_address: gp.player_address.to_owned(),
+                        chance_and_salt,
+                        proof_of_chance: gp.proof_of_chance.to_owned(),
+                    })
                 })
                 .collect(),
         );
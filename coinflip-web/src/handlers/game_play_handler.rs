@@ -1,59 +1,156 @@
+use ark_web3::chains::ChainId;
+use ark_web_common::auth::AuthenticatedPlayer;
 use ark_web_common::AppState;
 use axum::extract::{Json, Path, State};
+use axum::Extension;
 
-use coinflip::UnsavedGameActivity;
-use http::StatusCode;
-use serde::Deserialize;
+use coinflip::UnsavedSubmittedChance;
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
 
-use crate::handlers;
+use crate::handlers::{self, Error};
+use crate::reveal_signatures::{RevealChance, RevealSignatureError};
 
 use super::GenericMessage;
 
+#[derive(Debug, Serialize)]
+pub struct MyGamePlayResponse {
+    game_play_id: i32,
+    coin_side: i32,
+    proof_of_chance: String,
+    /// The chance submitted to Ark, only ever shown to its owner until the game completes
+    submitted_chance_and_salt: Option<String>,
+    submitted_at: Option<i64>,
+}
+
+pub async fn get_my_game_play(
+    State(app_state): State<AppState>,
+    Extension(authenticated_player): Extension<AuthenticatedPlayer>,
+    Path((game_id, chain_id)): Path<(u64, u64)>,
+) -> Result<Json<MyGamePlayResponse>, handlers::Error> {
+    let repos = app_state.repos;
+
+    let game_play = repos
+        .game_plays
+        .get_game_play(
+            game_id as i64,
+            chain_id as i64,
+            &authenticated_player.address,
+        )
+        .await?
+        .ok_or(Error::not_found("Game play not found"))?;
+
+    let submitted_chance = repos.game_plays.get_submitted_chance(&game_play).await?;
+    let chance_cipher = &app_state.chance_cipher;
+
+    Ok(Json(MyGamePlayResponse {
+        game_play_id: game_play.id,
+        coin_side: game_play.coin_side,
+        proof_of_chance: game_play.proof_of_chance,
+        submitted_chance_and_salt: submitted_chance
+            .as_ref()
+            .and_then(|submitted_chance| submitted_chance.get_chance_and_salt(chance_cipher)),
+        submitted_at: submitted_chance.map(|submitted_chance| submitted_chance.submitted_at),
+    }))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UpdateMyGamePlayParams {
     public_address: String,
     chance_and_salt: String,
+    /// Decimal, unique per player and chain
+    nonce: String,
+    expiry: u64,
+    /// EIP-712 signature over `RevealChance`, see `reveal_signatures`
+    signature: String,
 }
 
+/// Escrows the player's chance with Ark as soon as their play is indexed,
+/// so `reveal_game_play_chances` can reveal without waiting on every player to come back.
 pub async fn update_my_game_play(
     State(app_state): State<AppState>,
+    Extension(authenticated_player): Extension<AuthenticatedPlayer>,
     Path((game_id, chain_id)): Path<(u64, u64)>,
     Json(UpdateMyGamePlayParams {
         public_address,
         chance_and_salt,
+        nonce,
+        expiry,
+        signature,
     }): Json<UpdateMyGamePlayParams>,
 ) -> Result<Json<GenericMessage>, handlers::Error> {
-    let game_id = game_id as i64;
-    let chain_id = chain_id as i64;
+    if !authenticated_player.is(&public_address) {
+        return Err(Error::forbidden("Not your game play"));
+    }
+
+    let repos = &app_state.repos;
 
-    let mut conn = handlers::new_conn(app_state.db_pool).await?;
+    let game = repos
+        .games
+        .get_game(game_id as i64, chain_id as i64)
+        .await?
+        .ok_or(Error::not_found("Game not found"))?;
+    let game_play = repos
+        .game_plays
+        .get_game_play(game.id, game.chain_id, &public_address)
+        .await?
+        .ok_or(Error::not_found("Game play not found"))?;
 
-    let maybe_game = coinflip_repo::get_game(&mut conn, game_id, chain_id).await;
-    let maybe_game_play =
-        coinflip_repo::get_game_play(&mut conn, game_id, chain_id, &public_address).await;
+    if !game.is_awaiting() || game.has_expired(app_state.clock.as_ref()) {
+        return Err(Error::conflict("Game is no longer accepting chances"));
+    }
+
+    let chain_config = app_state
+        .config
+        .get_chain(&ChainId::from(chain_id))
+        .ok_or(Error::validation("Chain not supported"))?;
+    let coinflip_contract = chain_config
+        .get_contract("coinflip")
+        .ok_or(Error::validation("Coinflip not supported on this chain"))?;
+
+    // Parsed once so e.g. "1" and "01" are the same nonce when checked for replays
+    let nonce = U256::from_dec_str(&nonce).map_err(|_| Error::validation("Invalid nonce"))?;
+    let now = app_state.clock.now();
+
+    let reveal_chance = RevealChance {
+        game_id,
+        chain_id,
+        chance_and_salt: chance_and_salt.clone(),
+        nonce,
+        expiry,
+        coinflip_contract_address: coinflip_contract.address.clone(),
+    };
+    reveal_chance
+        .verify(&signature, &public_address, now as u64)
+        .map_err(|err| match err {
+            RevealSignatureError::Malformed => Error::validation(err.get_message()),
+            RevealSignatureError::Expired | RevealSignatureError::Mismatch => {
+                Error::unauthorized(err.get_message())
+            }
+        })?;
+
+    if !game_play.is_chance_and_salt(&chance_and_salt) {
+        return Err(Error::validation("Invalid play proof"));
+    }
 
-    if maybe_game.is_some()
-        && maybe_game.as_ref().unwrap().is_awaiting_revealed_chances()
-        && maybe_game_play.is_some()
+    // Checked before the nonce is used up, so resubmitting doesn't burn a fresh signature
+    if repos.game_plays.get_submitted_chance(&game_play).await?.is_some() {
+        return Err(Error::conflict("Chance already submitted"));
+    }
+
+    if !repos
+        .game_plays
+        .use_reveal_nonce(game.chain_id, &public_address, &nonce.to_string(), now)
+        .await?
     {
-        let game_play = maybe_game_play.unwrap();
-
-        if game_play.is_chance_and_salt(&chance_and_salt) {
-            coinflip_repo::update_game_play_chance_and_salt(&mut conn, &game_play, chance_and_salt)
-                .await;
-
-            let game_activity =
-                UnsavedGameActivity::new_chance_revealed(game_id as u64, chain_id, &public_address);
-            coinflip_repo::create_game_activity(&mut conn, &game_activity).await;
-
-            Ok(Json(GenericMessage::new("game proof publicized")))
-        } else {
-            Err((
-                StatusCode::UNPROCESSABLE_ENTITY,
-                "Invalid play proof".to_owned(),
-            ))
-        }
-    } else {
-        Ok(Json(GenericMessage::new("game proof publicized")))
+        return Err(Error::conflict("Signature already used"));
     }
+
+    let submitted_chance =
+        UnsavedSubmittedChance::new(&game_play, &chance_and_salt, &app_state.chance_cipher, now);
+    if !repos.game_plays.create_submitted_chance(&submitted_chance).await? {
+        return Err(Error::conflict("Chance already submitted"));
+    }
+
+    Ok(Json(GenericMessage::new("game proof publicized")))
 }
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use ark_utils::floats;
+use ark_web_common::AppState;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+
+use ark_web3::chains::get_currency_chain_id;
+use coinflip::GetHouseRevenueReportsParams;
+use serde::Serialize;
+
+use crate::handlers;
+
+#[derive(Debug, Serialize)]
+pub struct HouseRevenueReportResponse {
+    chain_id: i64,
+    period_start: i64,
+    games_count: i64,
+    fees_collected: f64,
+    fees_collected_usd: Option<f64>,
+}
+
+/// Service charges collected from completed games, per chain and per period
+pub async fn get_house_revenue_reports(
+    State(app_state): State<AppState>,
+    Query(params): Query<GetHouseRevenueReportsParams>,
+) -> Result<Json<Vec<HouseRevenueReportResponse>>, handlers::Error> {
+    let reports = app_state.repos.games.get_house_revenue_reports(&params).await?;
+
+    let currency_chain_ids: Vec<_> =
+        reports.iter().map(|report| get_currency_chain_id(report.chain_id)).collect();
+    let chain_currencies = app_state
+        .repos
+        .chain_currencies
+        .get_chain_currencies(&currency_chain_ids)
+        .await?;
+    let chain_currencies_by_chain_id: HashMap<_, _> = chain_currencies
+        .iter()
+        .map(|chain_currency| (chain_currency.chain_id, chain_currency))
+        .collect();
+
+    let reports = reports
+        .into_iter()
+        .map(|report| {
+            let fees_collected_usd = chain_currencies_by_chain_id
+                .get(&get_currency_chain_id(report.chain_id))
+                .map(|chain_currency| {
+                    floats::to_2dp(chain_currency.convert_to_usd(report.fees_collected))
+                });
+
+            HouseRevenueReportResponse {
+                chain_id: report.chain_id,
+                period_start: report.period_start,
+                games_count: report.games_count,
+                fees_collected: report.fees_collected,
+                fees_collected_usd,
+            }
+        })
+        .collect();
+
+    Ok(Json(reports))
+}
@@ -0,0 +1,100 @@
+use ark_web_common::auth::AuthenticatedPlayer;
+use ark_web_common::AppState;
+use axum::extract::{Json, Path, Query, State};
+use axum::Extension;
+
+use coinflip::{GetPlayerActionsParams, PlayerActionKind, DEFAULT_EXPIRING_WITHIN_SECS};
+use serde::{Deserialize, Serialize};
+
+use crate::handlers::{self, Error};
+
+use super::game_handler::{self, GameResponse};
+
+/// Credits are looked back on for this long when no cursor is given
+const DEFAULT_CREDITED_WITHIN_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, Deserialize)]
+pub struct GetPlayerActionsQuery {
+    /// `next_cursor` from the previous response
+    pub cursor: Option<i64>,
+    pub expiring_within_secs: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlayerActionResponse {
+    kind: PlayerActionKind,
+    action_at: i64,
+    #[serde(flatten)]
+    game: GameResponse,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlayerActionsResponse {
+    actions: Vec<PlayerActionResponse>,
+    /// Pass back as `cursor` to only get refunds and wins credited since this response
+    next_cursor: i64,
+}
+
+pub async fn get_player_actions(
+    State(app_state): State<AppState>,
+    Extension(authenticated_player): Extension<AuthenticatedPlayer>,
+    Path(player_address): Path<String>,
+    Query(GetPlayerActionsQuery {
+        cursor,
+        expiring_within_secs,
+    }): Query<GetPlayerActionsQuery>,
+) -> Result<Json<PlayerActionsResponse>, handlers::Error> {
+    if !authenticated_player.is(&player_address) {
+        return Err(Error::forbidden("Not your actions"));
+    }
+
+    let now = app_state.clock.now();
+    let credited_since = cursor.unwrap_or(now - DEFAULT_CREDITED_WITHIN_SECS);
+
+    let player_actions = app_state
+        .repos
+        .games
+        .get_player_actions(&GetPlayerActionsParams {
+            player_address,
+            now,
+            expiring_within_secs: expiring_within_secs.unwrap_or(DEFAULT_EXPIRING_WITHIN_SECS),
+            credited_since,
+        })
+        .await?;
+
+    let next_cursor = player_actions
+        .iter()
+        .filter(|player_action| player_action.is_credit())
+        .map(|player_action| player_action.action_at)
+        .max()
+        .unwrap_or(credited_since);
+
+    let games = player_actions.iter().map(|player_action| player_action.game.clone()).collect();
+    let game_responses =
+        game_handler::get_game_responses(&app_state.repos, &games, app_state.clock.as_ref())
+            .await?;
+
+    let actions = player_actions
+        .iter()
+        .zip(game_responses)
+        .map(|(player_action, game_response)| {
+            let kind = player_action.get_kind();
+            let is_awaiting_my_chance_reveal = match kind {
+                PlayerActionKind::MustReveal => Some(true),
+                _ => None,
+            };
+
+            PlayerActionResponse {
+                kind,
+                action_at: player_action.action_at,
+                game: game_response
+                    .set_my_game_play(player_action.game_play_id, is_awaiting_my_chance_reveal),
+            }
+        })
+        .collect();
+
+    Ok(Json(PlayerActionsResponse {
+        actions,
+        next_cursor,
+    }))
+}
@@ -0,0 +1,67 @@
+use ark_web_common::auth::AuthenticatedPlayer;
+use ark_web_common::AppState;
+use axum::extract::{Json, State};
+use axum::Extension;
+
+use coinflip::{PendingReveal, RevealReminder};
+use serde::Serialize;
+
+use crate::handlers;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RevealReminderResponse {
+    #[serde(flatten)]
+    reveal_reminder: RevealReminder,
+    blocked_for_secs: i64,
+}
+
+/// Admin listing of plays currently blocking stuck games
+pub async fn get_reveal_reminders(
+    State(app_state): State<AppState>,
+) -> Result<Json<Vec<RevealReminderResponse>>, handlers::Error> {
+    let now = app_state.clock.now();
+
+    let reveal_reminders =
+        app_state.repos.reveal_reminders.get_unresolved_reveal_reminders().await?;
+
+    Ok(Json(
+        reveal_reminders
+            .into_iter()
+            .map(|reveal_reminder| RevealReminderResponse {
+                blocked_for_secs: (now - reveal_reminder.blocked_since).max(0),
+                reveal_reminder,
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PendingRevealResponse {
+    #[serde(flatten)]
+    pending_reveal: PendingReveal,
+    blocked_for_secs: i64,
+}
+
+/// Games waiting on the signed in player's reveal, however briefly
+pub async fn get_my_pending_reveals(
+    State(app_state): State<AppState>,
+    Extension(authenticated_player): Extension<AuthenticatedPlayer>,
+) -> Result<Json<Vec<PendingRevealResponse>>, handlers::Error> {
+    let now = app_state.clock.now();
+
+    let pending_reveals = app_state
+        .repos
+        .reveal_reminders
+        .get_pending_reveals(now, now, Some(&authenticated_player.address))
+        .await?;
+
+    Ok(Json(
+        pending_reveals
+            .into_iter()
+            .map(|pending_reveal| PendingRevealResponse {
+                blocked_for_secs: pending_reveal.get_blocked_for_secs(now),
+                pending_reveal,
+            })
+            .collect(),
+    ))
+}
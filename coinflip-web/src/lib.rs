@@ -1,5 +1,6 @@
 pub mod app_router;
 pub mod app_workers;
 pub mod handlers;
+pub mod reveal_signatures;
 
 pub use app_router::AppRouter;
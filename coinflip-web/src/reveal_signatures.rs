@@ -0,0 +1,107 @@
+use std::str::FromStr;
+
+use coinflip::GamePlay;
+use ethers::abi::{encode, Token};
+use ethers::types::transaction::eip712::{EIP712Domain, Eip712, Eip712Error};
+use ethers::types::{Address, Signature, H256, U256};
+use ethers::utils::keccak256;
+
+const DOMAIN_NAME: &str = "Ark Coinflip";
+const DOMAIN_VERSION: &str = "1";
+
+/// Signatures further in the future than this are rejected,
+/// so a leaked signature cannot be held on to indefinitely.
+pub const MAX_EXPIRY_WINDOW_SECS: u64 = 60 * 60;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum RevealSignatureError {
+    Malformed,
+    Expired,
+    Mismatch,
+}
+
+impl RevealSignatureError {
+    pub fn get_message(&self) -> &'static str {
+        match self {
+            RevealSignatureError::Malformed => "Malformed signature",
+            RevealSignatureError::Expired => "Signature expired",
+            RevealSignatureError::Mismatch => "Signature does not match player",
+        }
+    }
+}
+
+/// EIP-712 typed data a player signs to reveal their chance:
+/// `RevealChance(uint256 gameId,uint256 chainId,bytes chanceAndSalt,uint256 nonce,uint256 expiry)`
+/// signed under the Coinflip contract's domain for the chain.
+#[derive(Clone, Debug)]
+pub struct RevealChance {
+    pub game_id: u64,
+    pub chain_id: u64,
+    pub chance_and_salt: String,
+    pub nonce: U256,
+    pub expiry: u64,
+    /// The domain's verifying contract, not part of the signed struct
+    pub coinflip_contract_address: String,
+}
+
+impl RevealChance {
+    pub fn verify(
+        &self,
+        signature: &str,
+        player_address: &str,
+        now: u64,
+    ) -> Result<(), RevealSignatureError> {
+        if self.expiry < now || self.expiry > now + MAX_EXPIRY_WINDOW_SECS {
+            return Err(RevealSignatureError::Expired);
+        }
+
+        let signature =
+            Signature::from_str(signature).map_err(|_| RevealSignatureError::Malformed)?;
+        let player_address =
+            Address::from_str(player_address).map_err(|_| RevealSignatureError::Malformed)?;
+        let hash = self.encode_eip712().map_err(|_| RevealSignatureError::Malformed)?;
+
+        signature
+            .verify(H256::from(hash), player_address)
+            .map_err(|_| RevealSignatureError::Mismatch)
+    }
+}
+
+impl Eip712 for RevealChance {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        let verifying_contract = self
+            .coinflip_contract_address
+            .parse()
+            .map_err(|_| Eip712Error::Message("Invalid Coinflip contract address".to_string()))?;
+
+        Ok(EIP712Domain {
+            name: Some(DOMAIN_NAME.to_string()),
+            version: Some(DOMAIN_VERSION.to_string()),
+            chain_id: Some(U256::from(self.chain_id)),
+            verifying_contract: Some(verifying_contract),
+            salt: None,
+        })
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(keccak256(
+            "RevealChance(uint256 gameId,uint256 chainId,bytes chanceAndSalt,uint256 nonce,uint256 expiry)",
+        ))
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let chance_and_salt = GamePlay::parse_chance_and_salt_bytes(&self.chance_and_salt)
+            .ok_or_else(|| Eip712Error::Message("Invalid chance and salt".to_string()))?;
+
+        Ok(keccak256(encode(&[
+            Token::FixedBytes(Self::type_hash()?.to_vec()),
+            Token::Uint(U256::from(self.game_id)),
+            Token::Uint(U256::from(self.chain_id)),
+            Token::FixedBytes(keccak256(chance_and_salt).to_vec()),
+            Token::Uint(self.nonce),
+            Token::Uint(U256::from(self.expiry)),
+        ])))
+    }
+}
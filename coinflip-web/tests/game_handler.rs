@@ -0,0 +1,206 @@
+mod configs;
+mod in_memory_app;
+
+use ark::total_paid_out_report::UnsavedTotalPaidOutReport;
+use ark_web3::chains::ChainId;
+use ark_web_common::auth::AuthenticatedPlayer;
+use ark_web_common::Error;
+use axum::extract::{Path, Query, State};
+use axum::response::IntoResponse;
+use axum::Extension;
+use coinflip::{Game, GamePlay, GameRepo, GameSort, GameStatus, GetGamesParams};
+use coinflip_web::handlers::game_handler;
+use http::StatusCode;
+use in_memory_app::{InMemoryApp, NOW};
+use serde_json::Value;
+
+const CHAIN_ID: i64 = ChainId::Sepolia as i64;
+const ALICE: &str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+const ONE_ETHER: &str = "1000000000000000000";
+
+impl InMemoryApp {
+    async fn get_game(&self, id: i64, player_address: Option<&str>) -> Result<Value, Error> {
+        let maybe_authenticated_player = player_address.map(|address| {
+            Extension(AuthenticatedPlayer {
+                address: address.to_string(),
+            })
+        });
+
+        game_handler::get_game(
+            State(self.app_state.clone()),
+            maybe_authenticated_player,
+            Path((id as u64, CHAIN_ID as u64)),
+        )
+        .await
+        .map(|game_response| serde_json::to_value(game_response.0).unwrap())
+    }
+}
+
+fn new_game(id: i64, status: GameStatus, expiry_timestamp: i64) -> Game {
+    Game {
+        id,
+        chain_id: CHAIN_ID,
+        number_of_players: 2,
+        expiry_timestamp,
+        creator_address: ALICE.to_string(),
+        block_number: id,
+        wager: ONE_ETHER.to_string(),
+        play_count: 1,
+        unavailable_coin_side: None,
+        outcome: None,
+        amount_for_each_winner: None,
+        completed_at: None,
+        refunded_amount_per_player: None,
+        refunded_at: None,
+        chain_agnostic_index: id,
+        created_at: NOW - 60,
+        status,
+        head_play_count: 1,
+        tail_play_count: 0,
+        all_seats_filled_at: None,
+    }
+}
+
+fn new_game_play(game_id: i64, player_address: &str) -> GamePlay {
+    GamePlay {
+        id: 0,
+        game_id,
+        chain_id: CHAIN_ID,
+        coin_side: 0,
+        player_address: player_address.to_string(),
+        proof_of_chance: "0x".to_string(),
+        chance_and_salt: None,
+        status: "pending".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn returns_not_found_for_unknown_games() {
+    let app = InMemoryApp::new().await;
+
+    let err = app.get_game(1, None).await.unwrap_err();
+    assert_eq!(err.get_status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn reports_a_missing_chain_price_as_upstream_unavailable() {
+    let app = InMemoryApp::with_chain_currencies(vec![]).await;
+    app.coinflip_repo
+        .insert_game(new_game(1, GameStatus::AwaitingPlayers, NOW + 60));
+
+    let response = app.get_game(1, None).await.unwrap_err().into_response();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["code"], "upstream_unavailable");
+    assert_eq!(body["message"], "No USD price for chain yet");
+}
+
+#[tokio::test]
+async fn prices_a_game_and_shows_players_their_play() {
+    let app = InMemoryApp::new().await;
+    app.coinflip_repo
+        .insert_game(new_game(1, GameStatus::AwaitingPlayers, NOW + 60));
+    app.coinflip_repo.insert_game_play(new_game_play(1, ALICE));
+
+    let game = app.get_game(1, None).await.unwrap();
+    assert_eq!(game["wager"], 1.0);
+    assert_eq!(game["wager_usd"], 2000.0);
+    assert_eq!(game["my_game_play_id"], Value::Null);
+
+    let game = app.get_game(1, Some(ALICE)).await.unwrap();
+    assert_eq!(game["my_game_play_id"], 0);
+}
+
+#[tokio::test]
+async fn reports_awaiting_games_past_their_expiry_as_expired() {
+    let app = InMemoryApp::new().await;
+    app.coinflip_repo
+        .insert_game(new_game(1, GameStatus::AwaitingPlayers, NOW + 60));
+
+    let game = app.get_game(1, None).await.unwrap();
+    assert_eq!(game["status"], "awaiting_players");
+
+    app.clock.advance(60);
+
+    let game = app.get_game(1, None).await.unwrap();
+    assert_eq!(game["status"], "expired_pending_refund");
+}
+
+#[tokio::test]
+async fn totals_games_and_payouts_alongside_a_page_of_games() {
+    let app = InMemoryApp::new().await;
+    app.coinflip_repo
+        .insert_game(new_game(1, GameStatus::AwaitingPlayers, NOW + 60));
+    app.coinflip_repo.insert_game(new_game(2, GameStatus::Completed, NOW + 60));
+    app.ark_repo.insert_total_paid_out_report(&UnsavedTotalPaidOutReport::new(1.5));
+    app.ark_repo.insert_total_paid_out_report(&UnsavedTotalPaidOutReport::new(3.25));
+
+    let games = game_handler::get_games(State(app.app_state.clone()), Query(GetGamesParams::new()))
+        .await
+        .unwrap();
+    let games = serde_json::to_value(games.0).unwrap();
+
+    assert_eq!(games["games"].as_array().unwrap().len(), 2);
+    assert_eq!(games["total_games_count"], 2);
+    assert_eq!(games["total_completed_games_count"], 1);
+    assert_eq!(games["total_paid_out_amount"], 3.25);
+}
+
+#[tokio::test]
+async fn rejects_an_unknown_available_coin_side() {
+    let app = InMemoryApp::new().await;
+    let params = GetGamesParams::new().with_available_coin_side(2);
+
+    let err = game_handler::get_games(State(app.app_state.clone()), Query(params))
+        .await
+        .unwrap_err();
+    assert_eq!(err.get_status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn filters_and_sorts_games_in_memory() {
+    let app = InMemoryApp::new().await;
+    app.coinflip_repo
+        .insert_game(new_game(1, GameStatus::AwaitingPlayers, NOW + 300));
+    app.coinflip_repo
+        .insert_game(new_game(2, GameStatus::AwaitingPlayers, NOW + 60));
+    app.coinflip_repo.insert_game(new_game(3, GameStatus::Completed, NOW + 600));
+    app.coinflip_repo
+        .insert_game(new_game(4, GameStatus::AwaitingPlayers, NOW + 900));
+
+    let get_game_ids = |games: Vec<Game>| games.iter().map(|game| game.id).collect::<Vec<_>>();
+    let games_repo = &app.app_state.repos.games;
+
+    let params = GetGamesParams::new().with_status(GameStatus::AwaitingPlayers);
+    let games = games_repo.get_games(&params, app.clock.as_ref()).await.unwrap();
+    assert_eq!(get_game_ids(games), vec![4, 2, 1]);
+
+    let params = params.sorted_by(GameSort::SoonestExpiry).expiring_between(None, Some(300));
+    let games = games_repo.get_games(&params, app.clock.as_ref()).await.unwrap();
+    assert_eq!(get_game_ids(games), vec![2, 1]);
+
+    let params = GetGamesParams::new().with_wager_usd_between(Some(2500.0), None);
+    let games = games_repo.get_games(&params, app.clock.as_ref()).await.unwrap();
+    assert!(games.is_empty());
+
+    app.clock.advance(300);
+
+    let params = GetGamesParams::new().expired();
+    let games = games_repo.get_games(&params, app.clock.as_ref()).await.unwrap();
+    assert_eq!(get_game_ids(games), vec![2, 1]);
+
+    let params = GetGamesParams::new().with_status(GameStatus::AwaitingPlayers);
+    let games = games_repo.get_games(&params, app.clock.as_ref()).await.unwrap();
+    assert_eq!(get_game_ids(games), vec![4]);
+
+    let params = GetGamesParams::new().with_status(GameStatus::Completed);
+    let games = games_repo.get_games(&params, app.clock.as_ref()).await.unwrap();
+    assert_eq!(get_game_ids(games), vec![3, 2, 1]);
+
+    assert_eq!(
+        app.coinflip_repo.get_total_completed_games_count().await.unwrap(),
+        1
+    );
+}
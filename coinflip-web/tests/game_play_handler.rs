@@ -0,0 +1,151 @@
+mod configs;
+mod in_memory_app;
+
+use ark_web3::chains::ChainId;
+use ark_web_common::auth::AuthenticatedPlayer;
+use ark_web_common::Error;
+use axum::extract::{Json, Path, State};
+use axum::Extension;
+use coinflip::{Game, GamePlay, GamePlayRepo, GameStatus};
+use coinflip_web::handlers::game_play_handler;
+use coinflip_web::reveal_signatures::RevealChance;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::U256;
+use http::StatusCode;
+use in_memory_app::{InMemoryApp, NOW};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+const CHAIN_ID: i64 = ChainId::Local as i64;
+const PLAYER_PRIVATE_KEY: &str =
+    "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+fn get_chance_and_salt(game_id: i64) -> String {
+    format!("0x{}", format!("{:02x}", game_id).repeat(64))
+}
+
+fn get_player() -> LocalWallet {
+    PLAYER_PRIVATE_KEY.parse().unwrap()
+}
+
+fn get_player_address() -> String {
+    format!("{:?}", get_player().address())
+}
+
+/// A game awaiting a second player, with the player's play on heads
+fn insert_game_with_play(app: &InMemoryApp, game_id: i64) {
+    app.coinflip_repo.insert_game(Game {
+        id: game_id,
+        chain_id: CHAIN_ID,
+        number_of_players: 2,
+        expiry_timestamp: NOW + 600,
+        creator_address: get_player_address(),
+        block_number: game_id,
+        wager: "1000000000000000000".to_string(),
+        play_count: 1,
+        unavailable_coin_side: None,
+        outcome: None,
+        amount_for_each_winner: None,
+        completed_at: None,
+        refunded_amount_per_player: None,
+        refunded_at: None,
+        chain_agnostic_index: game_id,
+        created_at: NOW - 60,
+        status: GameStatus::AwaitingPlayers,
+        head_play_count: 1,
+        tail_play_count: 0,
+        all_seats_filled_at: None,
+    });
+
+    let chance_and_salt = GamePlay::get_chance_and_salt_bytes(&get_chance_and_salt(game_id));
+    app.coinflip_repo.insert_game_play(GamePlay {
+        id: 1,
+        game_id,
+        chain_id: CHAIN_ID,
+        coin_side: 0,
+        player_address: get_player_address(),
+        proof_of_chance: format!("{:x}", Sha256::digest(chance_and_salt)),
+        chance_and_salt: None,
+        status: "pending".to_string(),
+    });
+}
+
+async fn submit_chance(app: &InMemoryApp, game_id: i64, nonce: u64) -> Result<(), Error> {
+    let reveal_chance = RevealChance {
+        game_id: game_id as u64,
+        chain_id: CHAIN_ID as u64,
+        chance_and_salt: get_chance_and_salt(game_id),
+        nonce: U256::from(nonce),
+        expiry: NOW as u64 + 60,
+        coinflip_contract_address: configs::COINFLIP_CONTRACT_ADDRESS.to_string(),
+    };
+    let signature = get_player().sign_typed_data(&reveal_chance).await.unwrap();
+
+    let params = json!({
+        "public_address": get_player_address(),
+        "chance_and_salt": reveal_chance.chance_and_salt,
+        "nonce": nonce.to_string(),
+        "expiry": reveal_chance.expiry,
+        "signature": signature.to_string(),
+    });
+
+    game_play_handler::update_my_game_play(
+        State(app.app_state.clone()),
+        Extension(AuthenticatedPlayer {
+            address: get_player_address(),
+        }),
+        Path((game_id as u64, CHAIN_ID as u64)),
+        Json(serde_json::from_value(params).unwrap()),
+    )
+    .await
+    .map(|_| ())
+}
+
+#[tokio::test]
+async fn escrows_a_signed_chance() {
+    let app = InMemoryApp::new().await;
+    insert_game_with_play(&app, 1);
+
+    submit_chance(&app, 1, 1).await.unwrap();
+
+    let game_play = app
+        .coinflip_repo
+        .get_game_play(1, CHAIN_ID, &get_player_address())
+        .await
+        .unwrap()
+        .unwrap();
+    let submitted_chance =
+        app.coinflip_repo.get_submitted_chance(&game_play).await.unwrap().unwrap();
+    assert_eq!(
+        submitted_chance.get_chance_and_salt(&app.app_state.chance_cipher),
+        Some(get_chance_and_salt(1))
+    );
+}
+
+#[tokio::test]
+async fn rejects_a_reused_nonce() {
+    let app = InMemoryApp::new().await;
+    insert_game_with_play(&app, 1);
+    insert_game_with_play(&app, 2);
+
+    submit_chance(&app, 1, 1).await.unwrap();
+
+    let err = submit_chance(&app, 2, 1).await.unwrap_err();
+    assert_eq!(err.get_status_code(), StatusCode::CONFLICT);
+    assert_eq!(err.get_message(), "Signature already used");
+}
+
+#[tokio::test]
+async fn rejects_a_resubmitted_chance_without_using_up_its_nonce() {
+    let app = InMemoryApp::new().await;
+    insert_game_with_play(&app, 1);
+    insert_game_with_play(&app, 2);
+
+    submit_chance(&app, 1, 1).await.unwrap();
+
+    let err = submit_chance(&app, 1, 2).await.unwrap_err();
+    assert_eq!(err.get_status_code(), StatusCode::CONFLICT);
+    assert_eq!(err.get_message(), "Chance already submitted");
+
+    submit_chance(&app, 2, 2).await.unwrap();
+}
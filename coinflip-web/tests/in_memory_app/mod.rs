@@ -0,0 +1,64 @@
+//! An `AppState` backed by in-memory repos, for calling handlers directly
+
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use ark_repo::{ChainCurrencyRepo, InMemoryArkRepo};
+use ark_utils::clocks::TestClock;
+use ark_web3::chains::{ChainId, UnsavedChainCurrency};
+use ark_web_common::{AppState, Repos};
+use chaindexing::KeepNodeActiveRequest;
+use coinflip_repo::InMemoryCoinflipRepo;
+use diesel_async::pooled_connection::AsyncDieselConnectionManager;
+
+use crate::configs;
+
+pub const NOW: i64 = 1_700_000_000;
+
+pub struct InMemoryApp {
+    pub app_state: AppState,
+    pub ark_repo: Arc<InMemoryArkRepo>,
+    pub coinflip_repo: Arc<InMemoryCoinflipRepo>,
+    pub clock: Arc<TestClock>,
+}
+
+impl InMemoryApp {
+    pub async fn new() -> Self {
+        Self::with_chain_currencies(vec![UnsavedChainCurrency::new(
+            ChainId::Sepolia,
+            "SepoliaETH",
+            2000.0,
+        )])
+        .await
+    }
+
+    pub async fn with_chain_currencies(chain_currencies: Vec<UnsavedChainCurrency>) -> Self {
+        let ark_repo = Arc::new(InMemoryArkRepo::new());
+        ark_repo.create_or_update_chain_currencies(&chain_currencies).await.unwrap();
+
+        let coinflip_repo = Arc::new(InMemoryCoinflipRepo::new(ark_repo.clone()));
+        let clock = Arc::new(TestClock::new(NOW));
+
+        // Never connected to, since the handlers under test only go through the repos
+        let db_pool = Arc::new(
+            bb8::Pool::builder()
+                .build_unchecked(AsyncDieselConnectionManager::new("postgres://unused")),
+        );
+
+        let app_state = AppState::new(
+            db_pool,
+            &KeepNodeActiveRequest::new(60_000),
+            clock.clone(),
+            Repos::new(ark_repo.clone(), coinflip_repo.clone()),
+            Arc::new(configs::get_local("http://127.0.0.1:8545", None)),
+        );
+
+        Self {
+            app_state,
+            ark_repo,
+            coinflip_repo,
+            clock,
+        }
+    }
+}
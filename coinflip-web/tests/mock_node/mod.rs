@@ -0,0 +1,54 @@
+#![allow(dead_code)]
+
+use ark::configs::ArkConfig;
+use ark_web3::chains::{ChainConfig, ChainId};
+use ethers::abi::parse_abi;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, U256};
+use mock_json_rpc::{MockGasOracle, MockJsonRpc};
+
+use crate::configs::{self, COINFLIP_CONTRACT_ADDRESS, PRIVATE_KEY};
+
+pub const CHAIN_ID: ChainId = ChainId::Local;
+
+pub const SAFE_GAS_PRICE_GWEI: u64 = 20;
+pub const FAST_GAS_PRICE_GWEI: u64 = 35;
+
+const COINFLIP_ABI: &[&str] = &[
+    "function revealChancesAndCreditWinners(uint gameID, bytes[] chanceAndSalts) external",
+    "function refundExpiredGamePlayersForGames(uint[] gameIDs) external",
+];
+
+/// Starts a mock node and a local config pointing the workers at it
+pub async fn start() -> (MockJsonRpc, ArkConfig) {
+    let mock_json_rpc = MockJsonRpc::start(CHAIN_ID as u64, parse_abi(COINFLIP_ABI).unwrap()).await;
+
+    mock_json_rpc.set_gas_oracle(MockGasOracle {
+        last_block: 1,
+        safe_gas_price: SAFE_GAS_PRICE_GWEI as f64,
+        fast_gas_price: FAST_GAS_PRICE_GWEI as f64,
+    });
+
+    let config = configs::get_local(
+        &mock_json_rpc.url(),
+        Some(mock_json_rpc.gas_oracle_url().as_str()),
+    );
+
+    (mock_json_rpc, config)
+}
+
+pub fn get_chain_config(config: &ArkConfig) -> &ChainConfig {
+    config.get_chain(&CHAIN_ID).unwrap()
+}
+
+pub fn get_signer_address() -> Address {
+    PRIVATE_KEY.parse::<LocalWallet>().unwrap().address()
+}
+
+pub fn get_coinflip_contract_address() -> Address {
+    COINFLIP_CONTRACT_ADDRESS.parse().unwrap()
+}
+
+pub fn gwei(amount: u64) -> U256 {
+    U256::from(amount) * U256::exp10(9)
+}
@@ -0,0 +1,48 @@
+mod configs;
+mod mock_node;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ark_utils::clocks::SystemClock;
+
+use coinflip_web::app_workers::caching::RecentCache;
+use coinflip_web::app_workers::refund_expired_game_players::refund_expired_game_players_for_all_games;
+use ethers::abi::Token;
+use mock_node::*;
+
+#[tokio::test]
+async fn refunds_all_expired_games_of_a_chain_in_one_transaction() {
+    let (mock_json_rpc, config) = mock_node::start().await;
+    let game_ids_by_chain_id = HashMap::from([(CHAIN_ID as i64, vec![1, 2, 5])]);
+
+    refund_expired_game_players_for_all_games(
+        game_ids_by_chain_id,
+        &config,
+        &mut RecentCache::new(60, Arc::new(SystemClock)),
+    )
+    .await
+    .unwrap();
+
+    let sent_transactions = mock_json_rpc.get_sent_transactions();
+    assert_eq!(sent_transactions.len(), 1);
+
+    let sent_transaction = &sent_transactions[0];
+    assert_eq!(sent_transaction.from, get_signer_address());
+    assert_eq!(sent_transaction.to, Some(get_coinflip_contract_address()));
+    assert_eq!(sent_transaction.gas_price, Some(gwei(SAFE_GAS_PRICE_GWEI)));
+    assert_eq!(
+        sent_transaction.get_function_name(),
+        Some("refundExpiredGamePlayersForGames")
+    );
+
+    let calls = mock_json_rpc.get_calls("refundExpiredGamePlayersForGames");
+    assert_eq!(
+        calls[0].params,
+        vec![Token::Array(vec![
+            Token::Uint(1.into()),
+            Token::Uint(2.into()),
+            Token::Uint(5.into()),
+        ])]
+    );
+}
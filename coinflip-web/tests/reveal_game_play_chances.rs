@@ -0,0 +1,211 @@
+mod configs;
+mod mock_node;
+
+use std::sync::Arc;
+
+use ark_repo::InMemoryArkRepo;
+use ark_utils::clocks::TestClock;
+use ark_web_common::workers::AppWorker;
+use ark_web_common::Repos;
+use chaindexing::KeepNodeActiveRequest;
+use coinflip::{ChanceCipher, Game, GamePlay, GameStatus, UnsavedSubmittedChance};
+use coinflip_repo::InMemoryCoinflipRepo;
+use coinflip_web::app_workers::caching::RecentCache;
+use coinflip_web::app_workers::reveal_game_play_chances::{
+    reveal_chances_and_credit_winners, RevealGamePlayChances,
+};
+use ethers::abi::Token;
+use ethers::types::{Bytes, U256};
+use mock_json_rpc::{MockGasOracle, MockResponse};
+use mock_node::*;
+
+const GAME_ID: u64 = 3;
+const GAS_INFO_STALE_AFTER_SECS: u64 = 60;
+const NOW: i64 = 1_700_000_000;
+const PLAYERS: [&str; 2] = [
+    "0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+    "0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc",
+];
+
+fn get_chance_and_salts() -> Vec<Bytes> {
+    vec![vec![1; 64].into(), vec![2; 64].into()]
+}
+
+#[tokio::test]
+async fn reveals_chances_and_caches_gas_info_until_stale() {
+    let (mock_json_rpc, config) = mock_node::start().await;
+    mock_json_rpc.set_transaction_count(7);
+    let clock = Arc::new(TestClock::new(1_700_000_000));
+    let mut cached_gas_infos = RecentCache::new(GAS_INFO_STALE_AFTER_SECS, clock.clone());
+
+    reveal_chances_and_credit_winners(
+        GAME_ID,
+        get_chain_config(&config),
+        &get_chance_and_salts(),
+        &mut cached_gas_infos,
+    )
+    .await
+    .unwrap();
+
+    let sent_transactions = mock_json_rpc.get_sent_transactions();
+    assert_eq!(sent_transactions.len(), 1);
+
+    let sent_transaction = &sent_transactions[0];
+    assert_eq!(sent_transaction.from, get_signer_address());
+    assert_eq!(sent_transaction.to, Some(get_coinflip_contract_address()));
+    assert_eq!(sent_transaction.chain_id, Some(CHAIN_ID as u64));
+    assert_eq!(sent_transaction.nonce, Some(U256::from(7)));
+    assert_eq!(sent_transaction.gas_price, Some(gwei(FAST_GAS_PRICE_GWEI)));
+
+    let calls = mock_json_rpc.get_calls("revealChancesAndCreditWinners");
+    assert_eq!(
+        calls[0].params,
+        vec![
+            Token::Uint(GAME_ID.into()),
+            Token::Array(vec![Token::Bytes(vec![1; 64]), Token::Bytes(vec![2; 64])]),
+        ]
+    );
+
+    // Gas prices moving within the cache window shouldn't be picked up
+    mock_json_rpc.set_gas_oracle(MockGasOracle {
+        last_block: 2,
+        safe_gas_price: 80.0,
+        fast_gas_price: 90.0,
+    });
+
+    reveal_chances_and_credit_winners(
+        GAME_ID + 1,
+        get_chain_config(&config),
+        &get_chance_and_salts(),
+        &mut cached_gas_infos,
+    )
+    .await
+    .unwrap();
+
+    let sent_transactions = mock_json_rpc.get_sent_transactions();
+    assert_eq!(sent_transactions.len(), 2);
+    assert_eq!(sent_transactions[1].nonce, Some(U256::from(8)));
+    assert_eq!(
+        sent_transactions[1].gas_price,
+        Some(gwei(FAST_GAS_PRICE_GWEI))
+    );
+
+    clock.advance(GAS_INFO_STALE_AFTER_SECS as i64);
+
+    reveal_chances_and_credit_winners(
+        GAME_ID + 2,
+        get_chain_config(&config),
+        &get_chance_and_salts(),
+        &mut cached_gas_infos,
+    )
+    .await
+    .unwrap();
+
+    let sent_transactions = mock_json_rpc.get_sent_transactions();
+    assert_eq!(sent_transactions[2].gas_price, Some(gwei(90)));
+}
+
+#[tokio::test]
+async fn reports_rejected_reveals() {
+    let (mock_json_rpc, config) = mock_node::start().await;
+    mock_json_rpc.respond_once(
+        "eth_sendRawTransaction",
+        MockResponse::error("replacement transaction underpriced"),
+    );
+
+    let result = reveal_chances_and_credit_winners(
+        GAME_ID,
+        get_chain_config(&config),
+        &get_chance_and_salts(),
+        &mut RecentCache::new(
+            GAS_INFO_STALE_AFTER_SECS,
+            Arc::new(TestClock::new(1_700_000_000)),
+        ),
+    )
+    .await;
+
+    assert!(result.is_err());
+    assert!(mock_json_rpc.get_sent_transactions().is_empty());
+}
+
+#[tokio::test]
+async fn does_not_resend_reveals_awaiting_confirmation() {
+    let (mock_json_rpc, config) = mock_node::start().await;
+    let clock = Arc::new(TestClock::new(NOW));
+    let repos =
+        new_repos_with_a_filled_game(&ChanceCipher::new(&config.chance_encryption_key)).await;
+
+    let mut worker = RevealGamePlayChances::new(
+        repos,
+        KeepNodeActiveRequest::new(60_000),
+        clock.clone(),
+        Arc::new(config),
+    );
+
+    worker.run().await.unwrap();
+    worker.run().await.unwrap();
+    assert_eq!(mock_json_rpc.get_sent_transactions().len(), 1);
+
+    // Still unconfirmed after the timeout, so it is resent
+    clock.advance(5 * 60);
+
+    worker.run().await.unwrap();
+    assert_eq!(mock_json_rpc.get_sent_transactions().len(), 2);
+}
+
+async fn new_repos_with_a_filled_game(chance_cipher: &ChanceCipher) -> Repos {
+    let ark_repo = Arc::new(InMemoryArkRepo::new());
+    let coinflip_repo = Arc::new(InMemoryCoinflipRepo::new(ark_repo.clone()));
+    let repos = Repos::new(ark_repo, coinflip_repo.clone());
+
+    coinflip_repo.insert_game(Game {
+        id: GAME_ID as i64,
+        chain_id: CHAIN_ID as i64,
+        number_of_players: 2,
+        expiry_timestamp: NOW + 60 * 60,
+        creator_address: PLAYERS[0].to_string(),
+        block_number: 1,
+        wager: "1000000000000000000".to_string(),
+        play_count: 2,
+        unavailable_coin_side: None,
+        outcome: None,
+        amount_for_each_winner: None,
+        completed_at: None,
+        refunded_amount_per_player: None,
+        refunded_at: None,
+        chain_agnostic_index: 1,
+        created_at: NOW - 60,
+        status: GameStatus::AwaitingRevealedChances,
+        head_play_count: 1,
+        tail_play_count: 1,
+        all_seats_filled_at: Some(NOW - 30),
+    });
+
+    for (index, player_address) in PLAYERS.iter().enumerate() {
+        let game_play = GamePlay {
+            id: index as i32 + 1,
+            game_id: GAME_ID as i64,
+            chain_id: CHAIN_ID as i64,
+            coin_side: index as i32,
+            player_address: player_address.to_string(),
+            proof_of_chance: "0x".to_string(),
+            chance_and_salt: None,
+            status: "pending".to_string(),
+        };
+        let chance_and_salt = format!("{:02x}", index + 1).repeat(64);
+
+        repos
+            .game_plays
+            .create_submitted_chance(&UnsavedSubmittedChance::new(
+                &game_play,
+                &chance_and_salt,
+                chance_cipher,
+                NOW - 30,
+            ))
+            .await
+            .unwrap();
+        coinflip_repo.insert_game_play(game_play);
+    }
+
+    repos
+}
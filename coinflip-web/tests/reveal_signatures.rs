@@ -0,0 +1,97 @@
+use coinflip_web::reveal_signatures::{RevealChance, RevealSignatureError, MAX_EXPIRY_WINDOW_SECS};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::U256;
+
+const NOW: u64 = 1_700_000_000;
+const PLAYER_PRIVATE_KEY: &str =
+    "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+const OTHER_PRIVATE_KEY: &str =
+    "0x5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a";
+
+fn new_reveal_chance(expiry: u64) -> RevealChance {
+    RevealChance {
+        game_id: 3,
+        chain_id: 31337,
+        chance_and_salt: format!("0x{}", "01".repeat(64)),
+        nonce: U256::from(1),
+        expiry,
+        coinflip_contract_address: "0xe7f1725E7734CE288F8367e1Bb143E90bb3F0512".to_string(),
+    }
+}
+
+async fn sign(private_key: &str, reveal_chance: &RevealChance) -> String {
+    let wallet: LocalWallet = private_key.parse().unwrap();
+
+    wallet.sign_typed_data(reveal_chance).await.unwrap().to_string()
+}
+
+fn get_address(private_key: &str) -> String {
+    format!(
+        "{:?}",
+        private_key.parse::<LocalWallet>().unwrap().address()
+    )
+}
+
+#[tokio::test]
+async fn verifies_the_players_signature() {
+    let reveal_chance = new_reveal_chance(NOW + 60);
+    let signature = sign(PLAYER_PRIVATE_KEY, &reveal_chance).await;
+
+    assert_eq!(
+        reveal_chance.verify(&signature, &get_address(PLAYER_PRIVATE_KEY), NOW),
+        Ok(())
+    );
+}
+
+#[tokio::test]
+async fn rejects_signatures_past_or_too_far_from_their_expiry() {
+    let reveal_chance = new_reveal_chance(NOW - 1);
+    let signature = sign(PLAYER_PRIVATE_KEY, &reveal_chance).await;
+    assert_eq!(
+        reveal_chance.verify(&signature, &get_address(PLAYER_PRIVATE_KEY), NOW),
+        Err(RevealSignatureError::Expired)
+    );
+
+    let reveal_chance = new_reveal_chance(NOW + MAX_EXPIRY_WINDOW_SECS + 1);
+    let signature = sign(PLAYER_PRIVATE_KEY, &reveal_chance).await;
+    assert_eq!(
+        reveal_chance.verify(&signature, &get_address(PLAYER_PRIVATE_KEY), NOW),
+        Err(RevealSignatureError::Expired)
+    );
+}
+
+#[tokio::test]
+async fn rejects_signatures_from_anyone_but_the_player() {
+    let reveal_chance = new_reveal_chance(NOW + 60);
+    let signature = sign(OTHER_PRIVATE_KEY, &reveal_chance).await;
+
+    assert_eq!(
+        reveal_chance.verify(&signature, &get_address(PLAYER_PRIVATE_KEY), NOW),
+        Err(RevealSignatureError::Mismatch)
+    );
+}
+
+#[tokio::test]
+async fn rejects_signatures_over_another_nonce() {
+    let reveal_chance = new_reveal_chance(NOW + 60);
+    let signature = sign(PLAYER_PRIVATE_KEY, &reveal_chance).await;
+
+    let reveal_chance = RevealChance {
+        nonce: U256::from(2),
+        ..reveal_chance
+    };
+    assert_eq!(
+        reveal_chance.verify(&signature, &get_address(PLAYER_PRIVATE_KEY), NOW),
+        Err(RevealSignatureError::Mismatch)
+    );
+}
+
+#[tokio::test]
+async fn rejects_malformed_signatures() {
+    let reveal_chance = new_reveal_chance(NOW + 60);
+
+    assert_eq!(
+        reveal_chance.verify("0x1234", &get_address(PLAYER_PRIVATE_KEY), NOW),
+        Err(RevealSignatureError::Malformed)
+    );
+}
@@ -0,0 +1,176 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use ark_db::schema::coinflip_submitted_chances;
+use diesel::prelude::{Insertable, Queryable};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Game, GamePlay, PlayerAddress};
+
+/// A player's `chance_and_salt` submitted to Ark but not yet published on chain.
+/// It is only ever stored encrypted with the server's `ChanceCipher`.
+#[derive(Clone, Debug, Serialize, Deserialize, Queryable)]
+#[diesel(table_name = coinflip_submitted_chances)]
+pub struct SubmittedChance {
+    pub id: i64,
+    pub game_id: i64,
+    pub chain_id: i64,
+    pub game_play_id: i32,
+    pub player_address: String,
+    encrypted_chance_and_salt: String,
+    pub submitted_at: i64,
+}
+
+impl SubmittedChance {
+    pub fn get_chance_and_salt(&self, cipher: &ChanceCipher) -> Option<String> {
+        let associated_data = get_associated_data(self.chain_id, self.game_id, self.game_play_id);
+
+        cipher.decrypt(&self.encrypted_chance_and_salt, &associated_data)
+    }
+}
+
+#[derive(Clone, Debug, Insertable)]
+#[diesel(table_name = coinflip_submitted_chances)]
+pub struct UnsavedSubmittedChance {
+    game_id: i64,
+    chain_id: i64,
+    game_play_id: i32,
+    player_address: String,
+    encrypted_chance_and_salt: String,
+    submitted_at: i64,
+}
+
+impl UnsavedSubmittedChance {
+    pub fn new(
+        game_play: &GamePlay,
+        chance_and_salt: &str,
+        cipher: &ChanceCipher,
+        submitted_at: i64,
+    ) -> Self {
+        UnsavedSubmittedChance {
+            game_id: game_play.game_id,
+            chain_id: game_play.chain_id,
+            game_play_id: game_play.id,
+            player_address: game_play.player_address.to_lowercase(),
+            encrypted_chance_and_salt: cipher.encrypt(
+                chance_and_salt,
+                &get_associated_data(game_play.chain_id, game_play.game_id, game_play.id),
+            ),
+            submitted_at,
+        }
+    }
+
+    /// For stores that assign ids themselves e.g. in-memory repos
+    pub fn to_saved(&self, id: i64) -> SubmittedChance {
+        SubmittedChance {
+            id,
+            game_id: self.game_id,
+            chain_id: self.chain_id,
+            game_play_id: self.game_play_id,
+            player_address: self.player_address.clone(),
+            encrypted_chance_and_salt: self.encrypted_chance_and_salt.clone(),
+            submitted_at: self.submitted_at,
+        }
+    }
+}
+
+/// Binds a ciphertext to its game play, so it can't be swapped into another play's row
+fn get_associated_data(chain_id: i64, game_id: i64, game_play_id: i32) -> Vec<u8> {
+    format!("{chain_id}:{game_id}:{game_play_id}").into_bytes()
+}
+
+/// Encrypts chances at rest with the server key (AES-256-GCM).
+/// Ciphertexts are stored as `<nonce hex>:<ciphertext hex>`.
+#[derive(Clone)]
+pub struct ChanceCipher {
+    cipher: Aes256Gcm,
+}
+
+impl ChanceCipher {
+    /// The key is validated when `ArkConfig` loads, so a cipher is built once at boot
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+        }
+    }
+
+    pub fn encrypt(&self, chance_and_salt: &str, associated_data: &[u8]) -> String {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let payload = Payload {
+            msg: chance_and_salt.as_bytes(),
+            aad: associated_data,
+        };
+        let ciphertext = self.cipher.encrypt(&nonce, payload).unwrap();
+
+        format!("{}:{}", hex::encode(nonce), hex::encode(ciphertext))
+    }
+
+    /// `None` if the ciphertext was tampered with, or was encrypted with another key
+    /// or for other associated data
+    pub fn decrypt(
+        &self,
+        encrypted_chance_and_salt: &str,
+        associated_data: &[u8],
+    ) -> Option<String> {
+        let (nonce, ciphertext) = encrypted_chance_and_salt.split_once(':')?;
+        let nonce = hex::decode(nonce).ok()?;
+        let ciphertext = hex::decode(ciphertext).ok()?;
+
+        if nonce.len() != 12 {
+            return None;
+        }
+
+        let payload = Payload {
+            msg: ciphertext.as_ref(),
+            aad: associated_data,
+        };
+        let chance_and_salt = self.cipher.decrypt(Nonce::from_slice(&nonce), payload).ok()?;
+
+        String::from_utf8(chance_and_salt).ok()
+    }
+}
+
+/// Who is looking at a game's plays
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Viewer {
+    Anonymous,
+    /// A player whose address has been authenticated
+    Player(String),
+}
+
+impl Viewer {
+    pub fn owns(&self, game_play: &GamePlay) -> bool {
+        match self {
+            Viewer::Anonymous => false,
+            Viewer::Player(address) => {
+                PlayerAddress::do_both_match(address, &game_play.player_address)
+            }
+        }
+    }
+}
+
+/// Players' chances stay secret until a game completes,
+/// otherwise players yet to reveal could pick their reveal after reading others'.
+pub struct ChanceVisibility;
+
+impl ChanceVisibility {
+    pub fn can_see(game: &Game, game_play: &GamePlay, viewer: &Viewer) -> bool {
+        game.is_completed() || viewer.owns(game_play)
+    }
+
+    pub fn redact(game: &Game, game_plays: &Vec<GamePlay>, viewer: &Viewer) -> Vec<GamePlay> {
+        game_plays
+            .iter()
+            .map(|game_play| {
+                if Self::can_see(game, game_play, viewer) {
+                    game_play.clone()
+                } else {
+                    GamePlay {
+                        chance_and_salt: None,
+                        ..game_play.clone()
+                    }
+                }
+            })
+            .collect()
+    }
+}
@@ -13,6 +13,12 @@ impl CoinSide {
     pub fn is_tail(coin_side: u8) -> bool {
         coin_side == 1
     }
+    pub fn to_i32(&self) -> i32 {
+        match self {
+            CoinSide::Head => 0,
+            CoinSide::Tail => 1,
+        }
+    }
 }
 
 impl From<usize> for CoinSide {
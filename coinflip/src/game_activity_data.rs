@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+use crate::GameActivityKind;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GamePlayCreatedActivityData {
+    pub coin_side: u8,
+    pub proof_of_chance: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameCompletedActivityData {
+    pub outcome: u8,
+    pub winner_addresses: Vec<String>,
+    /// In wei
+    pub amount_for_each_winner: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameRefundedActivityData {
+    /// In wei
+    pub refunded_amount_per_player: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameExpiryAdjustedActivityData {
+    pub old_expiry_timestamp: i64,
+    pub new_expiry_timestamp: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WalletCreditReason {
+    #[serde(rename = "won")]
+    Won,
+    #[serde(rename = "refunded")]
+    Refunded,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WalletCreditedActivityData {
+    /// In wei
+    pub amount: String,
+    pub reason: WalletCreditReason,
+}
+
+/// A game activity's `data`, decoded according to its kind
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameActivityData {
+    GamePlayCreated(GamePlayCreatedActivityData),
+    GameCompleted(GameCompletedActivityData),
+    GameRefunded(GameRefundedActivityData),
+    GameExpiryAdjusted(GameExpiryAdjustedActivityData),
+    WalletCredited(WalletCreditedActivityData),
+}
+
+impl GameActivityData {
+    /// Returns None for kinds without data or data that doesn't match the kind's schema
+    pub fn decode(kind: &GameActivityKind, data: &serde_json::Value) -> Option<Self> {
+        let data = data.clone();
+
+        match kind {
+            GameActivityKind::GamePlayCreated => {
+                serde_json::from_value(data).ok().map(GameActivityData::GamePlayCreated)
+            }
+            GameActivityKind::GameCompleted => {
+                serde_json::from_value(data).ok().map(GameActivityData::GameCompleted)
+            }
+            GameActivityKind::GameRefunded => {
+                serde_json::from_value(data).ok().map(GameActivityData::GameRefunded)
+            }
+            GameActivityKind::GameExpiryAdjusted => {
+                serde_json::from_value(data).ok().map(GameActivityData::GameExpiryAdjusted)
+            }
+            GameActivityKind::WalletCredited => {
+                serde_json::from_value(data).ok().map(GameActivityData::WalletCredited)
+            }
+            GameActivityKind::GameCreated
+            | GameActivityKind::GamePlayChanceRevealed
+            | GameActivityKind::GameExpired => None,
+        }
+    }
+}
@@ -1,21 +1,33 @@
-use ark_db::schema::coinflip_game_activities;
-use diesel::prelude::{Insertable, Queryable};
+use std::str::FromStr;
 
+use ark_db::schema::{coinflip_game_activities, coinflip_games};
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::pg::{Pg, PgValue};
+use diesel::prelude::{Insertable, Queryable, QueryableByName};
+use diesel::sql_types::Text;
+
+use ark_utils::clocks::Clock;
 use ark_utils::ethers::convert_wei_to_ether;
 
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
-use ark_web3::chains::ChainId;
+use ark_web3::chains::get_currency_chain_id;
+use ark_web3::events::EventHandlerError;
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+use crate::{CoinSide, GameActivityData, GameCompletedActivityData, GamePlayCreatedActivityData};
+use crate::{GameExpiryAdjustedActivityData, GameRefundedActivityData, WalletCreditedActivityData};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, FromSqlRow)]
 pub enum GameStatus {
     #[serde(rename = "awaiting_players")]
     AwaitingPlayers,
     #[serde(rename = "awaiting_revealed_chances")]
     AwaitingRevealedChances,
-    #[serde(rename = "expired")]
-    Expired,
+    #[serde(rename = "expired_pending_refund", alias = "expired")]
+    ExpiredPendingRefund,
+    #[serde(rename = "refunded")]
+    Refunded,
     #[serde(rename = "completed")]
     Completed,
 }
@@ -25,13 +37,136 @@ impl<'a> Into<&'a str> for GameStatus {
         match self {
             GameStatus::AwaitingPlayers => "awaiting_players",
             GameStatus::AwaitingRevealedChances => "awaiting_revealed_chances",
-            GameStatus::Expired => "expired",
+            GameStatus::ExpiredPendingRefund => "expired_pending_refund",
+            GameStatus::Refunded => "refunded",
             GameStatus::Completed => "completed",
         }
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, Queryable)]
+impl Into<String> for GameStatus {
+    fn into(self) -> String {
+        <GameStatus as Into<&str>>::into(self).to_string()
+    }
+}
+
+impl FromStr for GameStatus {
+    type Err = UnknownGameStatus;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "awaiting_players" => Ok(GameStatus::AwaitingPlayers),
+            "awaiting_revealed_chances" => Ok(GameStatus::AwaitingRevealedChances),
+            "expired_pending_refund" => Ok(GameStatus::ExpiredPendingRefund),
+            "refunded" => Ok(GameStatus::Refunded),
+            "completed" => Ok(GameStatus::Completed),
+            _ => Err(UnknownGameStatus(value.to_string())),
+        }
+    }
+}
+
+/// Rows with an unknown status fail to load instead of panicking whoever reads them
+impl FromSql<Text, Pg> for GameStatus {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        let value = <String as FromSql<Text, Pg>>::from_sql(bytes)?;
+
+        Ok(value.parse()?)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownGameStatus(pub String);
+
+impl std::fmt::Display for UnknownGameStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown game status `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownGameStatus {}
+
+impl From<UnknownGameStatus> for EventHandlerError {
+    fn from(err: UnknownGameStatus) -> Self {
+        EventHandlerError::invalid_state(err)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidGameStatusTransition {
+    pub from: GameStatus,
+    pub to: GameStatus,
+}
+
+impl std::fmt::Display for InvalidGameStatusTransition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid game status transition from {:?} to {:?}",
+            self.from, self.to
+        )
+    }
+}
+
+impl std::error::Error for InvalidGameStatusTransition {}
+
+impl From<InvalidGameStatusTransition> for EventHandlerError {
+    fn from(err: InvalidGameStatusTransition) -> Self {
+        EventHandlerError::invalid_state(err)
+    }
+}
+
+impl GameStatus {
+    /// Game lifecycle:
+    /// awaiting_players -> awaiting_revealed_chances -> completed
+    /// Both awaiting states -> expired_pending_refund -> refunded
+    /// Expired games return to awaiting when their expiry is adjusted.
+    /// Expiry itself is never stored, see `Game::get_status_at`,
+    /// so stored awaiting statuses go straight to refunded or completed.
+    pub fn can_transition_to(&self, to: &GameStatus) -> bool {
+        use GameStatus::*;
+
+        match (self, to) {
+            (from, to) if from == to => true,
+            (AwaitingPlayers, AwaitingRevealedChances) => true,
+            (AwaitingPlayers | AwaitingRevealedChances, ExpiredPendingRefund) => true,
+            (AwaitingPlayers | AwaitingRevealedChances | ExpiredPendingRefund, Refunded) => true,
+            (AwaitingRevealedChances | ExpiredPendingRefund, Completed) => true,
+            (ExpiredPendingRefund, AwaitingPlayers | AwaitingRevealedChances) => true,
+            _ => false,
+        }
+    }
+
+    pub fn transition_to(&self, to: GameStatus) -> Result<GameStatus, InvalidGameStatusTransition> {
+        if self.can_transition_to(&to) {
+            Ok(to)
+        } else {
+            Err(InvalidGameStatusTransition { from: *self, to })
+        }
+    }
+
+    pub fn is_awaiting(&self) -> bool {
+        matches!(
+            self,
+            GameStatus::AwaitingPlayers | GameStatus::AwaitingRevealedChances
+        )
+    }
+
+    /// The statuses games are matched by when filtering by this status.
+    /// `Completed` matches every game that is over, including expired and refunded ones,
+    /// as it did before expiry and refunds were statuses of their own.
+    pub fn get_filtered_statuses(&self) -> Vec<GameStatus> {
+        match self {
+            GameStatus::Completed => vec![
+                GameStatus::Completed,
+                GameStatus::ExpiredPendingRefund,
+                GameStatus::Refunded,
+            ],
+            status => vec![*status],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Queryable, QueryableByName)]
 #[diesel(table_name = coinflip_games)]
 pub struct Game {
     pub id: i64,
@@ -49,12 +184,17 @@ pub struct Game {
     pub refunded_amount_per_player: Option<String>,
     pub refunded_at: Option<i64>,
     pub chain_agnostic_index: i64,
+    pub created_at: i64,
+    pub status: GameStatus,
+    pub head_play_count: i32,
+    pub tail_play_count: i32,
+    /// When the last seat filled i.e. the game started awaiting revealed chances
+    pub all_seats_filled_at: Option<i64>,
 }
 
 impl Game {
-    pub fn deduct_service_charge(amount: f64) -> f64 {
-        const SERVICE_CHARGE_PERCENT: f64 = 0.08;
-        amount - (amount * SERVICE_CHARGE_PERCENT)
+    pub fn deduct_service_charge(amount: f64, service_charge_percent: i32) -> f64 {
+        amount - (amount * service_charge_percent as f64 / 100.0)
     }
 
     pub fn has_all_chances_uploaded(&self, chance_and_salts_size: usize) -> bool {
@@ -63,6 +203,9 @@ impl Game {
     pub fn get_players_left(&self) -> u32 {
         (self.number_of_players - self.play_count) as u32
     }
+    pub fn is_awaiting(&self) -> bool {
+        self.get_status().is_awaiting()
+    }
     pub fn is_awaiting_players(&self) -> bool {
         self.get_status() == GameStatus::AwaitingPlayers
     }
@@ -72,23 +215,82 @@ impl Game {
     pub fn is_completed(&self) -> bool {
         self.get_status() == GameStatus::Completed
     }
-    pub fn is_expired(&self) -> bool {
-        self.get_status() == GameStatus::Expired
+    pub fn is_refunded(&self) -> bool {
+        self.get_status() == GameStatus::Refunded
     }
     pub fn get_status(&self) -> GameStatus {
-        let now = chrono::offset::Utc::now().timestamp();
-
-        if self.expiry_timestamp <= now && self.completed_at.is_none() {
-            GameStatus::Expired
-        } else if self.play_count < self.number_of_players {
-            GameStatus::AwaitingPlayers
-        } else if self.completed_at.is_none() && self.play_count == self.number_of_players {
-            GameStatus::AwaitingRevealedChances
-        } else if self.completed_at.is_some() {
-            GameStatus::Completed
+        self.status
+    }
+    pub fn get_current_status(&self, clock: &dyn Clock) -> GameStatus {
+        self.get_status_at(clock.now())
+    }
+    /// The stored status is owned by the indexer and never turns expired,
+    /// so expiry is derived from awaiting games that are past their expiry.
+    /// Mirrored in SQL by `coinflip_repo`'s `get_status_at_sql`, which every status filter goes through.
+    pub fn get_status_at(&self, now: i64) -> GameStatus {
+        let status = self.get_status();
+
+        if status.is_awaiting() && self.expiry_timestamp <= now {
+            GameStatus::ExpiredPendingRefund
         } else {
-            panic!("TODO: Unknown game status");
+            status
+        }
+    }
+    pub fn has_expired(&self, clock: &dyn Clock) -> bool {
+        self.get_current_status(clock) == GameStatus::ExpiredPendingRefund
+    }
+
+    pub fn get_play_count_for(&self, coin_side: &CoinSide) -> u32 {
+        match coin_side {
+            CoinSide::Head => self.head_play_count as u32,
+            CoinSide::Tail => self.tail_play_count as u32,
+        }
+    }
+    pub fn is_coin_side_available(&self, coin_side: &CoinSide) -> bool {
+        self.get_players_left() > 0 && self.unavailable_coin_side != Some(coin_side.to_i32())
+    }
+    /// What a new player would win by picking `coin_side`, given the plays so far.
+    /// None when the side can't be picked.
+    pub fn get_payout_scenario(
+        &self,
+        coin_side: &CoinSide,
+        service_charge_percent: i32,
+    ) -> Option<PayoutScenario> {
+        if !self.is_coin_side_available(coin_side) {
+            return None;
         }
+
+        let amount_shared_with_winners =
+            self.get_amount_shared_with_winners(service_charge_percent);
+
+        let fewest_winners = self.get_play_count_for(coin_side) + 1;
+        // The last player can't pick the side everyone else picked
+        let most_winners =
+            (fewest_winners + self.get_players_left() - 1).min(self.number_of_players as u32 - 1);
+
+        Some(PayoutScenario {
+            min_possible_win: amount_shared_with_winners / most_winners as f64,
+            max_possible_win: amount_shared_with_winners / fewest_winners as f64,
+        })
+    }
+    pub fn get_max_possible_win(&self, service_charge_percent: i32) -> f64 {
+        if self.get_players_left() > 0 {
+            [CoinSide::Head, CoinSide::Tail]
+                .iter()
+                .filter_map(|coin_side| self.get_payout_scenario(coin_side, service_charge_percent))
+                .map(|payout_scenario| payout_scenario.max_possible_win)
+                .fold(0.0, f64::max)
+        } else {
+            let fewest_winners = self.head_play_count.min(self.tail_play_count).max(1);
+
+            self.get_amount_shared_with_winners(service_charge_percent) / fewest_winners as f64
+        }
+    }
+    fn get_amount_shared_with_winners(&self, service_charge_percent: i32) -> f64 {
+        Self::deduct_service_charge(
+            self.number_of_players as f64 * self.get_wager_ether(),
+            service_charge_percent,
+        )
     }
 
     pub fn get_wager_ether(&self) -> f64 {
@@ -102,13 +304,19 @@ impl Game {
     }
 
     pub fn get_chain_id(&self) -> i64 {
-        match (self.chain_id as u64).into() {
-            ChainId::Local | ChainId::LocalAlt => ChainId::Ethereum as i64,
-            _any_other_chain => self.chain_id,
-        }
+        get_currency_chain_id(self.chain_id)
     }
 }
 
+/// Payout range for a new player on one coin side:
+/// at most when every remaining player picks the other side
+/// and at least when they all pick the same side.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PayoutScenario {
+    pub min_possible_win: f64,
+    pub max_possible_win: f64,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GamePlayStatus {
     #[serde(rename = "pending")]
@@ -133,18 +341,31 @@ impl Into<String> for GamePlayStatus {
     }
 }
 
-impl From<String> for GamePlayStatus {
-    fn from(value: String) -> Self {
-        match value.as_ref() {
-            "pending" => GamePlayStatus::Pending,
-            "won" => GamePlayStatus::Expired,
-            "lost" => GamePlayStatus::Lost,
-            "expired" => GamePlayStatus::Expired,
-            _ => panic!("Unknown game play status"),
+impl FromStr for GamePlayStatus {
+    type Err = UnknownGamePlayStatus;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "pending" => Ok(GamePlayStatus::Pending),
+            "won" => Ok(GamePlayStatus::Won),
+            "lost" => Ok(GamePlayStatus::Lost),
+            "expired" => Ok(GamePlayStatus::Expired),
+            _ => Err(UnknownGamePlayStatus(value.to_string())),
         }
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownGamePlayStatus(pub String);
+
+impl std::fmt::Display for UnknownGamePlayStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown game play status `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownGamePlayStatus {}
+
 impl GamePlayStatus {
     pub fn pending_string() -> String {
         "pending".to_string()
@@ -175,21 +396,20 @@ pub struct GamePlay {
 
 impl GamePlay {
     pub fn is_chance_and_salt(&self, chance_and_salt: &str) -> bool {
-        self.proof_of_chance == hash_proof(&Self::get_chance_and_salt_bytes(chance_and_salt))
+        Self::parse_chance_and_salt_bytes(chance_and_salt)
+            .map(|chance_and_salt| self.proof_of_chance == hash_proof(&chance_and_salt))
+            .unwrap_or(false)
     }
     pub fn get_chance_and_salt_bytes(chance_and_salt: &str) -> Vec<u8> {
+        Self::parse_chance_and_salt_bytes(chance_and_salt).unwrap()
+    }
+    pub fn parse_chance_and_salt_bytes(chance_and_salt: &str) -> Option<Vec<u8>> {
         let chance_and_salt = chance_and_salt.replace("0x", "");
-        hex::decode(&chance_and_salt).unwrap()
+        hex::decode(&chance_and_salt).ok()
     }
 
-    pub fn get_status(&self) -> GamePlayStatus {
-        match self.status.as_ref() {
-            "pending" => GamePlayStatus::Pending,
-            "won" => GamePlayStatus::Won,
-            "lost" => GamePlayStatus::Lost,
-            "expired" => GamePlayStatus::Expired,
-            _ => unreachable!("Unknown state"),
-        }
+    pub fn get_status(&self) -> Result<GamePlayStatus, UnknownGamePlayStatus> {
+        self.status.parse()
     }
 
     pub fn filter_by_coin_side<'a>(
@@ -216,20 +436,45 @@ pub enum GameActivityKind {
     GamePlayChanceRevealed,
     #[serde(rename = "game_expired")]
     GameExpired,
+    #[serde(rename = "game_completed")]
+    GameCompleted,
+    #[serde(rename = "game_refunded")]
+    GameRefunded,
+    #[serde(rename = "game_expiry_adjusted")]
+    GameExpiryAdjusted,
+    #[serde(rename = "wallet_credited")]
+    WalletCredited,
 }
 
-impl From<String> for GameActivityKind {
-    fn from(value: String) -> Self {
-        match value.as_ref() {
-            "game_created" => GameActivityKind::GameCreated,
-            "game_play_created" => GameActivityKind::GamePlayCreated,
-            "game_play_chance_revealed" => GameActivityKind::GamePlayChanceRevealed,
-            "game_expired" => GameActivityKind::GameExpired,
-            _ => unimplemented!("Unknown game activity kind"),
+impl FromStr for GameActivityKind {
+    type Err = UnknownGameActivityKind;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "game_created" => Ok(GameActivityKind::GameCreated),
+            "game_play_created" => Ok(GameActivityKind::GamePlayCreated),
+            "game_play_chance_revealed" => Ok(GameActivityKind::GamePlayChanceRevealed),
+            "game_expired" => Ok(GameActivityKind::GameExpired),
+            "game_completed" => Ok(GameActivityKind::GameCompleted),
+            "game_refunded" => Ok(GameActivityKind::GameRefunded),
+            "game_expiry_adjusted" => Ok(GameActivityKind::GameExpiryAdjusted),
+            "wallet_credited" => Ok(GameActivityKind::WalletCredited),
+            _ => Err(UnknownGameActivityKind(value.to_string())),
         }
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownGameActivityKind(pub String);
+
+impl std::fmt::Display for UnknownGameActivityKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown game activity kind `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownGameActivityKind {}
+
 impl Into<String> for GameActivityKind {
     fn into(self) -> String {
         match self {
@@ -237,11 +482,27 @@ impl Into<String> for GameActivityKind {
             GameActivityKind::GamePlayCreated => "game_play_created",
             GameActivityKind::GamePlayChanceRevealed => "game_play_chance_revealed",
             GameActivityKind::GameExpired => "game_expired",
+            GameActivityKind::GameCompleted => "game_completed",
+            GameActivityKind::GameRefunded => "game_refunded",
+            GameActivityKind::GameExpiryAdjusted => "game_expiry_adjusted",
+            GameActivityKind::WalletCredited => "wallet_credited",
         }
         .to_string()
     }
 }
 
+/// The on-chain event an activity comes from.
+/// Activities are unique per event, kind and trigger, since one event can produce several
+/// e.g. a completed game credits each winner, so replaying an event never duplicates them.
+#[derive(Clone, Debug)]
+pub struct GameActivityEvent {
+    pub chain_id: i64,
+    pub block_number: i64,
+    pub block_timestamp: i64,
+    pub transaction_hash: String,
+    pub log_index: i32,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, Insertable)]
 #[diesel(table_name = coinflip_game_activities)]
 pub struct UnsavedGameActivity {
@@ -252,77 +513,137 @@ pub struct UnsavedGameActivity {
     pub data: Option<serde_json::Value>,
     pub occurred_at: i64,
     pub transaction_hash: Option<String>,
+    pub log_index: Option<i32>,
+    pub block_number: Option<i64>,
 }
 
 impl UnsavedGameActivity {
     pub fn new_game_created(
         game_id: u64,
-        chain_id: i64,
-        trigger_public_address: String,
-        block_timestamp: i64,
-        transaction_hash: String,
+        trigger_public_address: &str,
+        event: &GameActivityEvent,
     ) -> Self {
-        UnsavedGameActivity {
-            game_id: game_id as i64,
-            chain_id,
-            occurred_at: block_timestamp,
-            trigger_public_address: trigger_public_address.to_lowercase(),
-            kind: "game_created".to_string(),
-            data: None,
-            transaction_hash: Some(transaction_hash.to_lowercase()),
-        }
+        Self::new(
+            game_id,
+            trigger_public_address,
+            GameActivityKind::GameCreated,
+            None,
+            event,
+        )
     }
     pub fn new_game_play_created(
         game_id: u64,
-        chain_id: i64,
-        trigger_public_address: String,
-        block_timestamp: i64,
-        transaction_hash: String,
+        trigger_public_address: &str,
+        event: &GameActivityEvent,
         coin_side: u8,
         proof_of_chance: String,
     ) -> Self {
-        #[derive(Clone, Debug, Serialize, Deserialize)]
-        struct GamePlayCreatedActivityData {
-            pub coin_side: u8,
-            pub proof_of_chance: String,
-        }
-
-        UnsavedGameActivity {
-            game_id: game_id as i64,
-            chain_id,
-            occurred_at: block_timestamp,
-            trigger_public_address: trigger_public_address.to_lowercase(),
-            kind: "game_play_created".to_string(),
-            data: Some(
+        Self::new(
+            game_id,
+            trigger_public_address,
+            GameActivityKind::GamePlayCreated,
+            Some(
                 serde_json::to_value(GamePlayCreatedActivityData {
                     coin_side,
                     proof_of_chance,
                 })
                 .unwrap(),
             ),
-            transaction_hash: Some(transaction_hash.to_lowercase()),
-        }
+            event,
+        )
     }
-    pub fn new_chance_revealed(game_id: u64, chain_id: i64, trigger_public_address: &str) -> Self {
-        let now = chrono::offset::Utc::now().timestamp();
-
+    pub fn new_chance_revealed(
+        game_id: u64,
+        trigger_public_address: &str,
+        event: &GameActivityEvent,
+    ) -> Self {
+        Self::new(
+            game_id,
+            trigger_public_address,
+            GameActivityKind::GamePlayChanceRevealed,
+            None,
+            event,
+        )
+    }
+    pub fn new_game_completed(
+        game_id: u64,
+        trigger_public_address: &str,
+        event: &GameActivityEvent,
+        data: GameCompletedActivityData,
+    ) -> Self {
+        Self::new(
+            game_id,
+            trigger_public_address,
+            GameActivityKind::GameCompleted,
+            Some(serde_json::to_value(data).unwrap()),
+            event,
+        )
+    }
+    pub fn new_game_refunded(
+        game_id: u64,
+        trigger_public_address: &str,
+        event: &GameActivityEvent,
+        data: GameRefundedActivityData,
+    ) -> Self {
+        Self::new(
+            game_id,
+            trigger_public_address,
+            GameActivityKind::GameRefunded,
+            Some(serde_json::to_value(data).unwrap()),
+            event,
+        )
+    }
+    pub fn new_game_expiry_adjusted(
+        game_id: u64,
+        trigger_public_address: &str,
+        event: &GameActivityEvent,
+        data: GameExpiryAdjustedActivityData,
+    ) -> Self {
+        Self::new(
+            game_id,
+            trigger_public_address,
+            GameActivityKind::GameExpiryAdjusted,
+            Some(serde_json::to_value(data).unwrap()),
+            event,
+        )
+    }
+    /// The credited player is the trigger
+    pub fn new_wallet_credited(
+        game_id: u64,
+        player_address: &str,
+        event: &GameActivityEvent,
+        data: WalletCreditedActivityData,
+    ) -> Self {
+        Self::new(
+            game_id,
+            player_address,
+            GameActivityKind::WalletCredited,
+            Some(serde_json::to_value(data).unwrap()),
+            event,
+        )
+    }
+    fn new(
+        game_id: u64,
+        trigger_public_address: &str,
+        kind: GameActivityKind,
+        data: Option<serde_json::Value>,
+        event: &GameActivityEvent,
+    ) -> Self {
         UnsavedGameActivity {
             game_id: game_id as i64,
-            chain_id,
+            chain_id: event.chain_id,
             trigger_public_address: trigger_public_address.to_lowercase(),
-            kind: "game_play_chance_revealed".to_string(),
-            data: None,
-            occurred_at: now,
-            transaction_hash: None,
+            kind: kind.into(),
+            data,
+            occurred_at: event.block_timestamp,
+            transaction_hash: Some(event.transaction_hash.to_lowercase()),
+            log_index: Some(event.log_index),
+            block_number: Some(event.block_number),
         }
     }
-    pub fn with_transaction_hash(mut self, transaction_hash: &str) -> Self {
-        self.transaction_hash = Some(transaction_hash.to_string());
-        self
-    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, Queryable)]
+#[derive(Clone, Debug, Serialize, Deserialize, Queryable, QueryableByName)]
 #[diesel(table_name = coinflip_game_activities)]
 pub struct GameActivity {
     pub id: i64,
@@ -333,6 +654,35 @@ pub struct GameActivity {
     pub data: serde_json::Value,
     pub occurred_at: i64,
     pub transaction_hash: Option<String>,
+    pub log_index: Option<i32>,
+    pub block_number: Option<i64>,
+}
+
+impl GameActivity {
+    pub fn get_kind(&self) -> Result<GameActivityKind, UnknownGameActivityKind> {
+        self.kind.parse()
+    }
+    /// None for kinds without data, including kinds this version doesn't know
+    pub fn get_data(&self) -> Option<GameActivityData> {
+        GameActivityData::decode(&self.get_kind().ok()?, &self.data)
+    }
+}
+
+pub const MAX_GAME_ACTIVITIES_COUNT: i64 = 50;
+
+#[derive(Debug, Deserialize, Default)]
+pub struct GetPlayerGameActivitiesParams {
+    /// Only activities older than this activity id i.e. the previous page's `next_cursor`
+    pub cursor: Option<i64>,
+    pub page_size: Option<i64>,
+}
+
+impl GetPlayerGameActivitiesParams {
+    pub fn get_page_size(&self) -> i64 {
+        self.page_size
+            .unwrap_or(MAX_GAME_ACTIVITIES_COUNT)
+            .clamp(1, MAX_GAME_ACTIVITIES_COUNT)
+    }
 }
 
 pub struct PlayerAddress;
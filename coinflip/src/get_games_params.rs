@@ -0,0 +1,189 @@
+use serde::{Deserialize, Deserializer};
+
+use crate::GameStatus;
+
+pub const MAX_GAMES_COUNT: i64 = 40;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum GameSort {
+    #[default]
+    #[serde(rename = "newest")]
+    Newest,
+    #[serde(rename = "oldest")]
+    Oldest,
+    #[serde(rename = "soonest_expiry")]
+    SoonestExpiry,
+    #[serde(rename = "largest_wager")]
+    LargestWager,
+    #[serde(rename = "fewest_players_left")]
+    FewestPlayersLeft,
+}
+
+/// Filters for querying `coinflip_games`.
+/// Every field is an independent filter, so any subset of them can be combined,
+/// either from an HTTP query string or with the builder methods below.
+#[derive(Clone, Debug, Deserialize, Default)]
+pub struct GetGamesParams {
+    /// Matched against each game's status at the time of the query.
+    /// `completed` matches every game that is over, see `GameStatus::get_filtered_statuses`.
+    pub status: Option<GameStatus>,
+    pub reject_status: Option<GameStatus>,
+    pub is_completed: Option<bool>,
+    pub is_refunded: Option<bool>,
+    pub player_address: Option<String>,
+    pub creator_address: Option<String>,
+    /// Comma separated in query strings e.g. `chain_ids=1,137`
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    pub chain_ids: Option<Vec<i64>>,
+    #[serde(default, deserialize_with = "deserialize_comma_separated")]
+    pub chain_ids_to_ignore: Option<Vec<i64>>,
+    pub chain_id_to_ignore: Option<i64>,
+    /// In the chain's native unit e.g. ETH
+    #[serde(default, deserialize_with = "deserialize_finite")]
+    pub min_wager: Option<f64>,
+    #[serde(default, deserialize_with = "deserialize_finite")]
+    pub max_wager: Option<f64>,
+    #[serde(default, deserialize_with = "deserialize_finite")]
+    pub min_wager_usd: Option<f64>,
+    #[serde(default, deserialize_with = "deserialize_finite")]
+    pub max_wager_usd: Option<f64>,
+    pub number_of_players: Option<i32>,
+    /// Games where a new player can still pick this side, 0 for head and 1 for tail
+    pub available_coin_side: Option<i32>,
+    pub min_expires_in_secs: Option<i64>,
+    pub max_expires_in_secs: Option<i64>,
+    pub created_after: Option<i64>,
+    pub created_before: Option<i64>,
+    pub id_to_ignore: Option<i64>,
+    pub sort: Option<GameSort>,
+    pub page_size: Option<i64>,
+    pub offset: Option<u64>,
+    #[serde(skip)]
+    pub is_unbounded: bool,
+}
+
+impl GetGamesParams {
+    pub fn new() -> Self {
+        Default::default()
+    }
+    pub fn expired(mut self) -> Self {
+        self.status = Some(GameStatus::ExpiredPendingRefund);
+        self
+    }
+    pub fn not_expired(mut self) -> Self {
+        self.reject_status = Some(GameStatus::ExpiredPendingRefund);
+        self
+    }
+    pub fn not_refunded(mut self) -> Self {
+        self.is_refunded = Some(false);
+        self
+    }
+    pub fn only_incomplete(mut self) -> Self {
+        self.is_completed = Some(false);
+        self
+    }
+    pub fn with_status(mut self, status: GameStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+    pub fn with_player(mut self, player_address: &str) -> Self {
+        self.player_address = Some(player_address.to_string());
+        self
+    }
+    pub fn with_creator(mut self, creator_address: &str) -> Self {
+        self.creator_address = Some(creator_address.to_string());
+        self
+    }
+    pub fn on_chains(mut self, chain_ids: Vec<i64>) -> Self {
+        self.chain_ids = Some(chain_ids);
+        self
+    }
+    pub fn ignoring_chains(mut self, chain_ids: Vec<i64>) -> Self {
+        self.chain_ids_to_ignore = Some(chain_ids);
+        self
+    }
+    pub fn with_wager_between(mut self, min_wager: Option<f64>, max_wager: Option<f64>) -> Self {
+        self.min_wager = min_wager;
+        self.max_wager = max_wager;
+        self
+    }
+    pub fn with_wager_usd_between(
+        mut self,
+        min_wager_usd: Option<f64>,
+        max_wager_usd: Option<f64>,
+    ) -> Self {
+        self.min_wager_usd = min_wager_usd;
+        self.max_wager_usd = max_wager_usd;
+        self
+    }
+    pub fn with_number_of_players(mut self, number_of_players: i32) -> Self {
+        self.number_of_players = Some(number_of_players);
+        self
+    }
+    pub fn with_available_coin_side(mut self, coin_side: i32) -> Self {
+        self.available_coin_side = Some(coin_side);
+        self
+    }
+    pub fn expiring_between(
+        mut self,
+        min_expires_in_secs: Option<i64>,
+        max_expires_in_secs: Option<i64>,
+    ) -> Self {
+        self.min_expires_in_secs = min_expires_in_secs;
+        self.max_expires_in_secs = max_expires_in_secs;
+        self
+    }
+    pub fn created_between(
+        mut self,
+        created_after: Option<i64>,
+        created_before: Option<i64>,
+    ) -> Self {
+        self.created_after = created_after;
+        self.created_before = created_before;
+        self
+    }
+    pub fn sorted_by(mut self, sort: GameSort) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+    pub fn paginate(mut self, page_size: i64, offset: u64) -> Self {
+        self.page_size = Some(page_size);
+        self.offset = Some(offset);
+        self
+    }
+    /// Lifts the page size cap, meant for workers that must see every match.
+    pub fn unbounded(mut self) -> Self {
+        self.is_unbounded = true;
+        self
+    }
+}
+
+/// Rejects `NaN` and infinities, which parse as `f64` but can't be compared with wagers
+fn deserialize_finite<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<f64>::deserialize(deserializer)? {
+        Some(value) if !value.is_finite() => Err(serde::de::Error::custom(format!(
+            "expected a finite number, found {value}"
+        ))),
+        value => Ok(value),
+    }
+}
+
+fn deserialize_comma_separated<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    let value = String::deserialize(deserializer)?;
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| item.parse::<T>().map_err(serde::de::Error::custom))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
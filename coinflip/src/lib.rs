@@ -1,5 +1,21 @@
+mod chances;
 mod coin;
+mod game_activity_data;
 mod games;
+mod get_games_params;
+mod player_actions;
+mod recent_results;
+mod repos;
+mod reveal_reminders;
+mod service_charges;
 
+pub use chances::*;
 pub use coin::*;
+pub use game_activity_data::*;
 pub use games::*;
+pub use get_games_params::*;
+pub use player_actions::*;
+pub use recent_results::*;
+pub use repos::*;
+pub use reveal_reminders::*;
+pub use service_charges::*;
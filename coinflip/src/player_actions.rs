@@ -0,0 +1,115 @@
+use std::str::FromStr;
+
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::pg::{Pg, PgValue};
+use diesel::prelude::QueryableByName;
+use diesel::sql_types::{BigInt, Integer, Text};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Game;
+
+/// Games about to expire within this window count as needing attention
+pub const DEFAULT_EXPIRING_WITHIN_SECS: i64 = 30 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, FromSqlRow)]
+pub enum PlayerActionKind {
+    /// The player has yet to reveal their chance in a filled game
+    #[serde(rename = "must_reveal")]
+    MustReveal,
+    /// The player's game is about to expire while awaiting opponents
+    #[serde(rename = "expiring_soon")]
+    ExpiringSoon,
+    #[serde(rename = "refunded")]
+    Refunded,
+    #[serde(rename = "won")]
+    Won,
+}
+
+impl<'a> Into<&'a str> for PlayerActionKind {
+    fn into(self) -> &'a str {
+        match self {
+            PlayerActionKind::MustReveal => "must_reveal",
+            PlayerActionKind::ExpiringSoon => "expiring_soon",
+            PlayerActionKind::Refunded => "refunded",
+            PlayerActionKind::Won => "won",
+        }
+    }
+}
+
+impl FromStr for PlayerActionKind {
+    type Err = UnknownPlayerActionKind;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "must_reveal" => Ok(PlayerActionKind::MustReveal),
+            "expiring_soon" => Ok(PlayerActionKind::ExpiringSoon),
+            "refunded" => Ok(PlayerActionKind::Refunded),
+            "won" => Ok(PlayerActionKind::Won),
+            _ => Err(UnknownPlayerActionKind(value.to_string())),
+        }
+    }
+}
+
+/// Rows with an unknown kind fail to load instead of panicking whoever reads them
+impl FromSql<Text, Pg> for PlayerActionKind {
+    fn from_sql(bytes: PgValue<'_>) -> deserialize::Result<Self> {
+        let value = <String as FromSql<Text, Pg>>::from_sql(bytes)?;
+
+        Ok(value.parse()?)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownPlayerActionKind(pub String);
+
+impl std::fmt::Display for UnknownPlayerActionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Unknown player action kind `{}`", self.0)
+    }
+}
+
+impl std::error::Error for UnknownPlayerActionKind {}
+
+#[derive(Clone, Debug)]
+pub struct GetPlayerActionsParams {
+    pub player_address: String,
+    pub now: i64,
+    pub expiring_within_secs: i64,
+    /// Refunds and wins at or before this timestamp were already seen by the player
+    pub credited_since: i64,
+}
+
+/// A game needing the player's attention, see `GameRepo::get_player_actions`
+#[derive(Clone, Debug, QueryableByName)]
+pub struct PlayerAction {
+    #[diesel(embed)]
+    pub game: Game,
+    #[diesel(sql_type = Integer)]
+    pub game_play_id: i32,
+    #[diesel(sql_type = Text)]
+    kind: PlayerActionKind,
+    /// When the action became due e.g. when the last seat filled or the game was refunded
+    #[diesel(sql_type = BigInt)]
+    pub action_at: i64,
+}
+
+impl PlayerAction {
+    pub fn new(game: Game, game_play_id: i32, kind: PlayerActionKind, action_at: i64) -> Self {
+        PlayerAction {
+            game,
+            game_play_id,
+            kind,
+            action_at,
+        }
+    }
+    pub fn get_kind(&self) -> PlayerActionKind {
+        self.kind
+    }
+    pub fn is_credit(&self) -> bool {
+        matches!(
+            self.get_kind(),
+            PlayerActionKind::Refunded | PlayerActionKind::Won
+        )
+    }
+}
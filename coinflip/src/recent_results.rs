@@ -0,0 +1,171 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use ark_utils::ethers::convert_wei_to_ether;
+use ark_utils::floats;
+use ark_web3::chains::{ChainCurrency, ChainId};
+use diesel::prelude::QueryableByName;
+use diesel::sql_types::{BigInt, Integer, Text};
+
+use serde::Serialize;
+
+/// How many of the latest wins are kept in memory
+pub const RECENT_RESULTS_CAPACITY: usize = 200;
+pub const MAX_RECENT_RESULTS_COUNT: usize = 50;
+
+/// A winning play in a completed game
+#[derive(Clone, Debug, QueryableByName)]
+pub struct GameWin {
+    #[diesel(sql_type = BigInt)]
+    pub game_id: i64,
+    #[diesel(sql_type = BigInt)]
+    pub chain_id: i64,
+    #[diesel(sql_type = Integer)]
+    pub game_play_id: i32,
+    #[diesel(sql_type = Text)]
+    pub winner_address: String,
+    /// In wei
+    #[diesel(sql_type = Text)]
+    pub amount_won: String,
+    #[diesel(sql_type = BigInt)]
+    pub completed_at: i64,
+}
+
+/// Newer results sort first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RecentResultCursor {
+    completed_at: i64,
+    chain_id: i64,
+    game_id: i64,
+    game_play_id: i32,
+}
+
+impl RecentResultCursor {
+    pub fn parse(cursor: &str) -> Option<Self> {
+        let mut parts = cursor.split('-').map(|part| part.parse::<i64>().ok());
+
+        let cursor = RecentResultCursor {
+            completed_at: parts.next()??,
+            chain_id: parts.next()??,
+            game_id: parts.next()??,
+            game_play_id: parts.next()?? as i32,
+        };
+
+        parts.next().is_none().then_some(cursor)
+    }
+}
+
+impl fmt::Display for RecentResultCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}-{}-{}",
+            self.completed_at, self.chain_id, self.game_id, self.game_play_id
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RecentResult {
+    pub game_id: i64,
+    pub chain_id: i64,
+    pub game_play_id: i32,
+    pub winner_address: String,
+    pub currency_symbol: String,
+    pub amount_won: f64,
+    /// Priced when the game completed
+    pub amount_won_usd: Option<f64>,
+    pub completed_at: i64,
+}
+
+impl RecentResult {
+    pub fn new(game_win: &GameWin, chain_currency: Option<&ChainCurrency>) -> Self {
+        let amount_won = convert_wei_to_ether(&game_win.amount_won);
+
+        RecentResult {
+            game_id: game_win.game_id,
+            chain_id: game_win.chain_id,
+            game_play_id: game_win.game_play_id,
+            winner_address: game_win.winner_address.to_lowercase(),
+            currency_symbol: ChainId::from(game_win.chain_id as u64)
+                .get_currency_symbol()
+                .to_string(),
+            amount_won,
+            amount_won_usd: chain_currency
+                .map(|chain_currency| floats::to_2dp(chain_currency.convert_to_usd(amount_won))),
+            completed_at: game_win.completed_at,
+        }
+    }
+    pub fn get_cursor(&self) -> RecentResultCursor {
+        RecentResultCursor {
+            completed_at: self.completed_at,
+            chain_id: self.chain_id,
+            game_id: self.game_id,
+            game_play_id: self.game_play_id,
+        }
+    }
+}
+
+/// The latest wins across all chains, newest first.
+/// Duplicates are ignored and the oldest results are evicted once full.
+pub struct RecentResults {
+    results: VecDeque<RecentResult>,
+    capacity: usize,
+}
+
+impl Default for RecentResults {
+    fn default() -> Self {
+        Self::new(RECENT_RESULTS_CAPACITY)
+    }
+}
+
+impl RecentResults {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            results: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+    /// Swaps in results freshly loaded from committed games, so wins undone by reorgs drop out
+    pub fn reload(&mut self, results: Vec<RecentResult>) {
+        self.results.clear();
+
+        for result in results {
+            self.insert(result);
+        }
+    }
+    pub fn insert(&mut self, result: RecentResult) {
+        let cursor = result.get_cursor();
+
+        match self.results.binary_search_by(|existing| cursor.cmp(&existing.get_cursor())) {
+            Ok(_already_inserted) => {}
+            Err(index) => {
+                if index < self.capacity {
+                    self.results.insert(index, result);
+                    self.results.truncate(self.capacity);
+                }
+            }
+        }
+    }
+    /// Results older than the cursor, with the cursor for the next page if any
+    pub fn get_page(
+        &self,
+        cursor: Option<RecentResultCursor>,
+        page_size: usize,
+    ) -> (Vec<RecentResult>, Option<RecentResultCursor>) {
+        let start = match cursor {
+            Some(cursor) => self.results.partition_point(|result| result.get_cursor() >= cursor),
+            None => 0,
+        };
+
+        let page: Vec<_> = self.results.iter().skip(start).take(page_size).cloned().collect();
+
+        let next_cursor = if start + page.len() < self.results.len() {
+            page.last().map(|result| result.get_cursor())
+        } else {
+            None
+        };
+
+        (page, next_cursor)
+    }
+}
@@ -0,0 +1,101 @@
+use ark_db::RepoResult;
+use ark_utils::clocks::Clock;
+use async_trait::async_trait;
+
+use crate::{Game, GameActivity, GamePlay, GameStatus, GetGamesParams, ServiceChargeSchedule};
+use crate::{GetHouseRevenueReportsParams, GetPlayerActionsParams, GetPlayerGameActivitiesParams};
+use crate::{HouseRevenueReport, PendingReveal, PlayerAction, RevealReminder};
+use crate::{SubmittedChance, UnsavedGameActivity, UnsavedRevealReminder, UnsavedSubmittedChance};
+
+#[async_trait]
+pub trait GameRepo: Send + Sync {
+    async fn get_game(&self, id: i64, chain_id: i64) -> RepoResult<Option<Game>>;
+    async fn get_games(&self, params: &GetGamesParams, clock: &dyn Clock) -> RepoResult<Vec<Game>>;
+    async fn get_total_games_count(&self) -> RepoResult<u64>;
+    async fn get_total_completed_games_count(&self) -> RepoResult<u64>;
+    async fn get_service_charge_schedule(
+        &self,
+        chain_ids: &[i64],
+    ) -> RepoResult<ServiceChargeSchedule>;
+    /// Ordered by pending reveals, then games expiring soonest, then the latest credits
+    async fn get_player_actions(
+        &self,
+        params: &GetPlayerActionsParams,
+    ) -> RepoResult<Vec<PlayerAction>>;
+    /// Charged with the fee schedule effective at the block each completed game was created in
+    async fn get_house_revenue_reports(
+        &self,
+        params: &GetHouseRevenueReportsParams,
+    ) -> RepoResult<Vec<HouseRevenueReport>>;
+}
+
+#[async_trait]
+pub trait GamePlayRepo: Send + Sync {
+    async fn get_game_plays(&self, game_id: i64, chain_id: i64) -> RepoResult<Vec<GamePlay>>;
+    async fn get_game_play(
+        &self,
+        game_id: i64,
+        chain_id: i64,
+        player_address: &str,
+    ) -> RepoResult<Option<GamePlay>>;
+    /// Returns false when the nonce was already used by the player i.e. a replay.
+    /// `nonce` must be canonical e.g. `U256::to_string()`, so equal nonces collide.
+    async fn use_reveal_nonce(
+        &self,
+        chain_id: i64,
+        player_address: &str,
+        nonce: &str,
+        now: i64,
+    ) -> RepoResult<bool>;
+    /// Returns false when the game play already has a submitted chance
+    async fn create_submitted_chance(
+        &self,
+        submitted_chance: &UnsavedSubmittedChance,
+    ) -> RepoResult<bool>;
+    async fn get_submitted_chance(
+        &self,
+        game_play: &GamePlay,
+    ) -> RepoResult<Option<SubmittedChance>>;
+    async fn get_submitted_chances(
+        &self,
+        game_and_chain_ids: &[(i64, i64)],
+    ) -> RepoResult<Vec<SubmittedChance>>;
+}
+
+#[async_trait]
+pub trait ActivityRepo: Send + Sync {
+    async fn create_game_activity(&self, game_activity: &UnsavedGameActivity) -> RepoResult<()>;
+    async fn get_game_activities(
+        &self,
+        game_ids: &[i64],
+        chain_ids: &[i64],
+    ) -> RepoResult<Vec<GameActivity>>;
+    /// Activities in every game the player is part of, newest first.
+    /// `game_status` is matched like `Game::get_status_at(now)`.
+    async fn get_player_game_activities(
+        &self,
+        player_address: &str,
+        game_status: Option<GameStatus>,
+        now: i64,
+        params: &GetPlayerGameActivitiesParams,
+    ) -> RepoResult<Vec<GameActivity>>;
+}
+
+#[async_trait]
+pub trait RevealReminderRepo: Send + Sync {
+    /// Plays in unexpired games that filled up at or before `blocked_before` and still lack a chance
+    async fn get_pending_reveals(
+        &self,
+        now: i64,
+        blocked_before: i64,
+        player_address: Option<&str>,
+    ) -> RepoResult<Vec<PendingReveal>>;
+    /// Records newly detected reminders and refreshes already known ones
+    async fn create_or_update_reveal_reminders(
+        &self,
+        reveal_reminders: &[UnsavedRevealReminder],
+    ) -> RepoResult<()>;
+    /// Resolves reminders that were not detected again in the run at `now`
+    async fn resolve_reveal_reminders(&self, now: i64) -> RepoResult<usize>;
+    async fn get_unresolved_reveal_reminders(&self) -> RepoResult<Vec<RevealReminder>>;
+}
@@ -0,0 +1,82 @@
+use ark_db::schema::coinflip_reveal_reminders;
+use diesel::prelude::{Insertable, Queryable, QueryableByName};
+use diesel::sql_types::{BigInt, Integer, Text};
+
+use serde::{Deserialize, Serialize};
+
+/// A play in a filled game whose chance has neither been escrowed with Ark nor revealed on chain
+#[derive(Clone, Debug, Serialize, QueryableByName)]
+pub struct PendingReveal {
+    #[diesel(sql_type = BigInt)]
+    pub game_id: i64,
+    #[diesel(sql_type = BigInt)]
+    pub chain_id: i64,
+    #[diesel(sql_type = Integer)]
+    pub game_play_id: i32,
+    #[diesel(sql_type = Text)]
+    pub player_address: String,
+    /// When the game's last seat filled
+    #[diesel(sql_type = BigInt)]
+    pub blocked_since: i64,
+}
+
+impl PendingReveal {
+    pub fn get_blocked_for_secs(&self, now: i64) -> i64 {
+        (now - self.blocked_since).max(0)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Queryable)]
+#[diesel(table_name = coinflip_reveal_reminders)]
+pub struct RevealReminder {
+    pub id: i64,
+    pub game_id: i64,
+    pub chain_id: i64,
+    pub game_play_id: i32,
+    pub player_address: String,
+    pub blocked_since: i64,
+    pub first_detected_at: i64,
+    pub last_detected_at: i64,
+    pub resolved_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Insertable)]
+#[diesel(table_name = coinflip_reveal_reminders)]
+pub struct UnsavedRevealReminder {
+    pub game_id: i64,
+    pub chain_id: i64,
+    pub game_play_id: i32,
+    pub player_address: String,
+    pub blocked_since: i64,
+    pub first_detected_at: i64,
+    pub last_detected_at: i64,
+}
+
+impl UnsavedRevealReminder {
+    pub fn new(pending_reveal: &PendingReveal, now: i64) -> Self {
+        UnsavedRevealReminder {
+            game_id: pending_reveal.game_id,
+            chain_id: pending_reveal.chain_id,
+            game_play_id: pending_reveal.game_play_id,
+            player_address: pending_reveal.player_address.clone(),
+            blocked_since: pending_reveal.blocked_since,
+            first_detected_at: now,
+            last_detected_at: now,
+        }
+    }
+
+    /// For stores that assign ids themselves e.g. in-memory repos
+    pub fn to_saved(&self, id: i64) -> RevealReminder {
+        RevealReminder {
+            id,
+            game_id: self.game_id,
+            chain_id: self.chain_id,
+            game_play_id: self.game_play_id,
+            player_address: self.player_address.clone(),
+            blocked_since: self.blocked_since,
+            first_detected_at: self.first_detected_at,
+            last_detected_at: self.last_detected_at,
+            resolved_at: None,
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use ark_db::schema::coinflip_service_charges;
+use chrono::{Datelike, Duration, NaiveDate};
+use diesel::prelude::{Insertable, Queryable, QueryableByName};
+use diesel::sql_types::{BigInt, Double};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Game;
+
+/// Used for chains without any recorded service charge
+pub const DEFAULT_SERVICE_CHARGE_PERCENT: i32 = 8;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServiceChargeSource {
+    #[serde(rename = "configured")]
+    Configured,
+    #[serde(rename = "contract_view")]
+    ContractView,
+    #[serde(rename = "contract_event")]
+    ContractEvent,
+}
+
+impl Into<String> for ServiceChargeSource {
+    fn into(self) -> String {
+        match self {
+            ServiceChargeSource::Configured => "configured",
+            ServiceChargeSource::ContractView => "contract_view",
+            ServiceChargeSource::ContractEvent => "contract_event",
+        }
+        .to_string()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Queryable)]
+#[diesel(table_name = coinflip_service_charges)]
+pub struct ServiceCharge {
+    pub id: i64,
+    pub chain_id: i64,
+    pub percent: i32,
+    pub effective_from_block_number: i64,
+    pub source: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Insertable)]
+#[diesel(table_name = coinflip_service_charges)]
+pub struct UnsavedServiceCharge {
+    pub chain_id: i64,
+    pub percent: i32,
+    pub effective_from_block_number: i64,
+    pub source: String,
+}
+
+impl UnsavedServiceCharge {
+    pub fn new(
+        chain_id: i64,
+        percent: i32,
+        effective_from_block_number: i64,
+        source: ServiceChargeSource,
+    ) -> Self {
+        UnsavedServiceCharge {
+            chain_id,
+            percent,
+            effective_from_block_number,
+            source: source.into(),
+        }
+    }
+}
+
+/// Service charges per chain, each applying to games created
+/// from its `effective_from_block_number` onwards.
+#[derive(Clone, Debug, Default)]
+pub struct ServiceChargeSchedule {
+    service_charges: Vec<ServiceCharge>,
+}
+
+impl ServiceChargeSchedule {
+    pub fn new(mut service_charges: Vec<ServiceCharge>) -> Self {
+        service_charges
+            .sort_by(|a, b| b.effective_from_block_number.cmp(&a.effective_from_block_number));
+
+        Self { service_charges }
+    }
+
+    pub fn get_percent(&self, chain_id: i64, block_number: i64) -> i32 {
+        self.service_charges
+            .iter()
+            .find(|service_charge| {
+                service_charge.chain_id == chain_id
+                    && service_charge.effective_from_block_number <= block_number
+            })
+            .map(|service_charge| service_charge.percent)
+            .unwrap_or(DEFAULT_SERVICE_CHARGE_PERCENT)
+    }
+
+    pub fn get_percent_for_game(&self, game: &Game) -> i32 {
+        self.get_percent(game.chain_id, game.block_number)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum HouseRevenuePeriod {
+    #[default]
+    #[serde(rename = "day")]
+    Day,
+    #[serde(rename = "week")]
+    Week,
+    #[serde(rename = "month")]
+    Month,
+}
+
+impl<'a> Into<&'a str> for HouseRevenuePeriod {
+    fn into(self) -> &'a str {
+        match self {
+            HouseRevenuePeriod::Day => "day",
+            HouseRevenuePeriod::Week => "week",
+            HouseRevenuePeriod::Month => "month",
+        }
+    }
+}
+
+impl HouseRevenuePeriod {
+    /// Where the period `timestamp` falls in starts, in UTC like Postgres' `date_trunc`.
+    /// Weeks start on Mondays.
+    pub fn get_period_start(&self, timestamp: i64) -> i64 {
+        const SECS_PER_DAY: i64 = 24 * 60 * 60;
+
+        let days_since_epoch = timestamp.div_euclid(SECS_PER_DAY);
+
+        let period_start_days = match self {
+            HouseRevenuePeriod::Day => days_since_epoch,
+            // The epoch fell on a Thursday
+            HouseRevenuePeriod::Week => days_since_epoch - (days_since_epoch + 3).rem_euclid(7),
+            HouseRevenuePeriod::Month => {
+                let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+                let date = epoch + Duration::days(days_since_epoch);
+
+                (date.with_day(1).unwrap() - epoch).num_days()
+            }
+        };
+
+        period_start_days * SECS_PER_DAY
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct GetHouseRevenueReportsParams {
+    pub period: Option<HouseRevenuePeriod>,
+    pub chain_id: Option<i64>,
+    pub from: Option<i64>,
+    pub to: Option<i64>,
+}
+
+/// Service charges collected from completed games on a chain within a period
+#[derive(Clone, Debug, Serialize, QueryableByName)]
+pub struct HouseRevenueReport {
+    #[diesel(sql_type = BigInt)]
+    pub chain_id: i64,
+    #[diesel(sql_type = BigInt)]
+    pub period_start: i64,
+    #[diesel(sql_type = BigInt)]
+    pub games_count: i64,
+    /// In the chain's native unit e.g. ETH
+    #[diesel(sql_type = Double)]
+    pub fees_collected: f64,
+}
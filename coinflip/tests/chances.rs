@@ -0,0 +1,75 @@
+use coinflip::{ChanceCipher, GamePlay, UnsavedSubmittedChance};
+
+const KEY: [u8; 32] = [7; 32];
+const CHANCE_AND_SALT: &str = "0x6e5f2a1c9b8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e2f";
+const NOW: i64 = 1_700_000_000;
+
+fn new_game_play(id: i32) -> GamePlay {
+    GamePlay {
+        id,
+        game_id: 1,
+        chain_id: 11155111,
+        coin_side: 0,
+        player_address: "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".to_string(),
+        proof_of_chance: "0x".to_string(),
+        chance_and_salt: None,
+        status: "pending".to_string(),
+    }
+}
+
+#[test]
+fn decrypts_a_submitted_chance_with_the_same_key() {
+    let chance_cipher = ChanceCipher::new(&KEY);
+
+    let submitted_chance =
+        UnsavedSubmittedChance::new(&new_game_play(1), CHANCE_AND_SALT, &chance_cipher, NOW)
+            .to_saved(1);
+
+    assert_eq!(
+        submitted_chance.get_chance_and_salt(&chance_cipher),
+        Some(CHANCE_AND_SALT.to_string())
+    );
+}
+
+#[test]
+fn does_not_decrypt_with_another_key() {
+    let submitted_chance = UnsavedSubmittedChance::new(
+        &new_game_play(1),
+        CHANCE_AND_SALT,
+        &ChanceCipher::new(&KEY),
+        NOW,
+    )
+    .to_saved(1);
+
+    let other_chance_cipher = ChanceCipher::new(&[8; 32]);
+    assert_eq!(
+        submitted_chance.get_chance_and_salt(&other_chance_cipher),
+        None
+    );
+}
+
+#[test]
+fn does_not_decrypt_a_tampered_ciphertext() {
+    let chance_cipher = ChanceCipher::new(&KEY);
+    let encrypted_chance_and_salt = chance_cipher.encrypt(CHANCE_AND_SALT, b"1:1:1");
+
+    let (nonce, ciphertext) = encrypted_chance_and_salt.split_once(':').unwrap();
+    let mut ciphertext = hex::decode(ciphertext).unwrap();
+    ciphertext[0] ^= 1;
+    let tampered = format!("{nonce}:{}", hex::encode(ciphertext));
+
+    assert!(chance_cipher.decrypt(&encrypted_chance_and_salt, b"1:1:1").is_some());
+    assert_eq!(chance_cipher.decrypt(&tampered, b"1:1:1"), None);
+}
+
+#[test]
+fn does_not_decrypt_a_chance_moved_to_another_game_play() {
+    let chance_cipher = ChanceCipher::new(&KEY);
+
+    let mut submitted_chance =
+        UnsavedSubmittedChance::new(&new_game_play(1), CHANCE_AND_SALT, &chance_cipher, NOW)
+            .to_saved(1);
+    submitted_chance.game_play_id = 2;
+
+    assert_eq!(submitted_chance.get_chance_and_salt(&chance_cipher), None);
+}
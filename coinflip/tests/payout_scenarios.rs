@@ -0,0 +1,138 @@
+use coinflip::{CoinSide, Game, GameStatus, PayoutScenario};
+
+const ONE_ETHER: &str = "1000000000000000000";
+const NO_SERVICE_CHARGE: i32 = 0;
+
+fn new_game(number_of_players: i32, head_play_count: i32, tail_play_count: i32) -> Game {
+    let play_count = head_play_count + tail_play_count;
+    // Like the contract, the last seat can't go to the side every other player picked
+    let unavailable_coin_side = match (
+        number_of_players - play_count,
+        head_play_count,
+        tail_play_count,
+    ) {
+        (1, _, 0) => Some(CoinSide::Head.to_i32()),
+        (1, 0, _) => Some(CoinSide::Tail.to_i32()),
+        _ => None,
+    };
+
+    Game {
+        id: 1,
+        chain_id: 11155111,
+        number_of_players,
+        expiry_timestamp: 1_700_000_600,
+        creator_address: "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+        block_number: 1,
+        wager: ONE_ETHER.to_string(),
+        play_count,
+        unavailable_coin_side,
+        outcome: None,
+        amount_for_each_winner: None,
+        completed_at: None,
+        refunded_amount_per_player: None,
+        refunded_at: None,
+        chain_agnostic_index: 1,
+        created_at: 1_700_000_000,
+        status: if play_count < number_of_players {
+            GameStatus::AwaitingPlayers
+        } else {
+            GameStatus::AwaitingRevealedChances
+        },
+        head_play_count,
+        tail_play_count,
+        all_seats_filled_at: None,
+    }
+}
+
+fn new_payout_scenario(min_possible_win: f64, max_possible_win: f64) -> PayoutScenario {
+    PayoutScenario {
+        min_possible_win,
+        max_possible_win,
+    }
+}
+
+#[test]
+fn pays_a_lone_new_head_the_whole_pot_once_every_play_is_a_tail() {
+    let game = new_game(4, 0, 3);
+
+    assert_eq!(
+        game.get_payout_scenario(&CoinSide::Head, NO_SERVICE_CHARGE),
+        Some(new_payout_scenario(4.0, 4.0))
+    );
+    assert_eq!(
+        game.get_payout_scenario(&CoinSide::Tail, NO_SERVICE_CHARGE),
+        None
+    );
+    assert_eq!(game.get_max_possible_win(NO_SERVICE_CHARGE), 4.0);
+}
+
+#[test]
+fn keeps_the_last_seat_off_the_side_every_other_play_is_on() {
+    let game = new_game(4, 2, 0);
+
+    // The last player has to pick tails, so heads always share with the other two
+    assert_eq!(
+        game.get_payout_scenario(&CoinSide::Head, NO_SERVICE_CHARGE),
+        Some(new_payout_scenario(4.0 / 3.0, 4.0 / 3.0))
+    );
+    assert_eq!(
+        game.get_payout_scenario(&CoinSide::Tail, NO_SERVICE_CHARGE),
+        Some(new_payout_scenario(2.0, 4.0))
+    );
+    assert_eq!(game.get_max_possible_win(NO_SERVICE_CHARGE), 4.0);
+}
+
+#[test]
+fn ranges_payouts_by_how_the_remaining_players_split() {
+    let game = new_game(4, 1, 1);
+
+    assert_eq!(
+        game.get_payout_scenario(&CoinSide::Head, NO_SERVICE_CHARGE),
+        Some(new_payout_scenario(4.0 / 3.0, 2.0))
+    );
+    assert_eq!(
+        game.get_payout_scenario(&CoinSide::Tail, NO_SERVICE_CHARGE),
+        Some(new_payout_scenario(4.0 / 3.0, 2.0))
+    );
+    assert_eq!(game.get_max_possible_win(NO_SERVICE_CHARGE), 2.0);
+}
+
+#[test]
+fn fixes_the_payout_for_the_single_remaining_seat() {
+    let game = new_game(3, 1, 1);
+
+    assert_eq!(
+        game.get_payout_scenario(&CoinSide::Head, NO_SERVICE_CHARGE),
+        Some(new_payout_scenario(1.5, 1.5))
+    );
+    assert_eq!(
+        game.get_payout_scenario(&CoinSide::Tail, NO_SERVICE_CHARGE),
+        Some(new_payout_scenario(1.5, 1.5))
+    );
+}
+
+#[test]
+fn bounds_a_full_game_by_its_smaller_side() {
+    let game = new_game(3, 2, 1);
+
+    assert_eq!(
+        game.get_payout_scenario(&CoinSide::Head, NO_SERVICE_CHARGE),
+        None
+    );
+    assert_eq!(
+        game.get_payout_scenario(&CoinSide::Tail, NO_SERVICE_CHARGE),
+        None
+    );
+    assert_eq!(game.get_max_possible_win(NO_SERVICE_CHARGE), 3.0);
+}
+
+#[test]
+fn deducts_the_service_charge_before_sharing() {
+    let game = new_game(4, 1, 1);
+
+    assert_eq!(
+        game.get_payout_scenario(&CoinSide::Head, 10),
+        Some(new_payout_scenario(3.6 / 3.0, 1.8))
+    );
+    assert_eq!(game.get_max_possible_win(10), 1.8);
+}
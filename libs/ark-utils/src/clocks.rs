@@ -0,0 +1,42 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Source of the current time, in unix seconds.
+/// Injected wherever "now" matters so expiry behaviour can be tested and simulated.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> i64;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        chrono::offset::Utc::now().timestamp()
+    }
+}
+
+/// Only moves when told to
+#[derive(Debug, Default)]
+pub struct TestClock {
+    now: AtomicI64,
+}
+
+impl TestClock {
+    pub fn new(now: i64) -> Self {
+        Self {
+            now: AtomicI64::new(now),
+        }
+    }
+    pub fn set(&self, now: i64) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+    pub fn advance(&self, secs: i64) {
+        self.now.fetch_add(secs, Ordering::SeqCst);
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> i64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
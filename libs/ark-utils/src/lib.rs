@@ -1,3 +1,5 @@
+pub mod clocks;
 pub mod ethers;
 pub mod floats;
+pub mod panics;
 pub mod strings;
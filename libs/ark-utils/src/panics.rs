@@ -0,0 +1,12 @@
+use std::any::Any;
+
+/// The message a panic was raised with e.g. from `catch_unwind`
+pub fn get_panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
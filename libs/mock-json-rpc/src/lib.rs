@@ -0,0 +1,367 @@
+//! An in-process mock Ethereum JSON-RPC node.
+//!
+//! It answers the calls ethers makes when signing and sending contract transactions,
+//! records every `eth_sendRawTransaction` decoded against the given ABI, and serves
+//! an etherscan-like gas oracle for `chain_explorers`.
+
+mod transactions;
+
+pub use transactions::{DecodedCall, SentTransaction};
+
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::State;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use ethers::abi::Abi;
+use ethers::types::{H256, U256};
+use serde_json::{json, Value};
+
+const SERVER_ERROR: i64 = -32000;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+#[derive(Clone, Debug)]
+pub enum MockResponse {
+    Result(Value),
+    Error { code: i64, message: String },
+}
+
+impl MockResponse {
+    pub fn error(message: &str) -> Self {
+        MockResponse::Error {
+            code: SERVER_ERROR,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Etherscan's gas oracle prices are in gwei
+#[derive(Clone, Debug)]
+pub struct MockGasOracle {
+    pub last_block: u64,
+    pub safe_gas_price: f64,
+    pub fast_gas_price: f64,
+}
+
+struct MockState {
+    chain_id: u64,
+    abi: Abi,
+    block_number: u64,
+    transaction_count: u64,
+    gas_price: U256,
+    base_fee_per_gas: U256,
+    estimated_gas: U256,
+    fee_history: Option<Value>,
+    failed_receipts: bool,
+    gas_oracle: MockGasOracle,
+    scripted_responses: HashMap<String, VecDeque<MockResponse>>,
+    sent_transactions: Vec<SentTransaction>,
+    called_methods: Vec<String>,
+}
+
+#[derive(Clone)]
+pub struct MockJsonRpc {
+    address: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockJsonRpc {
+    /// Serves on a random local port until the runtime shuts down
+    pub async fn start(chain_id: u64, abi: Abi) -> Self {
+        let state = Arc::new(Mutex::new(MockState {
+            chain_id,
+            abi,
+            block_number: 1,
+            transaction_count: 0,
+            gas_price: U256::from(10_000_000_000_u64),
+            base_fee_per_gas: U256::from(1_000_000_000_u64),
+            estimated_gas: U256::from(200_000),
+            fee_history: None,
+            failed_receipts: false,
+            gas_oracle: MockGasOracle {
+                last_block: 1,
+                safe_gas_price: 20.0,
+                fast_gas_price: 30.0,
+            },
+            scripted_responses: HashMap::new(),
+            sent_transactions: vec![],
+            called_methods: vec![],
+        }));
+
+        let router = Router::new()
+            .route("/", post(handle_request))
+            .route("/gas_oracle", get(get_gas_oracle))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+
+        Self { address, state }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.address)
+    }
+    /// Responds like `?module=gastracker&action=gasoracle` whatever the query
+    pub fn gas_oracle_url(&self) -> String {
+        format!("http://{}/gas_oracle", self.address)
+    }
+
+    /// The pending nonce before any transaction is sent
+    pub fn set_transaction_count(&self, transaction_count: u64) {
+        self.state.lock().unwrap().transaction_count = transaction_count;
+    }
+    pub fn set_gas_price(&self, gas_price: U256) {
+        self.state.lock().unwrap().gas_price = gas_price;
+    }
+    pub fn set_base_fee_per_gas(&self, base_fee_per_gas: U256) {
+        self.state.lock().unwrap().base_fee_per_gas = base_fee_per_gas;
+    }
+    pub fn set_estimated_gas(&self, estimated_gas: U256) {
+        self.state.lock().unwrap().estimated_gas = estimated_gas;
+    }
+    /// Replaces the default `eth_feeHistory` result
+    pub fn set_fee_history(&self, fee_history: Value) {
+        self.state.lock().unwrap().fee_history = Some(fee_history);
+    }
+    /// Receipts of sent transactions report them as reverted
+    pub fn set_failed_receipts(&self, failed_receipts: bool) {
+        self.state.lock().unwrap().failed_receipts = failed_receipts;
+    }
+    pub fn set_gas_oracle(&self, gas_oracle: MockGasOracle) {
+        self.state.lock().unwrap().gas_oracle = gas_oracle;
+    }
+    pub fn mine_block(&self) {
+        self.state.lock().unwrap().block_number += 1;
+    }
+    /// Answers the next call to `method` with `response` instead of the default.
+    /// Responses for the same method are used in the order they were scripted.
+    pub fn respond_once(&self, method: &str, response: MockResponse) {
+        self.state
+            .lock()
+            .unwrap()
+            .scripted_responses
+            .entry(method.to_string())
+            .or_default()
+            .push_back(response);
+    }
+
+    pub fn get_sent_transactions(&self) -> Vec<SentTransaction> {
+        self.state.lock().unwrap().sent_transactions.clone()
+    }
+    /// Sent transactions that called `function_name`
+    pub fn get_calls(&self, function_name: &str) -> Vec<DecodedCall> {
+        self.get_sent_transactions()
+            .into_iter()
+            .filter_map(|sent_transaction| sent_transaction.call)
+            .filter(|call| call.function_name == function_name)
+            .collect()
+    }
+    pub fn get_called_methods(&self) -> Vec<String> {
+        self.state.lock().unwrap().called_methods.clone()
+    }
+}
+
+async fn handle_request(
+    State(state): State<Arc<Mutex<MockState>>>,
+    Json(request): Json<Value>,
+) -> Json<Value> {
+    let response = match request {
+        Value::Array(requests) => Value::Array(
+            requests
+                .iter()
+                .map(|request| respond(&mut state.lock().unwrap(), request))
+                .collect(),
+        ),
+        request => respond(&mut state.lock().unwrap(), &request),
+    };
+
+    Json(response)
+}
+
+fn respond(state: &mut MockState, request: &Value) -> Value {
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let method = request.get("method").and_then(Value::as_str).unwrap_or_default();
+    let params = request.get("params").and_then(Value::as_array).cloned().unwrap_or_default();
+
+    state.called_methods.push(method.to_string());
+
+    let response = state
+        .scripted_responses
+        .get_mut(method)
+        .and_then(|responses| responses.pop_front())
+        .unwrap_or_else(|| get_default_response(state, method, &params));
+
+    match response {
+        MockResponse::Result(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        MockResponse::Error { code, message } => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {"code": code, "message": message}
+        }),
+    }
+}
+
+fn get_default_response(state: &mut MockState, method: &str, params: &[Value]) -> MockResponse {
+    let result = match method {
+        "eth_chainId" => quantity(state.chain_id),
+        "net_version" => json!(state.chain_id.to_string()),
+        "eth_blockNumber" => quantity(state.block_number),
+        "eth_getTransactionCount" => {
+            quantity(state.transaction_count + state.sent_transactions.len() as u64)
+        }
+        "eth_gasPrice" => quantity(state.gas_price),
+        "eth_maxPriorityFeePerGas" => {
+            quantity(state.gas_price.saturating_sub(state.base_fee_per_gas))
+        }
+        "eth_estimateGas" => quantity(state.estimated_gas),
+        "eth_feeHistory" => state.fee_history.clone().unwrap_or_else(|| get_fee_history(state)),
+        "eth_getBlockByNumber" => get_block(state),
+        "eth_newBlockFilter" => quantity(1_u64),
+        "eth_getFilterChanges" => json!([]),
+        "eth_sendRawTransaction" => {
+            let Some(raw_transaction) = params.first().and_then(Value::as_str) else {
+                return invalid_params("Missing raw transaction");
+            };
+
+            match SentTransaction::decode(raw_transaction, &state.abi) {
+                Ok(sent_transaction) => {
+                    let hash = sent_transaction.hash;
+                    state.sent_transactions.push(sent_transaction);
+
+                    json!(hash)
+                }
+                Err(err) => return invalid_params(&err),
+            }
+        }
+        "eth_getTransactionReceipt" | "eth_getTransactionByHash" => {
+            let Some(hash) = params
+                .first()
+                .and_then(|hash| serde_json::from_value::<H256>(hash.clone()).ok())
+            else {
+                return invalid_params("Missing transaction hash");
+            };
+
+            match method {
+                "eth_getTransactionReceipt" => get_receipt(state, hash),
+                _ => get_transaction(state, hash),
+            }
+        }
+        _ => {
+            return MockResponse::Error {
+                code: METHOD_NOT_FOUND,
+                message: format!("Method {method} is not mocked"),
+            }
+        }
+    };
+
+    MockResponse::Result(result)
+}
+
+fn get_fee_history(state: &MockState) -> Value {
+    json!({
+        "oldestBlock": quantity(state.block_number),
+        "baseFeePerGas": [quantity(state.base_fee_per_gas), quantity(state.base_fee_per_gas)],
+        "gasUsedRatio": [0.5],
+        "reward": [[quantity(state.gas_price.saturating_sub(state.base_fee_per_gas))]],
+    })
+}
+
+fn get_block(state: &MockState) -> Value {
+    json!({
+        "number": quantity(state.block_number),
+        "hash": H256::from_low_u64_be(state.block_number),
+        "parentHash": H256::from_low_u64_be(state.block_number - 1),
+        "timestamp": quantity(state.block_number * 12),
+        "baseFeePerGas": quantity(state.base_fee_per_gas),
+        "gasLimit": quantity(30_000_000_u64),
+        "gasUsed": quantity(15_000_000_u64),
+        "transactions": [],
+        "uncles": [],
+    })
+}
+
+fn get_receipt(state: &MockState, hash: H256) -> Value {
+    let Some((index, sent_transaction)) = find_sent_transaction(state, hash) else {
+        return Value::Null;
+    };
+
+    json!({
+        "transactionHash": hash,
+        "transactionIndex": quantity(index as u64),
+        "blockHash": H256::from_low_u64_be(state.block_number),
+        "blockNumber": quantity(state.block_number),
+        "from": sent_transaction.from,
+        "to": sent_transaction.to,
+        "cumulativeGasUsed": quantity(state.estimated_gas),
+        "gasUsed": quantity(state.estimated_gas),
+        "effectiveGasPrice": quantity(sent_transaction.gas_price.unwrap_or(state.gas_price)),
+        "contractAddress": null,
+        "logs": [],
+        "logsBloom": format!("0x{}", "0".repeat(512)),
+        "status": quantity(if state.failed_receipts { 0_u64 } else { 1 }),
+    })
+}
+
+fn get_transaction(state: &MockState, hash: H256) -> Value {
+    let Some((index, sent_transaction)) = find_sent_transaction(state, hash) else {
+        return Value::Null;
+    };
+
+    json!({
+        "hash": hash,
+        "nonce": quantity(sent_transaction.nonce.unwrap_or_default()),
+        "blockHash": H256::from_low_u64_be(state.block_number),
+        "blockNumber": quantity(state.block_number),
+        "transactionIndex": quantity(index as u64),
+        "from": sent_transaction.from,
+        "to": sent_transaction.to,
+        "value": quantity(0_u64),
+        "gasPrice": quantity(sent_transaction.gas_price.unwrap_or(state.gas_price)),
+        "gas": quantity(sent_transaction.gas.unwrap_or(state.estimated_gas)),
+        "input": sent_transaction.data,
+        "v": quantity(0_u64),
+        "r": quantity(0_u64),
+        "s": quantity(0_u64),
+    })
+}
+
+fn find_sent_transaction(state: &MockState, hash: H256) -> Option<(usize, &SentTransaction)> {
+    state
+        .sent_transactions
+        .iter()
+        .enumerate()
+        .find(|(_index, sent_transaction)| sent_transaction.hash == hash)
+}
+
+async fn get_gas_oracle(State(state): State<Arc<Mutex<MockState>>>) -> Json<Value> {
+    let gas_oracle = state.lock().unwrap().gas_oracle.clone();
+
+    Json(json!({
+        "status": "1",
+        "message": "OK",
+        "result": {
+            "LastBlock": gas_oracle.last_block.to_string(),
+            "SafeGasPrice": gas_oracle.safe_gas_price.to_string(),
+            "ProposeGasPrice": gas_oracle.safe_gas_price.to_string(),
+            "FastGasPrice": gas_oracle.fast_gas_price.to_string(),
+        }
+    }))
+}
+
+fn invalid_params(message: &str) -> MockResponse {
+    MockResponse::Error {
+        code: INVALID_PARAMS,
+        message: message.to_string(),
+    }
+}
+
+/// Hex encoded like JSON-RPC quantities e.g. `0x1a`
+fn quantity(value: impl Into<U256>) -> Value {
+    json!(value.into())
+}
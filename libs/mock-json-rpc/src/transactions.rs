@@ -0,0 +1,73 @@
+use ethers::abi::{Abi, Token};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, Bytes, H256, U256};
+use ethers::utils::{hex, keccak256, rlp};
+
+/// A signed transaction received through `eth_sendRawTransaction`
+#[derive(Clone, Debug)]
+pub struct SentTransaction {
+    pub hash: H256,
+    /// Recovered from the signature
+    pub from: Address,
+    pub to: Option<Address>,
+    pub nonce: Option<U256>,
+    /// `maxFeePerGas` for EIP-1559 transactions
+    pub gas_price: Option<U256>,
+    pub gas: Option<U256>,
+    pub chain_id: Option<u64>,
+    pub data: Bytes,
+    /// None when the calldata doesn't match a function in the ABI
+    pub call: Option<DecodedCall>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedCall {
+    pub function_name: String,
+    pub params: Vec<Token>,
+}
+
+impl SentTransaction {
+    pub fn decode(raw_transaction: &str, abi: &Abi) -> Result<Self, String> {
+        let bytes = hex::decode(raw_transaction.trim_start_matches("0x"))
+            .map_err(|err| format!("Invalid hex: {err}"))?;
+
+        let (transaction, signature) = TypedTransaction::decode_signed(&rlp::Rlp::new(&bytes))
+            .map_err(|err| format!("Invalid transaction: {err}"))?;
+        let from = signature
+            .recover(transaction.sighash())
+            .map_err(|err| format!("Invalid signature: {err}"))?;
+        let data = transaction.data().cloned().unwrap_or_default();
+
+        Ok(SentTransaction {
+            hash: H256::from(keccak256(&bytes)),
+            from,
+            to: transaction.to_addr().cloned(),
+            nonce: transaction.nonce().cloned(),
+            gas_price: transaction.gas_price(),
+            gas: transaction.gas().cloned(),
+            chain_id: transaction.chain_id().map(|chain_id| chain_id.as_u64()),
+            call: decode_call(abi, &data),
+            data,
+        })
+    }
+
+    pub fn get_function_name(&self) -> Option<&str> {
+        self.call.as_ref().map(|call| call.function_name.as_str())
+    }
+}
+
+fn decode_call(abi: &Abi, data: &Bytes) -> Option<DecodedCall> {
+    if data.len() < 4 {
+        return None;
+    }
+    let (selector, params) = data.split_at(4);
+
+    abi.functions()
+        .find(|function| function.short_signature() == selector)
+        .and_then(|function| {
+            function.decode_input(params).ok().map(|params| DecodedCall {
+                function_name: function.name.clone(),
+                params,
+            })
+        })
+}